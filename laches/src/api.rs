@@ -0,0 +1,554 @@
+//! Local HTTP/JSON API served by the daemon.
+//!
+//! A deliberately small HTTP/1.1 server: `GET` only, one request per
+//! connection, bound to the loopback interface. Every request must carry
+//! the token from `config.toml`, either as `Authorization: Bearer <token>`
+//! or as a `token` query parameter.
+//!
+//! Endpoints, all returning JSON:
+//!
+//! - `/v1/status`: daemon version, the focused session and today's total
+//! - `/v1/current`: the open session, or `null`
+//! - `/v1/today`: today's total and per-process summaries
+//! - `/v1/summaries`: per-process summaries for a range, optionally `tag`
+//! - `/v1/sessions`: sessions overlapping a range
+//! - `/v1/daily`: active seconds per local day for a range
+//!
+//! Ranges are `date=YYYY-MM-DD`, `from=YYYY-MM-DD&to=YYYY-MM-DD` or
+//! `days=N` (the last N days); the default is today.
+
+use crate::config::Rule;
+use crate::db::{
+    date_range_for_day, format_timestamp, last_n_days_range, today_range, Database, Session,
+};
+use crate::utils::session_duration_secs;
+use chrono::{Local, Utc};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// Upper bound on the request line plus headers.
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// How long a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often the accept loop checks whether it should stop.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// A parsed request: method, path, query parameters and bearer token.
+#[derive(Debug, Default)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    pub bearer: Option<String>,
+}
+
+/// A response ready to be written.
+#[derive(Debug)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Response { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Response {
+            status,
+            body: json!({ "error": message }),
+        }
+    }
+}
+
+/// Bind the API listener on the loopback interface.
+pub fn bind(port: u16) -> std::io::Result<TcpListener> {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+}
+
+/// Serve requests until `running` is cleared. Connections are handled one
+/// at a time; clients are expected to be status bars and scripts. `rules`
+/// is checked before each request, so rules the daemon reloads apply to
+/// tag and category queries here too.
+pub fn serve(
+    listener: TcpListener,
+    db: &Database,
+    token: &str,
+    rules: &RwLock<Vec<Rule>>,
+    running: &AtomicBool,
+) {
+    if listener.set_nonblocking(true).is_err() {
+        return;
+    }
+    let mut applied: Option<Vec<Rule>> = None;
+    while running.load(Ordering::SeqCst) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                apply_rules(db, rules, &mut applied);
+                let _ = handle_connection(stream, db, token);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(POLL_INTERVAL);
+            }
+            Err(_) => std::thread::sleep(POLL_INTERVAL),
+        }
+    }
+}
+
+/// Hand `rules` to the database if they changed since they were last
+/// `applied`. A failure is retried on the next request.
+fn apply_rules(db: &Database, rules: &RwLock<Vec<Rule>>, applied: &mut Option<Vec<Rule>>) {
+    let Ok(rules) = rules.read() else {
+        return;
+    };
+    if applied.as_ref() == Some(&*rules) {
+        return;
+    }
+    if db.set_rules(&rules).is_ok() {
+        *applied = Some(rules.clone());
+    }
+}
+
+fn handle_connection(stream: TcpStream, db: &Database, token: &str) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+
+    let response = match read_request(&mut BufReader::new(stream)) {
+        Ok(request) => handle(db, token, &request),
+        Err(message) => Response::error(400, &message),
+    };
+    write_response(&mut writer, &response)
+}
+
+/// Read the request line and headers. The body, if any, is ignored.
+fn read_request(reader: &mut impl BufRead) -> Result<Request, String> {
+    let mut budget = MAX_REQUEST_BYTES;
+
+    let request_line = read_line(reader, &mut budget)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err("malformed request line".to_string());
+    };
+
+    let mut request = Request {
+        method: method.to_string(),
+        ..Default::default()
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    request.path = percent_decode(path);
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+        request.query.insert(percent_decode(k), percent_decode(v));
+    }
+
+    loop {
+        let line = read_line(reader, &mut budget)?;
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("authorization") {
+                if let Some(token) = value.trim().strip_prefix("Bearer ") {
+                    request.bearer = Some(token.trim().to_string());
+                }
+            }
+        }
+    }
+    Ok(request)
+}
+
+/// Read one line, within what is left of the request size limit.
+fn read_line<R: BufRead>(reader: &mut R, budget: &mut usize) -> Result<String, String> {
+    let mut line = String::new();
+    let n = std::io::Read::take(&mut *reader, *budget as u64)
+        .read_line(&mut line)
+        .map_err(|e| e.to_string())?;
+    *budget -= n;
+    if !line.ends_with('\n') {
+        return Err("request too large or incomplete".to_string());
+    }
+    Ok(line.trim_end().to_string())
+}
+
+fn write_response(out: &mut impl Write, response: &Response) -> std::io::Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    };
+    let body = response.body.to_string();
+    write!(
+        out,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason,
+        body.len(),
+        body
+    )?;
+    out.flush()
+}
+
+/// Route an authenticated request to its endpoint.
+pub fn handle(db: &Database, token: &str, request: &Request) -> Response {
+    let supplied = request
+        .bearer
+        .as_deref()
+        .or(request.query.get("token").map(String::as_str));
+    if !supplied.is_some_and(|t| tokens_match(t, token)) {
+        return Response::error(401, "missing or invalid token");
+    }
+    if request.method != "GET" {
+        return Response::error(405, "only GET is supported");
+    }
+
+    let result = match request.path.trim_end_matches('/') {
+        "/v1/status" => status(db),
+        "/v1/current" => current(db),
+        "/v1/today" => today(db),
+        "/v1/summaries" => summaries(db, &request.query),
+        "/v1/sessions" => sessions(db, &request.query),
+        "/v1/daily" => daily(db, &request.query),
+        _ => return Response::error(404, "no such endpoint"),
+    };
+    result.unwrap_or_else(|e| e)
+}
+
+type Handled = Result<Response, Response>;
+
+fn db_error(e: rusqlite::Error) -> Response {
+    Response::error(500, &format!("database error: {}", e))
+}
+
+fn status(db: &Database) -> Handled {
+    let (start, end) = today_range();
+    Ok(Response::ok(json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "current": open_session(db)?,
+        "today_seconds": db.query_total_active_seconds(&start, &end).map_err(db_error)?,
+    })))
+}
+
+fn current(db: &Database) -> Handled {
+    Ok(Response::ok(open_session(db)?))
+}
+
+fn today(db: &Database) -> Handled {
+    let (start, end) = today_range();
+    Ok(Response::ok(json!({
+        "date": Local::now().date_naive().format("%Y-%m-%d").to_string(),
+        "total_seconds": db.query_total_active_seconds(&start, &end).map_err(db_error)?,
+        "processes": process_summaries(db, &start, &end, None)?,
+    })))
+}
+
+fn summaries(db: &Database, query: &HashMap<String, String>) -> Handled {
+    let (start, end) = range(query)?;
+    let tag = query.get("tag").map(String::as_str);
+    Ok(Response::ok(json!({
+        "start": start,
+        "end": end,
+        "processes": process_summaries(db, &start, &end, tag)?,
+    })))
+}
+
+fn sessions(db: &Database, query: &HashMap<String, String>) -> Handled {
+    let (start, end) = range(query)?;
+    let sessions: Vec<Value> = db
+        .query_sessions(&start, &end)
+        .map_err(db_error)?
+        .iter()
+        .map(session_json)
+        .collect();
+    Ok(Response::ok(json!({
+        "start": start,
+        "end": end,
+        "sessions": sessions,
+    })))
+}
+
+fn daily(db: &Database, query: &HashMap<String, String>) -> Handled {
+    let (start, end) = range(query)?;
+    let days: Vec<Value> = db
+        .query_daily_totals(&start, &end)
+        .map_err(db_error)?
+        .into_iter()
+        .map(|(date, total_seconds)| json!({ "date": date, "total_seconds": total_seconds }))
+        .collect();
+    Ok(Response::ok(json!({
+        "start": start,
+        "end": end,
+        "days": days,
+    })))
+}
+
+fn process_summaries(
+    db: &Database,
+    start: &str,
+    end: &str,
+    tag: Option<&str>,
+) -> Result<Vec<Value>, Response> {
+    Ok(db
+        .query_process_summaries(start, end, tag)
+        .map_err(db_error)?
+        .into_iter()
+        .map(|p| {
+            json!({
+                "process": p.process_name,
+                "total_seconds": p.total_seconds,
+                "session_count": p.session_count,
+                "active_days": p.active_days,
+            })
+        })
+        .collect())
+}
+
+fn open_session(db: &Database) -> Result<Value, Response> {
+    Ok(db
+        .get_open_session()
+        .map_err(db_error)?
+        .map_or(Value::Null, |s| session_json(&s)))
+}
+
+fn session_json(s: &Session) -> Value {
+    let end = s
+        .end_time
+        .clone()
+        .unwrap_or_else(|| format_timestamp(&Utc::now()));
+    json!({
+        "id": s.id,
+        "process": s.process_name,
+        "exe_path": s.exe_path,
+        "window_title": s.window_title,
+        "start_time": s.start_time,
+        "end_time": s.end_time,
+        "duration_seconds": session_duration_secs(&s.start_time, &end).unwrap_or(0),
+        "kind": s.kind.as_str(),
+    })
+}
+
+/// Resolve the range query parameters to UTC bounds.
+fn range(query: &HashMap<String, String>) -> Result<(String, String), Response> {
+    let bad = |message: &str| Response::error(400, message);
+    if let Some(date) = query.get("date") {
+        return date_range_for_day(date).ok_or_else(|| bad("invalid date, use YYYY-MM-DD"));
+    }
+    match (query.get("from"), query.get("to")) {
+        (Some(from), Some(to)) => {
+            let (start, _) = date_range_for_day(from).ok_or_else(|| bad("invalid from date"))?;
+            let (_, end) = date_range_for_day(to).ok_or_else(|| bad("invalid to date"))?;
+            return Ok((start, end));
+        }
+        (None, None) => {}
+        _ => return Err(bad("from and to must be given together")),
+    }
+    if let Some(days) = query.get("days") {
+        return match days.parse::<i64>() {
+            Ok(n) if (1..=36500).contains(&n) => Ok(last_n_days_range(n)),
+            _ => Err(bad("days must be a positive number")),
+        };
+    }
+    Ok(today_range())
+}
+
+/// Compare tokens without returning early on the first difference.
+fn tokens_match(supplied: &str, expected: &str) -> bool {
+    !expected.is_empty()
+        && supplied.len() == expected.len()
+        && supplied
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// Decode `%XX` escapes and `+` in a URL component.
+fn percent_decode(s: &str) -> String {
+    let hex = |b: u8| (b as char).to_digit(16).map(|d| d as u8);
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => match (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                (Some(hi), Some(lo)) => {
+                    out.push(hi << 4 | lo);
+                    i += 2;
+                }
+                _ => out.push(b'%'),
+            },
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::SessionKind;
+    use std::io::Read;
+    use std::sync::Arc;
+
+    const TOKEN: &str = "secret";
+
+    fn get(path: &str) -> Request {
+        let raw = format!(
+            "GET {} HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer {}\r\n\r\n",
+            path, TOKEN
+        );
+        read_request(&mut raw.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_read_request() {
+        let raw = "GET /v1/summaries?tag=deep%20work&from=2026-04-01&to=2026-04-02 HTTP/1.1\r\n\
+                   authorization: Bearer abc\r\n\r\n";
+        let request = read_request(&mut raw.as_bytes()).unwrap();
+        assert_eq!(request.path, "/v1/summaries");
+        assert_eq!(request.query["tag"], "deep work");
+        assert_eq!(request.bearer.as_deref(), Some("abc"));
+
+        assert!(read_request(&mut "GET / HTTP/1.1\r\n".as_bytes()).is_err());
+        let huge = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_REQUEST_BYTES));
+        assert!(read_request(&mut huge.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_token_is_required() {
+        let db = Database::open_memory().unwrap();
+        let mut request = get("/v1/status");
+        assert_eq!(handle(&db, TOKEN, &request).status, 200);
+
+        request.bearer = Some("wrong!".to_string());
+        assert_eq!(handle(&db, TOKEN, &request).status, 401);
+
+        request.bearer = None;
+        assert_eq!(handle(&db, TOKEN, &request).status, 401);
+        request.query.insert("token".to_string(), TOKEN.to_string());
+        assert_eq!(handle(&db, TOKEN, &request).status, 200);
+
+        // an empty configured token never matches
+        request.query.insert("token".to_string(), String::new());
+        assert_eq!(handle(&db, "", &request).status, 401);
+    }
+
+    #[test]
+    fn test_endpoints() {
+        let db = Database::open_memory().unwrap();
+        db.insert_session(
+            "code",
+            SessionKind::Active,
+            "2026-04-01T10:00:00.000Z",
+            "2026-04-01T11:00:00.000Z",
+        )
+        .unwrap();
+        db.start_session("firefox", None, Some("docs"), SessionKind::Active)
+            .unwrap();
+
+        let current = handle(&db, TOKEN, &get("/v1/current"));
+        assert_eq!(current.body["process"], "firefox");
+        assert_eq!(current.body["window_title"], "docs");
+
+        let range = "from=2026-03-31&to=2026-04-02";
+        let summaries = handle(&db, TOKEN, &get(&format!("/v1/summaries?{}", range)));
+        assert_eq!(summaries.body["processes"][0]["process"], "code");
+        assert_eq!(summaries.body["processes"][0]["total_seconds"], 3600);
+
+        let sessions = handle(&db, TOKEN, &get(&format!("/v1/sessions?{}", range)));
+        assert_eq!(sessions.body["sessions"].as_array().unwrap().len(), 1);
+
+        let daily = handle(&db, TOKEN, &get(&format!("/v1/daily?{}", range)));
+        let total: i64 = daily.body["days"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|d| d["total_seconds"].as_i64().unwrap())
+            .sum();
+        assert_eq!(total, 3600);
+
+        assert_eq!(handle(&db, TOKEN, &get("/v1/today")).status, 200);
+        assert_eq!(handle(&db, TOKEN, &get("/v1/daily?days=x")).status, 400);
+        assert_eq!(
+            handle(&db, TOKEN, &get("/v1/daily?from=2026-04-01")).status,
+            400
+        );
+        assert_eq!(handle(&db, TOKEN, &get("/v2/nope")).status, 404);
+    }
+
+    #[test]
+    fn test_changed_rules_are_applied() {
+        use crate::config::{MatchKind, RuleAction, RuleField};
+
+        let db = Database::open_memory().unwrap();
+        db.insert_session(
+            "code",
+            SessionKind::Active,
+            "2026-04-01T10:00:00.000Z",
+            "2026-04-01T11:00:00.000Z",
+        )
+        .unwrap();
+        let request = get("/v1/summaries?tag=work&from=2026-03-31&to=2026-04-02");
+        let tagged = |db: &Database| {
+            handle(db, TOKEN, &request).body["processes"]
+                .as_array()
+                .unwrap()
+                .len()
+        };
+
+        let rules = RwLock::new(Vec::new());
+        let mut applied = None;
+        apply_rules(&db, &rules, &mut applied);
+        assert_eq!(tagged(&db), 0);
+
+        rules.write().unwrap().push(Rule {
+            field: RuleField::Process,
+            kind: MatchKind::Exact,
+            pattern: "code".to_string(),
+            action: RuleAction::Tag,
+            tag: Some("work".to_string()),
+            category: None,
+            process: None,
+        });
+        apply_rules(&db, &rules, &mut applied);
+        assert_eq!(tagged(&db), 1);
+        assert_eq!(applied.as_deref(), Some(&rules.read().unwrap()[..]));
+    }
+
+    #[test]
+    fn test_serve_over_loopback() {
+        let listener = bind(0).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let running = Arc::new(AtomicBool::new(true));
+        let server = {
+            let running = Arc::clone(&running);
+            std::thread::spawn(move || {
+                let db = Database::open_memory().unwrap();
+                serve(listener, &db, TOKEN, &RwLock::new(Vec::new()), &running);
+            })
+        };
+
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+        write!(stream, "GET /v1/status?token={} HTTP/1.1\r\n\r\n", TOKEN).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("\"current\":null"));
+
+        running.store(false, Ordering::SeqCst);
+        server.join().unwrap();
+    }
+}
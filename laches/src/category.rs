@@ -0,0 +1,183 @@
+//! Hierarchical categories.
+//!
+//! A category is a `/`-separated path such as `work/clientA/dev`. Every
+//! session belongs to exactly one category (its process's primary
+//! category, or one derived from a rule), so totals roll up the tree
+//! without counting any time twice.
+
+use crate::db::CategoryTotal;
+
+/// Category of sessions whose process has none assigned.
+pub const UNCATEGORIZED: &str = "uncategorized";
+
+/// Normalize a category path: trim every segment and drop empty ones.
+/// Returns None if nothing is left.
+pub fn normalize(path: &str) -> Option<String> {
+    let segments: Vec<&str> = path
+        .split('/')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+    if segments.is_empty() {
+        None
+    } else {
+        Some(segments.join("/"))
+    }
+}
+
+/// One level of the category tree with its rolled-up total.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CategoryNode {
+    /// Last path segment, e.g. `dev`.
+    pub name: String,
+    /// Full path, e.g. `work/clientA/dev`.
+    pub path: String,
+    /// Time in this category and all of its subcategories.
+    pub total_seconds: i64,
+    /// Subcategories, largest first.
+    pub children: Vec<CategoryNode>,
+    /// Processes filed directly under this category, largest first.
+    pub processes: Vec<(String, i64)>,
+}
+
+impl CategoryNode {
+    fn new(name: &str, path: String) -> Self {
+        CategoryNode {
+            name: name.to_string(),
+            path,
+            total_seconds: 0,
+            children: Vec::new(),
+            processes: Vec::new(),
+        }
+    }
+
+    fn sort(&mut self) {
+        self.children.sort_by(|a, b| {
+            b.total_seconds
+                .cmp(&a.total_seconds)
+                .then(a.name.cmp(&b.name))
+        });
+        self.processes
+            .sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        for child in &mut self.children {
+            child.sort();
+        }
+    }
+}
+
+/// Build the category tree from per-(category, process) totals. Returns
+/// the top-level categories, largest first.
+pub fn build_tree(totals: &[CategoryTotal]) -> Vec<CategoryNode> {
+    let mut root = CategoryNode::new("", String::new());
+
+    for t in totals {
+        let path = normalize(&t.category).unwrap_or_else(|| UNCATEGORIZED.to_string());
+        let mut node = &mut root;
+        node.total_seconds += t.total_seconds;
+        for segment in path.split('/') {
+            let child_path = if node.path.is_empty() {
+                segment.to_string()
+            } else {
+                format!("{}/{}", node.path, segment)
+            };
+            let idx = match node.children.iter().position(|c| c.name == segment) {
+                Some(idx) => idx,
+                None => {
+                    node.children.push(CategoryNode::new(segment, child_path));
+                    node.children.len() - 1
+                }
+            };
+            node = &mut node.children[idx];
+            node.total_seconds += t.total_seconds;
+        }
+        match node
+            .processes
+            .iter_mut()
+            .find(|(p, _)| *p == t.process_name)
+        {
+            Some((_, secs)) => *secs += t.total_seconds,
+            None => node
+                .processes
+                .push((t.process_name.clone(), t.total_seconds)),
+        }
+    }
+
+    root.sort();
+    root.children
+}
+
+/// Flatten a tree depth-first into (depth, node) pairs for display.
+pub fn flatten(nodes: &[CategoryNode]) -> Vec<(usize, &CategoryNode)> {
+    fn walk<'a>(nodes: &'a [CategoryNode], depth: usize, out: &mut Vec<(usize, &'a CategoryNode)>) {
+        for node in nodes {
+            out.push((depth, node));
+            walk(&node.children, depth + 1, out);
+        }
+    }
+    let mut out = Vec::new();
+    walk(nodes, 0, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(category: &str, process_name: &str, total_seconds: i64) -> CategoryTotal {
+        CategoryTotal {
+            category: category.to_string(),
+            process_name: process_name.to_string(),
+            total_seconds,
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalize(" work / clientA//dev/").as_deref(),
+            Some("work/clientA/dev")
+        );
+        assert_eq!(normalize("/ /"), None);
+    }
+
+    #[test]
+    fn test_tree_rolls_up_without_double_counting() {
+        let tree = build_tree(&[
+            total("work/clientA/dev", "code", 3600),
+            total("work/clientA", "firefox", 600),
+            total("work/clientB", "code", 1200),
+            total("personal", "firefox", 900),
+        ]);
+
+        assert_eq!(tree.len(), 2);
+        let work = &tree[0];
+        assert_eq!(work.path, "work");
+        assert_eq!(work.total_seconds, 5400);
+        assert_eq!(work.children[0].path, "work/clientA");
+        assert_eq!(work.children[0].total_seconds, 4200);
+        assert_eq!(
+            work.children[0].processes,
+            vec![("firefox".to_string(), 600)]
+        );
+        assert_eq!(work.children[0].children[0].total_seconds, 3600);
+        assert_eq!(tree[1].total_seconds, 900);
+
+        let grand_total: i64 = tree.iter().map(|n| n.total_seconds).sum();
+        assert_eq!(grand_total, 3600 + 600 + 1200 + 900);
+
+        let flat: Vec<(usize, &str)> = flatten(&tree)
+            .into_iter()
+            .map(|(d, n)| (d, n.path.as_str()))
+            .collect();
+        assert_eq!(
+            flat,
+            vec![
+                (0, "work"),
+                (1, "work/clientA"),
+                (2, "work/clientA/dev"),
+                (1, "work/clientB"),
+                (0, "personal"),
+            ]
+        );
+    }
+}
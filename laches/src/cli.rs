@@ -1,4 +1,4 @@
-use crate::config::FilterMode;
+use crate::config::{FilterMode, GoalDays, MatchKind, RuleAction, RuleField};
 use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
@@ -11,6 +11,10 @@ use clap::{Parser, Subcommand, ValueEnum};
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// time zone for day boundaries and displayed times (utc, +05:30, Europe/Berlin)
+    #[arg(long, global = true, value_name = "ZONE")]
+    pub tz: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -21,6 +25,44 @@ pub enum Commands {
     /// stop the background monitoring daemon
     Stop,
 
+    /// show what the daemon is currently tracking
+    Status,
+
+    /// stop recording for a while, or until `laches resume`
+    Pause {
+        /// how long to pause (e.g. 30m, 2h, 1h30m)
+        duration: Option<String>,
+
+        /// pause until this time of day (HH:MM)
+        #[arg(long, conflicts_with = "duration")]
+        until: Option<String>,
+    },
+
+    /// resume recording after `laches pause`
+    Resume,
+
+    /// make the daemon re-read its configuration
+    Reload,
+
+    /// start a timed focus block, or show the running one
+    Focus {
+        /// length of the block (e.g. 25m, 50m, 1h30m)
+        #[arg(requires = "allow")]
+        duration: Option<String>,
+
+        /// processes that count as focused, comma separated
+        #[arg(short, long, value_delimiter = ',', requires = "duration")]
+        allow: Vec<String>,
+
+        /// break to take after the block (e.g. 10m)
+        #[arg(short, long = "break", value_name = "DURATION", requires = "duration")]
+        break_length: Option<String>,
+
+        /// end the running block early
+        #[arg(long, conflicts_with_all = ["duration", "allow", "break_length"])]
+        stop: bool,
+    },
+
     /// list tracked process usage
     List {
         /// filter by tag name
@@ -58,11 +100,163 @@ pub enum Commands {
         /// include data from all synced machines
         #[arg(short = 'a', long)]
         all_machines: bool,
+
+        /// break down each process's time per machine (requires --all-machines)
+        #[arg(long, requires = "all_machines")]
+        by_machine: bool,
+
+        /// show time rolled up by category
+        #[arg(short = 'c', long, conflicts_with_all = ["sessions", "tag"])]
+        by_category: bool,
+
+        /// show active time as an hour-of-day by weekday heatmap
+        #[arg(long, conflicts_with_all = ["sessions", "tag", "by_category", "by_machine"])]
+        heatmap: bool,
     },
 
     /// quick daily overview with comparisons
     Summary,
 
+    /// break a process's time down by window title, domain or file
+    Titles {
+        /// process to break down
+        process: String,
+
+        /// show only today's usage
+        #[arg(long)]
+        today: bool,
+
+        /// show last 7 days
+        #[arg(short, long)]
+        week: bool,
+
+        /// show last 30 days
+        #[arg(short, long)]
+        month: bool,
+
+        /// show usage for a specific date (YYYY-MM-DD)
+        #[arg(short, long)]
+        date: Option<String>,
+
+        /// date range (YYYY-MM-DD..YYYY-MM-DD)
+        #[arg(long)]
+        range: Option<String>,
+
+        /// group by the raw window titles instead of normalized ones
+        #[arg(long)]
+        raw: bool,
+
+        /// list the raw titles under each group
+        #[arg(short, long, conflicts_with = "raw")]
+        verbose: bool,
+
+        /// number of groups to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+
+        /// include data from all synced machines
+        #[arg(short = 'a', long)]
+        all_machines: bool,
+    },
+
+    /// write a report of a week, month or date range as markdown or html
+    Report {
+        /// report on the last 7 days (the default)
+        #[arg(short, long)]
+        week: bool,
+
+        /// report on the last 30 days
+        #[arg(short, long, conflicts_with = "week")]
+        month: bool,
+
+        /// date range (YYYY-MM-DD..YYYY-MM-DD)
+        #[arg(long, conflicts_with_all = ["week", "month"])]
+        range: Option<String>,
+
+        /// output format (default: from the file extension, else markdown)
+        #[arg(short, long, value_enum)]
+        format: Option<ReportFormat>,
+
+        /// file to write the report to (default: standard output)
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// include data from all synced machines
+        #[arg(short = 'a', long)]
+        all_machines: bool,
+    },
+
+    /// record a session by hand, e.g. a meeting away from the computer
+    Add {
+        /// name to record the time under
+        process: String,
+
+        /// start time (HH:MM today, or YYYY-MM-DD HH:MM)
+        #[arg(long)]
+        from: String,
+
+        /// end time (HH:MM today, or YYYY-MM-DD HH:MM)
+        #[arg(long)]
+        to: String,
+
+        /// window title to store with the session
+        #[arg(long)]
+        title: Option<String>,
+
+        /// tag(s) to add to the process (comma-separated)
+        #[arg(long)]
+        tag: Option<String>,
+    },
+
+    /// change a recorded session (ids are shown by `list --sessions`)
+    Edit {
+        /// id of the session
+        id: i64,
+
+        /// attribute the session to another process
+        #[arg(long)]
+        process: Option<String>,
+
+        /// set the window title (empty to clear)
+        #[arg(long)]
+        title: Option<String>,
+
+        /// new start time (HH:MM on the session's day, or YYYY-MM-DD HH:MM)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// new end time (HH:MM on the session's day, or YYYY-MM-DD HH:MM)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// mark the session as idle time
+        #[arg(long, conflicts_with_all = ["process", "title", "active"])]
+        idle: bool,
+
+        /// mark an idle session as active (requires --process)
+        #[arg(long)]
+        active: bool,
+    },
+
+    /// split a session in two at a given time
+    Split {
+        /// id of the session
+        id: i64,
+
+        /// where to split (HH:MM on the session's day, or YYYY-MM-DD HH:MM)
+        #[arg(long)]
+        at: String,
+    },
+
+    /// merge two sessions into one spanning both
+    Merge {
+        /// id of the first session
+        first: i64,
+
+        /// id of the second session
+        second: i64,
+    },
+
     /// add, remove, or list tags on a tracked process
     Tag {
         /// name of the process to tag
@@ -81,6 +275,20 @@ pub enum Commands {
         list: bool,
     },
 
+    /// set, clear, or show the category of a process (e.g. work/clientA/dev)
+    Category {
+        /// name of the process; lists every assignment when omitted
+        process: Option<String>,
+
+        /// category path to assign
+        #[arg(short, long, requires = "process")]
+        set: Option<String>,
+
+        /// remove the assigned category
+        #[arg(short, long, requires = "process", conflicts_with = "set")]
+        clear: bool,
+    },
+
     /// manage whitelist patterns (only track matched processes)
     Whitelist {
         #[command(subcommand)]
@@ -93,6 +301,32 @@ pub enum Commands {
         action: FilterListAction,
     },
 
+    /// manage rules matching window titles and executable paths
+    Rule {
+        #[command(subcommand)]
+        action: RuleCommand,
+    },
+
+    /// manage daily and weekly usage limits
+    Limit {
+        #[command(subcommand)]
+        action: LimitCommand,
+    },
+
+    /// show goal progress and streaks, or manage goals
+    Goals {
+        /// days of history to show
+        #[arg(long, default_value_t = 14)]
+        days: u32,
+
+        /// evaluate recorded days again, e.g. after editing sessions or tags
+        #[arg(long)]
+        recompute: bool,
+
+        #[command(subcommand)]
+        action: Option<GoalCommand>,
+    },
+
     /// set the filtering mode
     Mode {
         /// filtering mode to use
@@ -125,11 +359,17 @@ pub enum ConfigAction {
         /// target directory path
         path: String,
     },
+
+    /// enable or disable the daemon's local http api
+    Api {
+        #[arg(value_enum)]
+        state: ApiToggle,
+    },
 }
 
 #[derive(Subcommand)]
 pub enum DataAction {
-    /// export tracked data to a json file
+    /// export tracked data to json, ndjson, csv or icalendar
     Export {
         /// output file path
         output: String,
@@ -138,11 +378,37 @@ pub enum DataAction {
         #[arg(long)]
         duration: Option<String>,
 
+        /// output format (default: from the file extension, else json)
+        #[arg(short, long, value_enum)]
+        format: Option<ExportFormat>,
+
+        /// export totals per process or per day and process instead of sessions
+        #[arg(short, long, value_enum)]
+        group_by: Option<ExportGrouping>,
+
         /// include data from all synced machines
         #[arg(short = 'a', long)]
         all_machines: bool,
     },
 
+    /// import sessions from a lachesis export or another time tracker
+    Import {
+        /// export file to read
+        input: String,
+
+        /// tracker the file was exported from
+        #[arg(long, value_enum, default_value = "lachesis")]
+        from: ImportSource,
+
+        /// input format of a lachesis export (default: from the file extension, else json)
+        #[arg(short, long, value_enum)]
+        format: Option<ExportFormat>,
+
+        /// import into this machine's database (id or hostname) instead of the current one
+        #[arg(short, long)]
+        machine: Option<String>,
+    },
+
     /// delete tracked data by duration or all
     Delete {
         /// delete all recorded data
@@ -183,6 +449,207 @@ pub enum FilterListAction {
     Clear,
 }
 
+#[derive(Subcommand)]
+pub enum RuleCommand {
+    /// add a rule
+    Add {
+        /// pattern to match
+        pattern: String,
+
+        /// what the pattern is matched against
+        #[arg(short, long, value_enum, default_value = "title")]
+        field: CliRuleField,
+
+        /// how the pattern is interpreted
+        #[arg(short = 'm', long = "match", value_enum, default_value = "glob")]
+        kind: CliMatchKind,
+
+        /// what happens to matching windows
+        #[arg(short, long, value_enum)]
+        action: CliRuleAction,
+
+        /// tag to attach (required for --action tag)
+        #[arg(short, long, required_if_eq("action", "tag"))]
+        tag: Option<String>,
+
+        /// category path to file matches under (required for --action category)
+        #[arg(short, long, required_if_eq("action", "category"))]
+        category: Option<String>,
+
+        /// only apply to this process
+        #[arg(short, long)]
+        process: Option<String>,
+    },
+
+    /// remove a rule by its number in `rule list`
+    Remove {
+        /// rule number
+        index: usize,
+    },
+
+    /// list all rules in evaluation order
+    List,
+
+    /// clear all rules
+    Clear,
+
+    /// show which rules match a window
+    Test {
+        /// process name
+        process: String,
+
+        /// window title
+        #[arg(short = 'T', long)]
+        title: Option<String>,
+
+        /// executable path
+        #[arg(short, long)]
+        exe: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum LimitCommand {
+    /// add a limit on a process, a tag, or all tracked time
+    Add {
+        /// allowed time per period (e.g. 30m, 2h, 1h30m)
+        limit: String,
+
+        /// only count this process
+        #[arg(short, long, conflicts_with = "tag")]
+        process: Option<String>,
+
+        /// only count processes with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// count per week (starting monday) instead of per day
+        #[arg(short, long)]
+        weekly: bool,
+
+        /// percentages of the limit to warn at, comma separated
+        #[arg(long, value_delimiter = ',', value_name = "PERCENT")]
+        warn: Vec<u8>,
+    },
+
+    /// remove a limit by its number in `limit list`
+    Remove {
+        /// limit number
+        index: usize,
+    },
+
+    /// list all limits with their usage so far
+    List,
+
+    /// clear all limits
+    Clear,
+}
+
+#[derive(Subcommand)]
+pub enum GoalCommand {
+    /// add a daily goal for a process, a tag, or all tracked time
+    Add {
+        /// track at least this much a day (e.g. 4h)
+        #[arg(long, conflicts_with = "at_most", required_unless_present = "at_most")]
+        at_least: Option<String>,
+
+        /// track at most this much a day (e.g. 1h)
+        #[arg(long)]
+        at_most: Option<String>,
+
+        /// only count this process
+        #[arg(short, long, conflicts_with = "tag")]
+        process: Option<String>,
+
+        /// only count processes with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// which days the goal applies to
+        #[arg(short, long, value_enum, default_value = "daily")]
+        days: CliGoalDays,
+    },
+
+    /// remove a goal by its number in `goals`
+    Remove {
+        /// goal number
+        index: usize,
+    },
+
+    /// clear all goals
+    Clear,
+}
+
+#[derive(Clone, ValueEnum)]
+pub enum CliGoalDays {
+    Daily,
+    Weekdays,
+    Weekends,
+}
+
+impl From<CliGoalDays> for GoalDays {
+    fn from(d: CliGoalDays) -> Self {
+        match d {
+            CliGoalDays::Daily => GoalDays::Daily,
+            CliGoalDays::Weekdays => GoalDays::Weekdays,
+            CliGoalDays::Weekends => GoalDays::Weekends,
+        }
+    }
+}
+
+#[derive(Clone, ValueEnum)]
+pub enum CliRuleField {
+    Process,
+    Title,
+    Exe,
+}
+
+impl From<CliRuleField> for RuleField {
+    fn from(f: CliRuleField) -> Self {
+        match f {
+            CliRuleField::Process => RuleField::Process,
+            CliRuleField::Title => RuleField::Title,
+            CliRuleField::Exe => RuleField::Exe,
+        }
+    }
+}
+
+#[derive(Clone, ValueEnum)]
+pub enum CliMatchKind {
+    Exact,
+    Glob,
+    Regex,
+}
+
+impl From<CliMatchKind> for MatchKind {
+    fn from(k: CliMatchKind) -> Self {
+        match k {
+            CliMatchKind::Exact => MatchKind::Exact,
+            CliMatchKind::Glob => MatchKind::Glob,
+            CliMatchKind::Regex => MatchKind::Regex,
+        }
+    }
+}
+
+#[derive(Clone, ValueEnum)]
+pub enum CliRuleAction {
+    Track,
+    Ignore,
+    Tag,
+    Category,
+}
+
+impl From<CliRuleAction> for RuleAction {
+    fn from(a: CliRuleAction) -> Self {
+        match a {
+            CliRuleAction::Track => RuleAction::Track,
+            CliRuleAction::Ignore => RuleAction::Ignore,
+            CliRuleAction::Tag => RuleAction::Tag,
+            CliRuleAction::Category => RuleAction::Category,
+        }
+    }
+}
+
 /// CLI-level filter mode (maps to config::FilterMode).
 /// Separate from config::FilterMode because clap's ValueEnum derive
 /// requires a different set of traits than serde.
@@ -203,6 +670,48 @@ impl From<CliFilterMode> for FilterMode {
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Debug, ValueEnum)]
+pub enum ExportFormat {
+    Json,
+    Ndjson,
+    Csv,
+    Ics,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, ValueEnum)]
+pub enum ImportSource {
+    /// json, ndjson or csv from `laches data export`
+    Lachesis,
+    /// activitywatch bucket export (json)
+    Activitywatch,
+    /// wakatime heartbeats data dump (json)
+    Wakatime,
+    /// rescuetime activity export (csv)
+    Rescuetime,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, ValueEnum)]
+pub enum ExportGrouping {
+    Process,
+    Day,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, ValueEnum)]
+pub enum ReportFormat {
+    #[value(alias = "md")]
+    Markdown,
+    Html,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, ValueEnum)]
+pub enum ApiToggle {
+    On,
+    Off,
+    Status,
+    /// replace the token with a new one
+    Rotate,
+}
+
 #[derive(Clone, ValueEnum)]
 pub enum AutostartToggle {
     On,
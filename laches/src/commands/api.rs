@@ -0,0 +1,49 @@
+use crate::cli::ApiToggle;
+use crate::config::{generate_api_token, save_config, Config};
+use crate::process::is_daemon_running;
+use std::error::Error;
+use std::path::Path;
+
+/// Handle `laches config api`. Turning the api on creates a token if
+/// there is none yet.
+pub fn handle_api_config(
+    config: &mut Config,
+    config_dir: &Path,
+    state: ApiToggle,
+) -> Result<(), Box<dyn Error>> {
+    match state {
+        ApiToggle::On => {
+            config.api.enabled = true;
+            if config.api.token.as_deref().is_none_or(str::is_empty) {
+                config.api.token = Some(generate_api_token());
+            }
+            save_config(config, config_dir)?;
+            println!("api enabled on http://127.0.0.1:{}", config.api.port);
+        }
+        ApiToggle::Off => {
+            config.api.enabled = false;
+            save_config(config, config_dir)?;
+            println!("api disabled");
+        }
+        ApiToggle::Rotate => {
+            config.api.token = Some(generate_api_token());
+            save_config(config, config_dir)?;
+            println!("api token replaced");
+        }
+        ApiToggle::Status => {
+            println!(
+                "api: {} (http://127.0.0.1:{})",
+                if config.api.enabled { "on" } else { "off" },
+                config.api.port
+            );
+        }
+    }
+
+    if let Some(token) = &config.api.token {
+        println!("token: {}", token);
+    }
+    if state != ApiToggle::Status && is_daemon_running(config_dir) {
+        println!("info: restart the daemon (`laches stop && laches start`) to apply this");
+    }
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use crate::category::normalize;
+use crate::config::{Config, RuleAction};
+use crate::db::Database;
+use std::error::Error;
+
+/// Handle `laches category`: assign, clear or show a process's category,
+/// or list every assignment when no process is given.
+pub fn handle_category(
+    db: &Database,
+    config: &Config,
+    process: Option<&str>,
+    set: Option<&str>,
+    clear: bool,
+) -> Result<(), Box<dyn Error>> {
+    let Some(process) = process else {
+        let assignments = db.get_all_categories()?;
+        let rules: Vec<_> = config
+            .rules
+            .iter()
+            .filter(|r| r.action == RuleAction::Category)
+            .collect();
+        if assignments.is_empty() && rules.is_empty() {
+            println!("no categories assigned");
+            return Ok(());
+        }
+        for (process, category) in &assignments {
+            println!("  {:<22} {}", process, category);
+        }
+        if !rules.is_empty() {
+            println!("category rules (checked first, see `laches rule list`):");
+            for rule in rules {
+                println!("  - {}", rule);
+            }
+        }
+        return Ok(());
+    };
+
+    if let Some(category) = set {
+        let category =
+            normalize(category).ok_or("error: category must not be empty, e.g. work/clientA")?;
+        db.set_category(process, &category)?;
+        println!("set category of '{}' to '{}'", process, category);
+    } else if clear {
+        if db.clear_category(process)? {
+            println!("cleared category of '{}'", process);
+        } else {
+            println!("'{}' has no category", process);
+        }
+    } else {
+        match db.get_category(process)? {
+            Some(category) => println!("category of '{}': {}", process, category),
+            None => println!("'{}' has no category", process),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,170 @@
+use crate::db::local_time;
+use crate::ipc::{self, Command};
+use crate::process::is_daemon_running;
+use crate::utils::{format_duration_short, local_hm, parse_duration_hms, session_duration_secs};
+use chrono::{DateTime, Local, NaiveTime, Utc};
+use serde_json::Value;
+use std::error::Error;
+use std::path::Path;
+
+/// Send `command` to the running daemon, turning an unreachable socket
+/// into a readable error.
+fn send(config_dir: &Path, command: Command) -> Result<Value, Box<dyn Error>> {
+    ipc::request(&ipc::socket_path(config_dir), command).map_err(|e| {
+        if is_daemon_running(config_dir) {
+            format!(
+                "error: laches_mon did not answer '{}': {}",
+                command.as_str(),
+                e
+            )
+            .into()
+        } else {
+            "error: laches_mon is not running. start it with `laches start`".into()
+        }
+    })
+}
+
+/// Handle `laches status`, `resume` and `reload`.
+pub fn handle_daemon_command(config_dir: &Path, command: Command) -> Result<(), Box<dyn Error>> {
+    if command == Command::Status {
+        return print_status(config_dir);
+    }
+
+    let reply = send(config_dir, command)?;
+    let changed = reply["changed"].as_bool().unwrap_or(true);
+    match command {
+        Command::Resume if changed => println!("info: tracking resumed"),
+        Command::Resume => println!("info: tracking is not paused"),
+        Command::Reload => println!("info: configuration reloaded"),
+        Command::Status | Command::Pause(_) | Command::Shutdown => {}
+    }
+    Ok(())
+}
+
+/// Handle `laches pause`: indefinitely, for `duration`, or until a time of
+/// day.
+pub fn handle_pause(
+    config_dir: &Path,
+    duration: Option<&str>,
+    until: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    let deadline = pause_deadline(duration, until, Local::now())?;
+    let reply = send(
+        config_dir,
+        Command::Pause(deadline.map(|t| t.with_timezone(&Utc))),
+    )?;
+    match deadline {
+        Some(deadline) => println!("info: tracking paused until {}", format_deadline(&deadline)),
+        None if reply["changed"].as_bool() == Some(false) => {
+            println!("info: tracking is already paused")
+        }
+        None => println!("info: tracking paused until `laches resume`"),
+    }
+    Ok(())
+}
+
+/// When a pause given as a duration (`30m`) or a time of day (`17:00`)
+/// ends. A time of day that has already passed today means tomorrow.
+fn pause_deadline(
+    duration: Option<&str>,
+    until: Option<&str>,
+    now: DateTime<Local>,
+) -> Result<Option<DateTime<Local>>, Box<dyn Error>> {
+    if let Some(duration) = duration {
+        let length = parse_duration_hms(duration).ok_or_else(|| {
+            format!(
+                "error: invalid duration '{}', use e.g. 30m, 2h or 1h30m",
+                duration
+            )
+        })?;
+        return Ok(Some(now + length));
+    }
+    let Some(until) = until else {
+        return Ok(None);
+    };
+    let time = NaiveTime::parse_from_str(until, "%H:%M")
+        .map_err(|_| format!("error: invalid time '{}', use HH:MM", until))?;
+    let mut day = now.date_naive();
+    if time <= now.time() {
+        day = day.succ_opt().ok_or("error: date out of range")?;
+    }
+    let deadline = day
+        .and_time(time)
+        .and_local_timezone(Local)
+        .earliest()
+        .ok_or_else(|| format!("error: {} does not exist in the local time zone", until))?;
+    Ok(Some(deadline))
+}
+
+/// `HH:MM` today, else the full local date and time.
+fn format_deadline(deadline: &DateTime<Local>) -> String {
+    if deadline.date_naive() == Local::now().date_naive() {
+        deadline.format("%H:%M").to_string()
+    } else {
+        deadline.format("%Y-%m-%d %H:%M").to_string()
+    }
+}
+
+fn print_status(config_dir: &Path) -> Result<(), Box<dyn Error>> {
+    let reply = match ipc::request(&ipc::socket_path(config_dir), Command::Status) {
+        Ok(reply) => reply,
+        Err(_) if !is_daemon_running(config_dir) => {
+            println!("laches_mon is not running");
+            return Ok(());
+        }
+        Err(e) => return Err(format!("error: laches_mon did not answer 'status': {}", e).into()),
+    };
+
+    println!(
+        "laches_mon {} is running (pid: {}, backend: {})",
+        reply["version"].as_str().unwrap_or("?"),
+        reply["pid"],
+        reply["backend"].as_str().unwrap_or("?"),
+    );
+    println!("  state: {}", reply["state"].as_str().unwrap_or("unknown"));
+    if let Some(process) = reply["process"].as_str() {
+        match reply["title"].as_str() {
+            Some(title) => println!("  tracking: {} \u{2014} {}", process, title),
+            None => println!("  tracking: {}", process),
+        }
+    }
+    if let Some(until) = reply["until"].as_str().and_then(local_time) {
+        println!("  paused until: {}", format_deadline(&until));
+    }
+    if let Some(since) = reply["since"].as_str() {
+        let now = crate::db::format_timestamp(&chrono::Local::now());
+        println!(
+            "  since: {} ({})",
+            local_hm(since).unwrap_or_else(|| since.to_string()),
+            format_duration_short(session_duration_secs(since, &now).unwrap_or(0))
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_pause_deadline() {
+        let now = Local.with_ymd_and_hms(2026, 4, 1, 15, 0, 0).unwrap();
+        assert_eq!(pause_deadline(None, None, now).unwrap(), None);
+        assert_eq!(
+            pause_deadline(Some("30m"), None, now).unwrap(),
+            Some(now + Duration::minutes(30))
+        );
+        assert_eq!(
+            pause_deadline(None, Some("17:00"), now).unwrap(),
+            Local.with_ymd_and_hms(2026, 4, 1, 17, 0, 0).single()
+        );
+        // already past today, so tomorrow
+        assert_eq!(
+            pause_deadline(None, Some("09:00"), now).unwrap(),
+            Local.with_ymd_and_hms(2026, 4, 2, 9, 0, 0).single()
+        );
+        assert!(pause_deadline(None, Some("25:00"), now).is_err());
+        assert!(pause_deadline(Some("30"), None, now).is_err());
+    }
+}
@@ -1,6 +1,16 @@
+use crate::cli::{ExportFormat, ExportGrouping};
+use crate::commands::export::{self, ExportWriter};
+use crate::config::Rule;
 use crate::db::{last_n_days_range, Database};
+use crate::rules::CompiledRules;
 use crate::utils::confirm;
+use std::collections::HashMap;
 use std::error::Error;
+use std::fs::File;
+use std::io::BufWriter;
+
+/// Range used for grouped exports without `--duration`.
+const ALL_TIME_DAYS: i64 = 365 * 100;
 
 /// Parse a duration string like "7d" or "30d" into a number of days.
 pub fn parse_duration_days(s: &str) -> Result<i64, Box<dyn Error>> {
@@ -17,38 +27,120 @@ pub fn parse_duration_days(s: &str) -> Result<i64, Box<dyn Error>> {
     Ok(days)
 }
 
-/// Export sessions to a JSON file.
+/// Export sessions, or per-process or per-day totals, to a file. Sessions
+/// are streamed to disk one row at a time.
 pub fn export_sessions(
     db: &Database,
+    rules: &[Rule],
     output: &str,
     duration: Option<&str>,
+    format: Option<ExportFormat>,
+    group_by: Option<ExportGrouping>,
 ) -> Result<(), Box<dyn Error>> {
-    let sessions = if let Some(dur) = duration {
-        let days = parse_duration_days(dur)?;
-        let (start, end) = last_n_days_range(days);
-        db.export_sessions(Some(&start), Some(&end))?
-    } else {
-        db.export_sessions(None, None)?
+    let format = format.unwrap_or_else(|| export::format_from_path(output));
+    if format == ExportFormat::Ics && group_by.is_some() {
+        return Err("error: --group-by cannot be used with ics export".into());
+    }
+
+    let range = match duration {
+        Some(dur) => Some(last_n_days_range(parse_duration_days(dur)?)),
+        None => None,
+    };
+
+    let mut process_tags: HashMap<String, Vec<String>> = HashMap::new();
+    for (process, tag) in db.get_all_tags()? {
+        process_tags.entry(process).or_default().push(tag);
+    }
+    let tags_of = |process: &str| process_tags.get(process).cloned().unwrap_or_default();
+
+    let out = BufWriter::new(File::create(output)?);
+
+    let (count, noun) = match group_by {
+        None => {
+            let rules = CompiledRules::new(rules);
+            let with_machine = db.is_merged();
+            let mut columns = vec![
+                "process",
+                "exe_path",
+                "window_title",
+                "start_time",
+                "end_time",
+                "duration_seconds",
+                "idle",
+                "kind",
+                "utc_offset",
+                "tags",
+            ];
+            if with_machine {
+                columns.insert(0, "machine");
+            }
+
+            let mut writer = ExportWriter::begin(out, format, &columns)?;
+            db.for_each_session(
+                range.as_ref().map(|(s, e)| (s.as_str(), e.as_str())),
+                |s| -> Result<(), Box<dyn Error>> {
+                    let mut tags = tags_of(&s.process_name);
+                    for tag in rules.tags_for(
+                        &s.process_name,
+                        s.exe_path.as_deref(),
+                        s.window_title.as_deref(),
+                    ) {
+                        if !tags.iter().any(|t| t == tag) {
+                            tags.push(tag.to_string());
+                        }
+                    }
+                    if format == ExportFormat::Ics {
+                        writer.write_event(&s, &tags)?;
+                    } else {
+                        writer.write_row(&export::session_row(&s, tags, with_machine))?;
+                    }
+                    Ok(())
+                },
+            )?;
+            (writer.finish()?, "sessions")
+        }
+
+        Some(ExportGrouping::Process) => {
+            let (start, end) = range.unwrap_or_else(|| last_n_days_range(ALL_TIME_DAYS));
+            let columns = [
+                "process",
+                "total_seconds",
+                "session_count",
+                "active_days",
+                "tags",
+            ];
+            let mut writer = ExportWriter::begin(out, format, &columns)?;
+            for p in db.query_process_summaries(&start, &end, None)? {
+                let tags = tags_of(&p.process_name);
+                writer.write_row(&vec![
+                    ("process", p.process_name.into()),
+                    ("total_seconds", p.total_seconds.into()),
+                    ("session_count", p.session_count.into()),
+                    ("active_days", p.active_days.into()),
+                    ("tags", tags.into()),
+                ])?;
+            }
+            (writer.finish()?, "process totals")
+        }
+
+        Some(ExportGrouping::Day) => {
+            let (start, end) = range.unwrap_or_else(|| last_n_days_range(ALL_TIME_DAYS));
+            let columns = ["date", "process", "total_seconds", "tags"];
+            let mut writer = ExportWriter::begin(out, format, &columns)?;
+            for d in db.query_daily_process_totals(&start, &end)? {
+                let tags = tags_of(&d.process_name);
+                writer.write_row(&vec![
+                    ("date", d.date.into()),
+                    ("process", d.process_name.into()),
+                    ("total_seconds", d.total_seconds.into()),
+                    ("tags", tags.into()),
+                ])?;
+            }
+            (writer.finish()?, "daily totals")
+        }
     };
 
-    let json = serde_json::to_string_pretty(
-        &sessions
-            .iter()
-            .map(|s| {
-                serde_json::json!({
-                    "process": s.process_name,
-                    "exe_path": s.exe_path,
-                    "window_title": s.window_title,
-                    "start_time": s.start_time,
-                    "end_time": s.end_time,
-                    "idle": s.idle,
-                })
-            })
-            .collect::<Vec<_>>(),
-    )?;
-
-    std::fs::write(output, &json)?;
-    println!("exported {} sessions to '{}'", sessions.len(), output);
+    println!("exported {} {} to '{}'", count, noun, output);
     Ok(())
 }
 
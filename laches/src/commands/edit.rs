@@ -0,0 +1,566 @@
+//! Manual entries and corrections to recorded sessions.
+
+use crate::db::{format_timestamp, local_time, parse_timestamp, Database, Session, SessionKind};
+use crate::utils::{format_duration_short, local_hm};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use std::error::Error;
+
+/// Changes requested by `laches edit`. `None` leaves a field alone.
+#[derive(Default)]
+pub struct SessionEdit<'a> {
+    pub process: Option<&'a str>,
+    /// An empty title clears it.
+    pub title: Option<&'a str>,
+    pub from: Option<&'a str>,
+    pub to: Option<&'a str>,
+    pub idle: bool,
+    pub active: bool,
+}
+
+/// Parse a time given on the command line. `HH:MM` is a time on `day` in
+/// the report time zone, `YYYY-MM-DD HH:MM` names the day too, and an
+/// RFC 3339 time with an offset is used as is.
+pub fn parse_entry_time(s: &str, day: NaiveDate) -> Result<DateTime<Utc>, Box<dyn Error>> {
+    let s = s.trim();
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Ok(t.with_timezone(&Utc));
+    }
+    let naive = ["%H:%M", "%H:%M:%S"]
+        .iter()
+        .find_map(|f| NaiveTime::parse_from_str(s, f).ok())
+        .map(|time| day.and_time(time))
+        .or_else(|| {
+            [
+                "%Y-%m-%d %H:%M",
+                "%Y-%m-%dT%H:%M",
+                "%Y-%m-%d %H:%M:%S",
+                "%Y-%m-%dT%H:%M:%S",
+            ]
+            .iter()
+            .find_map(|f| NaiveDateTime::parse_from_str(s, f).ok())
+        })
+        .ok_or_else(|| format!("error: invalid time '{}', use HH:MM or YYYY-MM-DD HH:MM", s))?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+        .ok_or_else(|| format!("error: {} does not exist in the local time zone", s).into())
+}
+
+/// Handle `laches add`: record a finished session by hand.
+pub fn handle_add(
+    db: &Database,
+    process: &str,
+    from: &str,
+    to: &str,
+    title: Option<&str>,
+    tags: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    let today = Local::now().date_naive();
+    let (start, end) = (parse_entry_time(from, today)?, parse_entry_time(to, today)?);
+    check_span(start, end)?;
+
+    let session = Session {
+        id: 0,
+        process_name: process.to_string(),
+        exe_path: None,
+        window_title: title.filter(|t| !t.is_empty()).map(str::to_string),
+        start_time: format_timestamp(&start),
+        end_time: Some(format_timestamp(&end)),
+        idle: false,
+        kind: SessionKind::Active,
+        utc_offset: Some(utc_offset_at(start)),
+        machine: None,
+    };
+    let tags: Vec<&str> = tags
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    let (id, trimmed) = db.transaction(|db| {
+        let trimmed = make_room(db, start, end, &[])?;
+        let id = db.add_session(&session)?;
+        for tag in &tags {
+            db.add_tag(process, tag)?;
+        }
+        Ok::<_, Box<dyn Error>>((id, trimmed))
+    })?;
+
+    println!("added session {}", describe(&Session { id, ..session }));
+    for tag in tags {
+        println!("added tag '{}' to '{}'", tag, process);
+    }
+    report_trimmed(trimmed);
+    Ok(())
+}
+
+/// Handle `laches edit`.
+pub fn handle_edit(db: &Database, id: i64, edit: &SessionEdit) -> Result<(), Box<dyn Error>> {
+    let mut session = finished_session(db, id)?;
+    let (mut start, mut end) = span(&session)?;
+    let day = session_day(&session);
+
+    if edit.process.is_none()
+        && edit.title.is_none()
+        && edit.from.is_none()
+        && edit.to.is_none()
+        && !edit.idle
+        && !edit.active
+    {
+        return Err("error: nothing to change, see `laches edit --help`".into());
+    }
+
+    if let Some(process) = edit.process {
+        session.process_name = process.to_string();
+    }
+    if let Some(title) = edit.title {
+        session.window_title = Some(title.to_string()).filter(|t| !t.is_empty());
+    }
+    if edit.idle {
+        // idle sessions are named after their kind, like the daemon does
+        session.kind = SessionKind::Idle;
+        session.process_name = SessionKind::Idle.as_str().to_string();
+        session.exe_path = None;
+        session.window_title = None;
+    }
+    if edit.active && session.kind != SessionKind::Active {
+        if edit.process.is_none() {
+            return Err("error: --active needs --process to say what was in use".into());
+        }
+        session.kind = SessionKind::Active;
+    }
+    session.idle = session.kind != SessionKind::Active;
+
+    if let Some(from) = edit.from {
+        start = parse_entry_time(from, day)?;
+        session.start_time = format_timestamp(&start);
+        session.utc_offset = Some(utc_offset_at(start));
+    }
+    if let Some(to) = edit.to {
+        end = parse_entry_time(to, day)?;
+        session.end_time = Some(format_timestamp(&end));
+    }
+    check_span(start, end)?;
+
+    let trimmed = db.transaction(|db| {
+        let trimmed = make_room(db, start, end, &[id])?;
+        db.update_session(&session)?;
+        Ok::<_, Box<dyn Error>>(trimmed)
+    })?;
+
+    println!("updated session {}", describe(&session));
+    report_trimmed(trimmed);
+    Ok(())
+}
+
+/// Handle `laches split`: cut a session in two at `at`.
+pub fn handle_split(db: &Database, id: i64, at: &str) -> Result<(), Box<dyn Error>> {
+    let session = finished_session(db, id)?;
+    let (start, end) = span(&session)?;
+    let at = parse_entry_time(at, session_day(&session))?;
+    if at <= start || at >= end {
+        return Err(format!(
+            "error: {} is not inside session {}",
+            hm(&format_timestamp(&at)),
+            describe(&session)
+        )
+        .into());
+    }
+
+    let mut head = session.clone();
+    head.end_time = Some(format_timestamp(&at));
+    let mut tail = session;
+    tail.start_time = format_timestamp(&at);
+    tail.utc_offset = Some(utc_offset_at(at));
+
+    tail.id = db.transaction(|db| {
+        db.update_session(&head)?;
+        db.add_session(&tail)
+    })?;
+
+    println!("split session #{} into:", id);
+    println!("  {}", describe(&head));
+    println!("  {}", describe(&tail));
+    Ok(())
+}
+
+/// Handle `laches merge`: join two sessions of the same kind into one
+/// spanning both. The earlier session's process and title are kept.
+pub fn handle_merge(db: &Database, first: i64, second: i64) -> Result<(), Box<dyn Error>> {
+    if first == second {
+        return Err("error: can't merge a session with itself".into());
+    }
+    let (a, b) = (finished_session(db, first)?, finished_session(db, second)?);
+    let ((a_start, a_end), (b_start, b_end)) = (span(&a)?, span(&b)?);
+    if a.kind != b.kind {
+        return Err(format!(
+            "error: can't merge a {} session with a {} one",
+            a.kind, b.kind
+        )
+        .into());
+    }
+    let (mut merged, dropped) = if a_start <= b_start { (a, b) } else { (b, a) };
+    let (start, end) = (a_start.min(b_start), a_end.max(b_end));
+    merged.end_time = Some(format_timestamp(&end));
+
+    let trimmed = db.transaction(|db| {
+        let trimmed = make_room(db, start, end, &[merged.id, dropped.id])?;
+        db.update_session(&merged)?;
+        db.delete_session(dropped.id)?;
+        Ok::<_, Box<dyn Error>>(trimmed)
+    })?;
+
+    println!("merged #{} into session {}", dropped.id, describe(&merged));
+    if dropped.process_name != merged.process_name {
+        println!(
+            "info: kept '{}' from the earlier session, '{}' was dropped",
+            merged.process_name, dropped.process_name
+        );
+    }
+    report_trimmed(trimmed);
+    Ok(())
+}
+
+/// Make room for a session spanning `start..end`. Idle, locked, suspended
+/// and paused time in the way is cut back, since a manual entry usually
+/// covers time away from the computer. Active sessions and the running
+/// session are never touched; overlapping one is an error. Returns how
+/// many sessions were cut back or removed.
+fn make_room(
+    db: &Database,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    exclude: &[i64],
+) -> Result<usize, Box<dyn Error>> {
+    let mut overlapping = Vec::new();
+    for s in db.query_sessions(&format_timestamp(&start), &format_timestamp(&end))? {
+        if exclude.contains(&s.id) {
+            continue;
+        }
+        if s.end_time.is_none() || s.kind == SessionKind::Active {
+            return Err(format!("error: overlaps session {}", describe(&s)).into());
+        }
+        let (s_start, s_end) = span(&s)?;
+        if s_start < end && s_end > start {
+            overlapping.push((s, s_start, s_end));
+        }
+    }
+
+    for (s, s_start, s_end) in &overlapping {
+        if *s_start >= start && *s_end <= end {
+            db.delete_session(s.id)?;
+            continue;
+        }
+        if *s_start < start && *s_end > end {
+            let mut tail = s.clone();
+            tail.start_time = format_timestamp(&end);
+            db.add_session(&tail)?;
+        }
+        let mut head = s.clone();
+        if *s_start < start {
+            head.end_time = Some(format_timestamp(&start));
+        } else {
+            head.start_time = format_timestamp(&end);
+        }
+        db.update_session(&head)?;
+    }
+    Ok(overlapping.len())
+}
+
+/// Look up a session that has ended. The running session belongs to the
+/// daemon and can't be edited.
+fn finished_session(db: &Database, id: i64) -> Result<Session, Box<dyn Error>> {
+    let session = db
+        .get_session(id)?
+        .ok_or_else(|| format!("error: no session with id {}", id))?;
+    if session.end_time.is_none() {
+        return Err(format!(
+            "error: session #{} is still running, edit it once it has ended",
+            id
+        )
+        .into());
+    }
+    Ok(session)
+}
+
+fn span(s: &Session) -> Result<(DateTime<Utc>, DateTime<Utc>), Box<dyn Error>> {
+    let parse = |t: &str| {
+        parse_timestamp(t)
+            .ok_or_else(|| format!("error: session #{} has invalid time '{}'", s.id, t))
+    };
+    let end = s
+        .end_time
+        .as_deref()
+        .ok_or_else(|| format!("error: session #{} is still running", s.id))?;
+    Ok((parse(&s.start_time)?, parse(end)?))
+}
+
+fn check_span(start: DateTime<Utc>, end: DateTime<Utc>) -> Result<(), Box<dyn Error>> {
+    if end <= start {
+        return Err("error: a session must end after it starts".into());
+    }
+    if end > Utc::now() {
+        return Err("error: a session can't end in the future".into());
+    }
+    Ok(())
+}
+
+/// The local day a session starts on, which bare `HH:MM` times refer to.
+fn session_day(s: &Session) -> NaiveDate {
+    local_time(&s.start_time)
+        .map(|t| t.date_naive())
+        .unwrap_or_else(|| Local::now().date_naive())
+}
+
+fn utc_offset_at(t: DateTime<Utc>) -> i32 {
+    t.with_timezone(&Local).offset().local_minus_utc()
+}
+
+fn hm(timestamp: &str) -> String {
+    local_hm(timestamp).unwrap_or_else(|| timestamp.to_string())
+}
+
+/// `#12 firefox 10:00-11:00 (1h 0m)`
+fn describe(s: &Session) -> String {
+    let end = s
+        .end_time
+        .as_deref()
+        .map(hm)
+        .unwrap_or_else(|| "now".into());
+    let duration = span(s)
+        .map(|(start, end)| format!(" ({})", format_duration_short((end - start).num_seconds())))
+        .unwrap_or_default();
+    format!(
+        "#{} {} {}-{}{}",
+        s.id,
+        s.process_name,
+        hm(&s.start_time),
+        end,
+        duration
+    )
+}
+
+fn report_trimmed(count: usize) {
+    if count > 0 {
+        println!(
+            "info: cut back {} overlapping idle, locked, suspended or paused session(s)",
+            count
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(db: &Database, process: &str, kind: SessionKind, start: &str, end: &str) -> i64 {
+        db.add_session(&Session {
+            id: 0,
+            process_name: process.to_string(),
+            exe_path: None,
+            window_title: None,
+            start_time: start.to_string(),
+            end_time: Some(end.to_string()),
+            idle: kind != SessionKind::Active,
+            kind,
+            utc_offset: Some(0),
+            machine: None,
+        })
+        .unwrap()
+    }
+
+    fn spans(db: &Database) -> Vec<(String, String, String)> {
+        let mut all = db
+            .query_sessions("2026-04-01T00:00:00.000Z", "2026-04-02T00:00:00.000Z")
+            .unwrap();
+        all.reverse();
+        all.into_iter()
+            .map(|s| (s.process_name, s.start_time, s.end_time.unwrap()))
+            .collect()
+    }
+
+    fn at(s: &str) -> DateTime<Utc> {
+        parse_timestamp(s).unwrap()
+    }
+
+    #[test]
+    fn test_make_room_cuts_back_away_time() {
+        let db = Database::open_memory().unwrap();
+        session(
+            &db,
+            "idle",
+            SessionKind::Idle,
+            "2026-04-01T09:00:00.000Z",
+            "2026-04-01T12:00:00.000Z",
+        );
+        session(
+            &db,
+            "locked",
+            SessionKind::Locked,
+            "2026-04-01T12:00:00.000Z",
+            "2026-04-01T12:30:00.000Z",
+        );
+
+        let trimmed = make_room(
+            &db,
+            at("2026-04-01T10:00:00.000Z"),
+            at("2026-04-01T12:30:00.000Z"),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(trimmed, 2);
+        assert_eq!(
+            spans(&db),
+            vec![(
+                "idle".to_string(),
+                "2026-04-01T09:00:00.000Z".to_string(),
+                "2026-04-01T10:00:00.000Z".to_string()
+            )]
+        );
+
+        // an idle session around the whole span is split in two
+        make_room(
+            &db,
+            at("2026-04-01T09:15:00.000Z"),
+            at("2026-04-01T09:30:00.000Z"),
+            &[],
+        )
+        .unwrap();
+        let ends: Vec<(String, String)> = spans(&db).into_iter().map(|(_, s, e)| (s, e)).collect();
+        assert_eq!(
+            ends,
+            vec![
+                (
+                    "2026-04-01T09:00:00.000Z".to_string(),
+                    "2026-04-01T09:15:00.000Z".to_string()
+                ),
+                (
+                    "2026-04-01T09:30:00.000Z".to_string(),
+                    "2026-04-01T10:00:00.000Z".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_make_room_refuses_active_and_running_sessions() {
+        let db = Database::open_memory().unwrap();
+        let id = session(
+            &db,
+            "firefox",
+            SessionKind::Active,
+            "2026-04-01T10:00:00.000Z",
+            "2026-04-01T11:00:00.000Z",
+        );
+        let (start, end) = (
+            at("2026-04-01T10:30:00.000Z"),
+            at("2026-04-01T11:30:00.000Z"),
+        );
+        assert!(make_room(&db, start, end, &[]).is_err());
+        assert_eq!(make_room(&db, start, end, &[id]).unwrap(), 0);
+        // touching end to start is fine
+        assert_eq!(
+            make_room(&db, at("2026-04-01T11:00:00.000Z"), end, &[]).unwrap(),
+            0
+        );
+
+        db.start_session("code", None, None, SessionKind::Active)
+            .unwrap();
+        let now = Utc::now();
+        let (start, end) = (
+            now - chrono::Duration::hours(1),
+            now + chrono::Duration::minutes(1),
+        );
+        assert!(make_room(&db, start, end, &[]).is_err());
+    }
+
+    #[test]
+    fn test_split_and_merge() {
+        let db = Database::open_memory().unwrap();
+        let id = session(
+            &db,
+            "firefox",
+            SessionKind::Active,
+            "2026-04-01T10:00:00.000Z",
+            "2026-04-01T11:00:00.000Z",
+        );
+        handle_split(&db, id, "2026-04-01T10:20:00Z").unwrap();
+        assert!(handle_split(&db, id, "2026-04-01T10:40:00Z").is_err());
+        let parts = spans(&db);
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].2, "2026-04-01T10:20:00.000Z");
+        assert_eq!(parts[1].1, "2026-04-01T10:20:00.000Z");
+
+        let tail = db
+            .query_sessions("2026-04-01T10:30:00.000Z", "2026-04-01T10:31:00.000Z")
+            .unwrap()[0]
+            .id;
+        handle_merge(&db, tail, id).unwrap();
+        assert_eq!(
+            spans(&db),
+            vec![(
+                "firefox".to_string(),
+                "2026-04-01T10:00:00.000Z".to_string(),
+                "2026-04-01T11:00:00.000Z".to_string()
+            )]
+        );
+        assert!(db.get_session(tail).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_edit_session() {
+        let db = Database::open_memory().unwrap();
+        let id = session(
+            &db,
+            "firefox",
+            SessionKind::Active,
+            "2026-04-01T10:00:00.000Z",
+            "2026-04-01T11:00:00.000Z",
+        );
+        assert!(handle_edit(&db, id, &SessionEdit::default()).is_err());
+
+        let edit = SessionEdit {
+            process: Some("zoom"),
+            title: Some("standup"),
+            to: Some("2026-04-01T10:30:00Z"),
+            ..Default::default()
+        };
+        handle_edit(&db, id, &edit).unwrap();
+        let s = db.get_session(id).unwrap().unwrap();
+        assert_eq!(s.process_name, "zoom");
+        assert_eq!(s.window_title.as_deref(), Some("standup"));
+        assert_eq!(s.end_time.as_deref(), Some("2026-04-01T10:30:00.000Z"));
+
+        let idle = SessionEdit {
+            idle: true,
+            ..Default::default()
+        };
+        handle_edit(&db, id, &idle).unwrap();
+        let s = db.get_session(id).unwrap().unwrap();
+        assert_eq!((s.kind, s.idle), (SessionKind::Idle, true));
+
+        let active = SessionEdit {
+            active: true,
+            ..Default::default()
+        };
+        assert!(handle_edit(&db, id, &active).is_err());
+    }
+
+    #[test]
+    fn test_parse_entry_time() {
+        let day = NaiveDate::from_ymd_opt(2026, 4, 1).unwrap();
+        let expected = Local
+            .with_ymd_and_hms(2026, 4, 1, 10, 30, 0)
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(parse_entry_time("10:30", day).unwrap(), expected);
+        assert_eq!(parse_entry_time("2026-04-01 10:30", day).unwrap(), expected);
+        assert_eq!(
+            parse_entry_time("2026-04-01T10:30:00+02:00", day).unwrap(),
+            at("2026-04-01T08:30:00.000Z")
+        );
+        assert!(parse_entry_time("half past ten", day).is_err());
+    }
+}
@@ -0,0 +1,353 @@
+use crate::cli::ExportFormat;
+use crate::db::{format_timestamp, parse_timestamp, Session, SessionKind};
+use chrono::Utc;
+use serde_json::{Map, Value};
+use std::io::{self, Write};
+
+/// Pick an export format from the output file extension, defaulting to json.
+pub fn format_from_path(path: &str) -> ExportFormat {
+    let ext = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match ext.as_deref() {
+        Some("csv") => ExportFormat::Csv,
+        Some("ndjson" | "jsonl") => ExportFormat::Ndjson,
+        Some("ics") => ExportFormat::Ics,
+        _ => ExportFormat::Json,
+    }
+}
+
+/// One exported record as ordered (column, value) pairs. Every row of an
+/// export has the same columns, in the same order.
+pub type Row = Vec<(&'static str, Value)>;
+
+/// Build the export row for a session.
+pub fn session_row(s: &Session, tags: Vec<String>, with_machine: bool) -> Row {
+    let end = s
+        .end_time
+        .clone()
+        .unwrap_or_else(|| format_timestamp(&Utc::now()));
+    let duration = crate::utils::session_duration_secs(&s.start_time, &end).unwrap_or(0);
+
+    let mut row: Row = Vec::with_capacity(11);
+    if with_machine {
+        row.push(("machine", s.machine.clone().into()));
+    }
+    row.extend([
+        ("process", s.process_name.clone().into()),
+        ("exe_path", s.exe_path.clone().into()),
+        ("window_title", s.window_title.clone().into()),
+        ("start_time", s.start_time.clone().into()),
+        ("end_time", s.end_time.clone().into()),
+        ("duration_seconds", duration.into()),
+        ("idle", s.idle.into()),
+        ("kind", s.kind.as_str().into()),
+        ("utc_offset", s.utc_offset.into()),
+        ("tags", tags.into()),
+    ]);
+    row
+}
+
+/// Streams rows to `out` in one of the export formats. Rows are written as
+/// they arrive, so nothing but the current row is held in memory.
+pub struct ExportWriter<W: Write> {
+    out: W,
+    format: ExportFormat,
+    rows: usize,
+}
+
+impl<W: Write> ExportWriter<W> {
+    /// Write the format's header. `columns` is the CSV header row.
+    pub fn begin(mut out: W, format: ExportFormat, columns: &[&str]) -> io::Result<Self> {
+        match format {
+            ExportFormat::Json => writeln!(out, "[")?,
+            ExportFormat::Ndjson => {}
+            ExportFormat::Csv => {
+                let header: Vec<String> = columns.iter().map(|c| csv_field(c)).collect();
+                write!(out, "{}\r\n", header.join(","))?;
+            }
+            ExportFormat::Ics => {
+                for line in [
+                    "BEGIN:VCALENDAR",
+                    "VERSION:2.0",
+                    "PRODID:-//lachesis//laches//EN",
+                    "CALSCALE:GREGORIAN",
+                ] {
+                    write!(out, "{}\r\n", line)?;
+                }
+            }
+        }
+        Ok(ExportWriter {
+            out,
+            format,
+            rows: 0,
+        })
+    }
+
+    /// Write one row. Only meaningful for json, ndjson and csv.
+    pub fn write_row(&mut self, row: &Row) -> io::Result<()> {
+        match self.format {
+            ExportFormat::Json | ExportFormat::Ndjson => {
+                let object: Map<String, Value> = row
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.clone()))
+                    .collect();
+                let json = serde_json::to_string(&object)?;
+                if self.format == ExportFormat::Json {
+                    let sep = if self.rows > 0 { ",\n" } else { "" };
+                    write!(self.out, "{}  {}", sep, json)?;
+                } else {
+                    writeln!(self.out, "{}", json)?;
+                }
+            }
+            ExportFormat::Csv => {
+                let fields: Vec<String> = row.iter().map(|(_, v)| csv_value(v)).collect();
+                write!(self.out, "{}\r\n", fields.join(","))?;
+            }
+            ExportFormat::Ics => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "ics export only supports sessions",
+                ))
+            }
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// Write a session as a calendar event. Returns false for sessions
+    /// that are skipped: anything but active time, and the open session.
+    pub fn write_event(&mut self, s: &Session, tags: &[String]) -> io::Result<bool> {
+        let (Some(start), Some(end)) = (
+            parse_timestamp(&s.start_time),
+            s.end_time.as_deref().and_then(parse_timestamp),
+        ) else {
+            return Ok(false);
+        };
+        if s.kind != SessionKind::Active {
+            return Ok(false);
+        }
+
+        let ics_time = |t: &chrono::DateTime<Utc>| t.format("%Y%m%dT%H%M%SZ").to_string();
+        let uid = match &s.machine {
+            Some(machine) => format!("{}-{}@lachesis", s.id, machine),
+            None => format!("{}@lachesis", s.id),
+        };
+        let summary = match &s.window_title {
+            Some(title) if !title.is_empty() => format!("{} - {}", s.process_name, title),
+            _ => s.process_name.clone(),
+        };
+
+        let mut lines = vec![
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}", ics_text(&uid)),
+            format!("DTSTAMP:{}", ics_time(&Utc::now())),
+            format!("DTSTART:{}", ics_time(&start)),
+            format!("DTEND:{}", ics_time(&end)),
+            format!("SUMMARY:{}", ics_text(&summary)),
+        ];
+        if let Some(exe) = &s.exe_path {
+            lines.push(format!("DESCRIPTION:{}", ics_text(exe)));
+        }
+        if !tags.is_empty() {
+            let tags: Vec<String> = tags.iter().map(|t| ics_text(t)).collect();
+            lines.push(format!("CATEGORIES:{}", tags.join(",")));
+        }
+        lines.push("END:VEVENT".to_string());
+
+        for line in lines {
+            write!(self.out, "{}\r\n", fold_ics_line(&line))?;
+        }
+        self.rows += 1;
+        Ok(true)
+    }
+
+    /// Write the format's footer and return the number of rows written.
+    pub fn finish(mut self) -> io::Result<usize> {
+        match self.format {
+            ExportFormat::Json => {
+                if self.rows > 0 {
+                    writeln!(self.out)?;
+                }
+                writeln!(self.out, "]")?;
+            }
+            ExportFormat::Ics => write!(self.out, "END:VCALENDAR\r\n")?,
+            ExportFormat::Ndjson | ExportFormat::Csv => {}
+        }
+        self.out.flush()?;
+        Ok(self.rows)
+    }
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Render a JSON value as a CSV field. Nulls are empty and lists are
+/// joined with `;`.
+fn csv_value(v: &Value) -> String {
+    match v {
+        Value::Null => String::new(),
+        Value::String(s) => csv_field(s),
+        Value::Array(items) => {
+            let items: Vec<String> = items
+                .iter()
+                .map(|i| i.as_str().map_or_else(|| i.to_string(), str::to_string))
+                .collect();
+            csv_field(&items.join(";"))
+        }
+        other => csv_field(&other.to_string()),
+    }
+}
+
+/// Escape text for an iCalendar property value (RFC 5545 3.3.11).
+fn ics_text(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line at 75 octets without splitting a UTF-8 character.
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / 74 * 3);
+    let mut width = 0;
+    for c in line.chars() {
+        let len = c.len_utf8();
+        if width + len > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += len;
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(kind: SessionKind, title: &str, end: Option<&str>) -> Session {
+        Session {
+            id: 7,
+            process_name: "code".to_string(),
+            exe_path: Some("/usr/bin/code".to_string()),
+            window_title: Some(title.to_string()),
+            start_time: "2026-04-01T10:00:00.000Z".to_string(),
+            end_time: end.map(str::to_string),
+            idle: kind != SessionKind::Active,
+            kind,
+            utc_offset: Some(7200),
+            machine: None,
+        }
+    }
+
+    fn export(format: ExportFormat, rows: &[Row]) -> String {
+        let columns: Vec<&str> = rows[0].iter().map(|(k, _)| *k).collect();
+        let mut out = Vec::new();
+        let mut w = ExportWriter::begin(&mut out, format, &columns).unwrap();
+        for row in rows {
+            w.write_row(row).unwrap();
+        }
+        assert_eq!(w.finish().unwrap(), rows.len());
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(format_from_path("out.CSV"), ExportFormat::Csv);
+        assert_eq!(format_from_path("out.jsonl"), ExportFormat::Ndjson);
+        assert_eq!(format_from_path("week.ics"), ExportFormat::Ics);
+        assert_eq!(format_from_path("out"), ExportFormat::Json);
+    }
+
+    #[test]
+    fn test_csv_escapes_and_joins_tags() {
+        let s = session(
+            SessionKind::Active,
+            "a, \"quoted\" title",
+            Some("2026-04-01T10:30:00.000Z"),
+        );
+        let row = session_row(&s, vec!["work".into(), "dev".into()], false);
+        let mut w = ExportWriter::begin(Vec::new(), ExportFormat::Csv, &["x"]).unwrap();
+        w.write_row(&row).unwrap();
+        let line = String::from_utf8(w.out).unwrap();
+        assert_eq!(
+            line,
+            "x\r\ncode,/usr/bin/code,\"a, \"\"quoted\"\" title\",2026-04-01T10:00:00.000Z,\
+             2026-04-01T10:30:00.000Z,1800,false,active,7200,work;dev\r\n"
+        );
+    }
+
+    #[test]
+    fn test_json_and_ndjson_are_parseable() {
+        let a = session(SessionKind::Active, "a", Some("2026-04-01T10:00:05.000Z"));
+        let b = session(SessionKind::Idle, "b", None);
+        let rows = vec![
+            session_row(&a, vec!["work".into()], false),
+            session_row(&b, vec![], false),
+        ];
+
+        let json: Vec<Value> = serde_json::from_str(&export(ExportFormat::Json, &rows)).unwrap();
+        assert_eq!(json.len(), 2);
+        assert_eq!(json[0]["tags"], serde_json::json!(["work"]));
+        assert_eq!(json[0]["duration_seconds"], 5);
+        assert_eq!(json[1]["end_time"], Value::Null);
+
+        let ndjson = export(ExportFormat::Ndjson, &rows);
+        let lines: Vec<Value> = ndjson
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines, json);
+
+        let empty = ExportWriter::begin(Vec::new(), ExportFormat::Json, &[]).unwrap();
+        assert_eq!(empty.finish().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_ics_events() {
+        let mut out = Vec::new();
+        let mut w = ExportWriter::begin(&mut out, ExportFormat::Ics, &[]).unwrap();
+        let done = session(
+            SessionKind::Active,
+            "notes; draft",
+            Some("2026-04-01T11:00:00.000Z"),
+        );
+        assert!(w.write_event(&done, &["work".to_string()]).unwrap());
+        assert!(!w
+            .write_event(&session(SessionKind::Active, "open", None), &[])
+            .unwrap());
+        assert!(!w
+            .write_event(
+                &session(SessionKind::Locked, "x", Some("2026-04-01T11:00:00.000Z")),
+                &[]
+            )
+            .unwrap());
+
+        assert_eq!(w.finish().unwrap(), 1);
+        let ics = String::from_utf8(out).unwrap();
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTART:20260401T100000Z\r\n"));
+        assert!(ics.contains("DTEND:20260401T110000Z\r\n"));
+        assert!(ics.contains("SUMMARY:code - notes\\; draft\r\n"));
+        assert!(ics.contains("CATEGORIES:work\r\n"));
+        assert!(ics.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn test_fold_ics_line() {
+        let line = format!("SUMMARY:{}", "é".repeat(60));
+        let folded = fold_ics_line(&line);
+        assert!(folded.split("\r\n").all(|l| l.len() <= 75));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
@@ -1,4 +1,5 @@
-use crate::config::{FilterMode, FilterPattern};
+use crate::config::{FilterMode, FilterPattern, Rule};
+use crate::rules::{CompiledPattern, CompiledRules};
 use regex::Regex;
 
 /// Check if a process name matches any pattern in the list.
@@ -23,30 +24,11 @@ pub fn matches_any_pattern(process_name: &str, patterns: &[FilterPattern]) -> bo
     false
 }
 
-enum CompiledPattern {
-    Exact(String),
-    Regex(Regex),
-}
-
-impl CompiledPattern {
-    fn matches(&self, name: &str) -> bool {
-        match self {
-            CompiledPattern::Exact(s) => {
-                if cfg!(windows) {
-                    s.eq_ignore_ascii_case(name)
-                } else {
-                    s == name
-                }
-            }
-            CompiledPattern::Regex(r) => r.is_match(name),
-        }
-    }
-}
-
 pub struct CompiledFilter {
     mode: FilterMode,
     whitelist: Vec<CompiledPattern>,
     blacklist: Vec<CompiledPattern>,
+    rules: CompiledRules,
 }
 
 impl CompiledFilter {
@@ -55,9 +37,16 @@ impl CompiledFilter {
             mode,
             whitelist: Self::compile_patterns(whitelist),
             blacklist: Self::compile_patterns(blacklist),
+            rules: CompiledRules::default(),
         }
     }
 
+    /// Check track/ignore rules before falling back to the filter mode.
+    pub fn with_rules(mut self, rules: &[Rule]) -> Self {
+        self.rules = CompiledRules::new(rules);
+        self
+    }
+
     fn compile_patterns(patterns: &[FilterPattern]) -> Vec<CompiledPattern> {
         patterns
             .iter()
@@ -72,6 +61,20 @@ impl CompiledFilter {
     }
 
     pub fn should_track(&self, process_name: &str) -> bool {
+        self.should_track_window(process_name, None, None)
+    }
+
+    /// Like `should_track`, but rules can also match the window title and
+    /// executable path.
+    pub fn should_track_window(
+        &self,
+        process_name: &str,
+        exe_path: Option<&str>,
+        window_title: Option<&str>,
+    ) -> bool {
+        if let Some(decision) = self.rules.decide(process_name, exe_path, window_title) {
+            return decision;
+        }
         match self.mode {
             FilterMode::Default => true,
             FilterMode::Whitelist => self.whitelist.iter().any(|p| p.matches(process_name)),
@@ -83,6 +86,7 @@ impl CompiledFilter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{MatchKind, RuleAction, RuleField};
 
     #[test]
     fn test_exact_match() {
@@ -218,4 +222,46 @@ mod tests {
         assert!(f.should_track("valid"));
         assert!(!f.should_track("invalid"));
     }
+
+    fn rule(field: RuleField, kind: MatchKind, pattern: &str, action: RuleAction) -> Rule {
+        Rule {
+            field,
+            kind,
+            pattern: pattern.to_string(),
+            action,
+            tag: None,
+            category: None,
+            process: None,
+        }
+    }
+
+    #[test]
+    fn test_rules_override_filter_mode() {
+        let rules = vec![
+            Rule {
+                process: Some("firefox".to_string()),
+                ..rule(
+                    RuleField::Title,
+                    MatchKind::Glob,
+                    "*youtube*",
+                    RuleAction::Ignore,
+                )
+            },
+            rule(
+                RuleField::Exe,
+                MatchKind::Regex,
+                "^/opt/work/",
+                RuleAction::Track,
+            ),
+        ];
+        let f = CompiledFilter::new(FilterMode::Blacklist, &[], &[]).with_rules(&rules);
+        assert!(!f.should_track_window("firefox", None, Some("Music - YouTube")));
+        assert!(f.should_track_window("firefox", None, Some("Jira")));
+        // the process restriction keeps other browsers unaffected
+        assert!(f.should_track_window("chromium", None, Some("Music - YouTube")));
+
+        let f = CompiledFilter::new(FilterMode::Whitelist, &[], &[]).with_rules(&rules);
+        assert!(f.should_track_window("tool", Some("/opt/work/bin/tool"), None));
+        assert!(!f.should_track_window("tool", Some("/usr/bin/tool"), None));
+    }
 }
@@ -0,0 +1,123 @@
+use crate::db::{format_timestamp, local_time, Database, FocusBlock};
+use crate::focus::{score, secs_until};
+use crate::platform::normalize_process_name;
+use crate::process::is_daemon_running;
+use crate::utils::{format_duration_hm, local_hm, parse_duration_hms};
+use chrono::{Duration, Local};
+use std::error::Error;
+use std::path::Path;
+
+/// Handle `laches focus`: start a block, stop the running one, or show it.
+pub fn handle_focus(
+    db: &Database,
+    config_dir: &Path,
+    duration: Option<&str>,
+    allow: &[String],
+    break_length: Option<&str>,
+    stop: bool,
+) -> Result<(), Box<dyn Error>> {
+    let now = Local::now();
+    let current = db.current_focus_block(&format_timestamp(&now))?;
+
+    if stop {
+        match current {
+            Some(block) => {
+                db.end_focus_block(block.id, &format_timestamp(&now))?;
+                let elapsed = local_time(&block.start_time).map_or(0, |t| (now - t).num_seconds());
+                println!(
+                    "info: focus block stopped after {}, {}",
+                    format_duration_hm(elapsed),
+                    score(db, &block, now)?
+                );
+            }
+            None => println!("info: no focus block running"),
+        }
+        return Ok(());
+    }
+
+    let Some(duration) = duration else {
+        return match current {
+            Some(block) => print_block(db, &block),
+            None => {
+                println!("no focus block running");
+                Ok(())
+            }
+        };
+    };
+
+    if let Some(block) = current {
+        return Err(format!(
+            "error: a focus block is already running until {}. stop it with `laches focus --stop`",
+            local_hm(block.end()).unwrap_or_default()
+        )
+        .into());
+    }
+    let length = parse_length(duration)?;
+    let break_secs = match break_length {
+        Some(b) => parse_length(b)?.num_seconds(),
+        None => 0,
+    };
+    let mut allowed: Vec<String> = Vec::new();
+    for name in allow.iter().map(|p| normalize_process_name(p.trim())) {
+        if !name.is_empty() && !allowed.contains(&name) {
+            allowed.push(name);
+        }
+    }
+    if allowed.is_empty() {
+        return Err("error: no processes to allow, use e.g. --allow code,alacritty".into());
+    }
+
+    let end = now + length;
+    db.start_focus_block(
+        &format_timestamp(&now),
+        &format_timestamp(&end),
+        &allowed,
+        break_secs,
+    )?;
+    println!(
+        "info: focus block started until {} (allowed: {})",
+        end.format("%H:%M"),
+        allowed.join(", ")
+    );
+    if !is_daemon_running(config_dir) {
+        println!(
+            "warning: laches_mon is not running, so the block won't be tracked. start it with `laches start`"
+        );
+    }
+    Ok(())
+}
+
+fn parse_length(s: &str) -> Result<Duration, Box<dyn Error>> {
+    parse_duration_hms(s).ok_or_else(|| {
+        format!(
+            "error: invalid duration '{}', use e.g. 25m, 50m or 1h30m",
+            s
+        )
+        .into()
+    })
+}
+
+fn print_block(db: &Database, block: &FocusBlock) -> Result<(), Box<dyn Error>> {
+    let now = Local::now();
+    let score = score(db, block, now)?;
+    println!(
+        "focus block: {}-{}, {} left",
+        local_hm(&block.start_time).unwrap_or_default(),
+        local_hm(block.end()).unwrap_or_default(),
+        format_duration_hm(secs_until(block.end(), now))
+    );
+    println!("allowed: {}", block.allowed.join(", "));
+    if block.break_secs > 0 {
+        println!("break: {}", format_duration_hm(block.break_secs));
+    }
+    println!("score: {}", score);
+    if !score.distractions.is_empty() {
+        let distractions: Vec<String> = score
+            .distractions
+            .iter()
+            .map(|(p, secs)| format!("{} {}", p, format_duration_hm(*secs)))
+            .collect();
+        println!("distractions: {}", distractions.join(", "));
+    }
+    Ok(())
+}
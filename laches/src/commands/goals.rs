@@ -0,0 +1,98 @@
+use crate::cli::GoalCommand;
+use crate::config::{save_config, validate_goal, Config, Goal};
+use crate::db::Database;
+use crate::goals::{progress, record, GoalProgress};
+use colored::Colorize;
+use std::error::Error;
+use std::path::Path;
+
+/// Handle `laches goals`: show progress and streaks, or change the goals
+/// in config.toml.
+pub fn handle_goals(
+    db: &Database,
+    config: &mut Config,
+    config_dir: &Path,
+    days: u32,
+    recompute: bool,
+    action: Option<&GoalCommand>,
+) -> Result<(), Box<dyn Error>> {
+    match action {
+        Some(GoalCommand::Add {
+            at_least,
+            at_most,
+            process,
+            tag,
+            days,
+        }) => {
+            let goal = Goal {
+                process: process.clone(),
+                tag: tag.clone(),
+                at_least: at_least.clone(),
+                at_most: at_most.clone(),
+                days: days.clone().into(),
+            };
+            validate_goal(&goal).map_err(|e| format!("error: {}", e))?;
+
+            if config.goals.contains(&goal) {
+                println!("goal already exists: {}", goal);
+            } else {
+                println!("added goal {}: {}", config.goals.len() + 1, goal);
+                config.goals.push(goal);
+                save_config(config, config_dir)?;
+            }
+        }
+
+        Some(GoalCommand::Remove { index }) => {
+            if *index == 0 || *index > config.goals.len() {
+                return Err(format!("error: no goal {}, see `laches goals`", index).into());
+            }
+            let goal = config.goals.remove(index - 1);
+            save_config(config, config_dir)?;
+            println!("removed goal {}: {}", index, goal);
+        }
+
+        Some(GoalCommand::Clear) => {
+            config.goals.clear();
+            save_config(config, config_dir)?;
+            println!("cleared goals");
+        }
+
+        None => {
+            if config.goals.is_empty() {
+                println!("no goals, add one with `laches goals add`");
+                return Ok(());
+            }
+            let today = chrono::Local::now().date_naive();
+            record(db, &config.goals, today, recompute)?;
+            println!("goals:");
+            for (i, goal) in config.goals.iter().enumerate() {
+                println!("  {:>2}. {}", i + 1, goal);
+                print_progress(&progress(db, goal, today, today, days)?);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_progress(p: &GoalProgress) {
+    println!("      today: {}", p.describe());
+    let days = |n: u32| format!("{} day{}", n, if n == 1 { "" } else { "s" });
+    println!(
+        "      streak: {} (best {})",
+        days(p.streak),
+        days(p.best_streak)
+    );
+    if !p.history.is_empty() {
+        let strip: String = p
+            .history
+            .iter()
+            .map(|(_, passed)| match passed {
+                Some(true) => "#".green().to_string(),
+                Some(false) => "x".red().to_string(),
+                None => ".".dimmed().to_string(),
+            })
+            .collect();
+        println!("      last {}: {}", days(p.history.len() as u32), strip);
+    }
+}
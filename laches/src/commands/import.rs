@@ -0,0 +1,407 @@
+use crate::cli::{ExportFormat, ImportSource};
+use crate::commands::export::format_from_path;
+use crate::config::{list_machine_dbs, machine_label, Rule};
+use crate::db::{format_timestamp, local_to_utc, parse_timestamp, Database, Session, SessionKind};
+use crate::importers::{self, parse_csv, Imported, ImportedSession};
+use crate::rules::CompiledRules;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+/// Invalid rows reported individually before the rest are only counted.
+const MAX_WARNINGS: usize = 10;
+
+/// Read an export file. Returns the valid sessions, oldest first, and a
+/// message for every row that was skipped.
+pub fn read_sessions(path: &Path, format: ExportFormat) -> Result<Imported, Box<dyn Error>> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| format!("error: cannot open '{}': {}", path.display(), e))?;
+    let reader = BufReader::new(file);
+
+    let rows: Vec<Map<String, Value>> = match format {
+        ExportFormat::Json => match serde_json::from_reader(reader)? {
+            Value::Array(items) => items.into_iter().map(into_object).collect(),
+            _ => return Err("error: expected a json array of sessions".into()),
+        },
+        ExportFormat::Ndjson => {
+            let mut rows = Vec::new();
+            for line in reader.lines() {
+                let line = line?;
+                if !line.trim().is_empty() {
+                    rows.push(into_object(serde_json::from_str(&line)?));
+                }
+            }
+            rows
+        }
+        ExportFormat::Csv => parse_csv(reader)?,
+        ExportFormat::Ics => {
+            return Err("error: ics exports can't be imported, use json, ndjson or csv".into())
+        }
+    };
+
+    if rows
+        .first()
+        .is_some_and(|r| !r.contains_key("start_time") && r.contains_key("total_seconds"))
+    {
+        return Err("error: this export holds aggregated totals, not sessions".into());
+    }
+
+    let mut sessions = Vec::with_capacity(rows.len());
+    let mut skipped = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        match parse_row(row) {
+            Ok(s) => sessions.push(s),
+            Err(e) => skipped.push(format!("row {}: {}", i + 1, e)),
+        }
+    }
+    sessions.sort_by(|a, b| a.session.start_time.cmp(&b.session.start_time));
+    Ok((sessions, skipped))
+}
+
+/// Import a lachesis export, or another tracker's, into the current
+/// machine's database or the one for `machine`. Sessions overlapping ones
+/// already stored are skipped and the process tags are restored.
+pub fn import_sessions(
+    db: &Database,
+    data_dir: &Path,
+    rules: &[Rule],
+    input: &str,
+    source: ImportSource,
+    format: Option<ExportFormat>,
+    machine: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    if source != ImportSource::Lachesis && format.is_some() {
+        return Err("error: --format only applies to lachesis exports".into());
+    }
+    let path = Path::new(input);
+    let (sessions, skipped) = match source {
+        ImportSource::Lachesis => {
+            read_sessions(path, format.unwrap_or_else(|| format_from_path(input)))?
+        }
+        ImportSource::Activitywatch => importers::activitywatch::read(path)?,
+        ImportSource::Wakatime => importers::wakatime::read(path)?,
+        ImportSource::Rescuetime => importers::rescuetime::read(path)?,
+    };
+
+    for warning in skipped.iter().take(MAX_WARNINGS) {
+        eprintln!("warning: skipping {}", warning);
+    }
+    if skipped.len() > MAX_WARNINGS {
+        eprintln!(
+            "warning: skipping {} more invalid rows",
+            skipped.len() - MAX_WARNINGS
+        );
+    }
+
+    let target;
+    let db = match machine {
+        Some(machine) => {
+            let path = machine_db(data_dir, machine)?;
+            println!("importing into '{}'", path.display());
+            target = Database::open(&path)?;
+            &target
+        }
+        None => db,
+    };
+
+    // tags that come from a rule are derived again at query time, so only
+    // the process's own tags are restored
+    let rules = CompiledRules::new(rules);
+    let mut tags = BTreeSet::new();
+    for imported in &sessions {
+        let s = &imported.session;
+        let from_rules = rules.tags_for(
+            &s.process_name,
+            s.exe_path.as_deref(),
+            s.window_title.as_deref(),
+        );
+        for tag in &imported.tags {
+            if !from_rules.contains(&tag.as_str()) {
+                tags.insert((s.process_name.clone(), tag.clone()));
+            }
+        }
+    }
+
+    for existing in db.get_all_tags()? {
+        tags.remove(&existing);
+    }
+
+    let rows: Vec<Session> = sessions.into_iter().map(|i| i.session).collect();
+    let imported = db.import_sessions(&rows)?;
+    for (process, tag) in &tags {
+        db.add_tag(process, tag)?;
+    }
+
+    println!(
+        "imported {} sessions ({} already present or overlapping, {} invalid)",
+        imported,
+        rows.len() - imported,
+        skipped.len()
+    );
+    if !tags.is_empty() {
+        println!("restored {} tags", tags.len());
+    }
+    Ok(())
+}
+
+/// Find the database for a machine by id or hostname label, or pick a
+/// path for a new one.
+fn machine_db(data_dir: &Path, machine: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let dbs = list_machine_dbs(data_dir)?;
+    if let Some((_, path)) = dbs.iter().find(|(id, _)| id == machine) {
+        return Ok(path.clone());
+    }
+    let by_label: Vec<&PathBuf> = dbs
+        .iter()
+        .filter(|(id, _)| machine_label(id) == machine)
+        .map(|(_, path)| path)
+        .collect();
+    match by_label.as_slice() {
+        [path] => Ok((*path).clone()),
+        [] => {
+            if machine.contains(['/', '\\']) || machine.starts_with('.') {
+                return Err(format!("error: invalid machine id '{}'", machine).into());
+            }
+            println!("info: no database for '{}', creating one", machine);
+            Ok(data_dir.join(format!("{}.db", machine)))
+        }
+        _ => Err(format!(
+            "error: '{}' matches several machines, use the full machine id",
+            machine
+        )
+        .into()),
+    }
+}
+
+fn into_object(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    }
+}
+
+/// Turn one exported row into a finished session in the stored format.
+fn parse_row(row: &Map<String, Value>) -> Result<ImportedSession, String> {
+    let text = |key: &str| -> Option<String> {
+        match row.get(key) {
+            Some(Value::String(s)) if !s.is_empty() => Some(s.clone()),
+            Some(Value::Number(n)) => Some(n.to_string()),
+            Some(Value::Bool(b)) => Some(b.to_string()),
+            _ => None,
+        }
+    };
+
+    let process_name = text("process").ok_or("missing process")?;
+    let start = text("start_time").ok_or("missing start_time")?;
+    let end = text("end_time").ok_or("session was still open when exported")?;
+
+    let utc_offset = match text("utc_offset") {
+        Some(offset) => Some(
+            offset
+                .parse::<i32>()
+                .map_err(|_| format!("invalid utc_offset '{}'", offset))?,
+        ),
+        None => None,
+    };
+    let (start_time, start_offset) = import_timestamp(&start)?;
+    let (end_time, _) = import_timestamp(&end)?;
+    if end_time < start_time {
+        return Err(format!("ends before it starts ({} > {})", start, end));
+    }
+
+    let kind = match text("kind") {
+        Some(kind) => SessionKind::parse(&kind).ok_or(format!("unknown kind '{}'", kind))?,
+        None if text("idle").as_deref() == Some("true") => SessionKind::Idle,
+        None => SessionKind::Active,
+    };
+
+    let tags = match row.get("tags") {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|t| t.as_str().map(str::to_string))
+            .collect(),
+        Some(Value::String(s)) => s.split(';').map(str::to_string).collect(),
+        _ => Vec::new(),
+    };
+    let tags = tags
+        .into_iter()
+        .map(|t: String| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    Ok(ImportedSession {
+        session: Session {
+            id: 0,
+            process_name,
+            exe_path: text("exe_path"),
+            window_title: text("window_title"),
+            start_time,
+            end_time: Some(end_time),
+            idle: kind != SessionKind::Active,
+            kind,
+            utc_offset: utc_offset.or(start_offset),
+            machine: None,
+        },
+        tags,
+    })
+}
+
+/// Normalize an exported timestamp to the stored UTC format. Timestamps
+/// ending in `Z` are UTC; ones without it come from exports made before
+/// sessions were stored in UTC and are read as local time, in which case
+/// the offset used is returned too.
+fn import_timestamp(s: &str) -> Result<(String, Option<i32>), String> {
+    let invalid = || format!("invalid timestamp '{}'", s);
+    if s.ends_with('Z') {
+        let t = parse_timestamp(s).ok_or_else(invalid)?;
+        Ok((format_timestamp(&t), None))
+    } else {
+        let (t, offset) = local_to_utc(s).ok_or_else(invalid)?;
+        Ok((format_timestamp(&t), Some(offset)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::{session_row, ExportWriter};
+    use std::io::Write;
+
+    fn session(start: &str, end: Option<&str>, title: &str) -> Session {
+        Session {
+            id: 1,
+            process_name: "code".to_string(),
+            exe_path: None,
+            window_title: Some(title.to_string()),
+            start_time: start.to_string(),
+            end_time: end.map(str::to_string),
+            idle: false,
+            kind: SessionKind::Active,
+            utc_offset: Some(3600),
+            machine: None,
+        }
+    }
+
+    fn write_export(format: ExportFormat, sessions: &[Session]) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        let columns: Vec<&str> = session_row(&sessions[0], vec![], false)
+            .iter()
+            .map(|(k, _)| *k)
+            .collect();
+        let mut w = ExportWriter::begin(file.as_file_mut(), format, &columns).unwrap();
+        for s in sessions {
+            w.write_row(&session_row(s, vec!["dev".to_string()], false))
+                .unwrap();
+        }
+        w.finish().unwrap();
+        file.flush().unwrap();
+        file
+    }
+
+    #[test]
+    fn test_round_trips_every_format() {
+        let sessions = vec![
+            session(
+                "2026-04-01T11:00:00.000Z",
+                Some("2026-04-01T11:30:00.000Z"),
+                "a \"quoted\",\nmultiline title",
+            ),
+            session(
+                "2026-04-01T10:00:00.000Z",
+                Some("2026-04-01T10:30:00.000Z"),
+                "plain",
+            ),
+        ];
+        for format in [ExportFormat::Json, ExportFormat::Ndjson, ExportFormat::Csv] {
+            let file = write_export(format, &sessions);
+            let (read, skipped) = read_sessions(file.path(), format).unwrap();
+            assert!(skipped.is_empty(), "{:?}: {:?}", format, skipped);
+            assert_eq!(read.len(), 2);
+            // sorted oldest first
+            assert_eq!(read[0].session.window_title.as_deref(), Some("plain"));
+            assert_eq!(read[1].session.window_title, sessions[0].window_title);
+            assert_eq!(read[1].session.utc_offset, Some(3600));
+            assert_eq!(read[1].tags, vec!["dev".to_string()]);
+        }
+    }
+
+    #[test]
+    fn test_invalid_rows_are_skipped() {
+        let sessions = vec![
+            session("2026-04-01T10:00:00.000Z", None, "open"),
+            session("not a time", Some("2026-04-01T10:00:00.000Z"), "bad"),
+            session(
+                "2026-04-01T10:00:00.000Z",
+                Some("2026-04-01T09:00:00.000Z"),
+                "backwards",
+            ),
+            session(
+                "2026-04-01T10:00:00.000Z",
+                Some("2026-04-01T10:00:01.000Z"),
+                "ok",
+            ),
+        ];
+        let file = write_export(ExportFormat::Ndjson, &sessions);
+        let (read, skipped) = read_sessions(file.path(), ExportFormat::Ndjson).unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(skipped.len(), 3);
+        assert!(skipped[0].starts_with("row 1: "));
+    }
+
+    #[test]
+    fn test_old_local_exports_are_converted() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            file,
+            r#"[{{"process":"code","start_time":"2026-04-01T10:00:00","end_time":"2026-04-01T10:05:00","idle":true}}]"#
+        )
+        .unwrap();
+        let (read, _) = read_sessions(file.path(), ExportFormat::Json).unwrap();
+        let s = &read[0].session;
+        assert_eq!(s.kind, SessionKind::Idle);
+        assert!(s.start_time.ends_with('Z'));
+        assert_eq!(
+            parse_timestamp(s.start_time.as_str()).map(|t| t.timestamp()),
+            local_to_utc("2026-04-01T10:00:00").map(|(t, _)| t.timestamp())
+        );
+    }
+
+    #[test]
+    fn test_import_restores_tags_and_dedupes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db = Database::open_memory().unwrap();
+        let sessions = vec![session(
+            "2026-04-01T10:00:00.000Z",
+            Some("2026-04-01T10:30:00.000Z"),
+            "x",
+        )];
+        let file = write_export(ExportFormat::Json, &sessions);
+        let input = file.path().to_str().unwrap();
+
+        import_sessions(
+            &db,
+            dir.path(),
+            &[],
+            input,
+            ImportSource::Lachesis,
+            Some(ExportFormat::Json),
+            None,
+        )
+        .unwrap();
+        import_sessions(
+            &db,
+            dir.path(),
+            &[],
+            input,
+            ImportSource::Lachesis,
+            Some(ExportFormat::Json),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(db.export_sessions(None, None).unwrap().len(), 1);
+        assert_eq!(db.get_tags("code").unwrap(), vec!["dev".to_string()]);
+    }
+}
@@ -0,0 +1,95 @@
+use crate::cli::LimitCommand;
+use crate::config::{
+    default_limit_warnings, save_config, validate_limit, Config, Limit, LimitPeriod,
+};
+use crate::db::Database;
+use crate::limits::{usage, Alert};
+use colored::Colorize;
+use std::error::Error;
+use std::path::Path;
+
+/// Handle `laches limit` subcommands, saving any change to config.toml.
+/// A running daemon picks the change up on its next poll.
+pub fn handle_limit_command(
+    db: &Database,
+    config: &mut Config,
+    config_dir: &Path,
+    command: &LimitCommand,
+) -> Result<(), Box<dyn Error>> {
+    match command {
+        LimitCommand::Add {
+            limit,
+            process,
+            tag,
+            weekly,
+            warn,
+        } => {
+            let limit = Limit {
+                process: process.clone(),
+                tag: tag.clone(),
+                period: if *weekly {
+                    LimitPeriod::Weekly
+                } else {
+                    LimitPeriod::Daily
+                },
+                limit: limit.clone(),
+                warn: if warn.is_empty() {
+                    default_limit_warnings()
+                } else {
+                    warn.clone()
+                },
+            };
+            validate_limit(&limit).map_err(|e| format!("error: {}", e))?;
+
+            if config.limits.contains(&limit) {
+                println!("limit already exists: {}", limit);
+            } else {
+                println!("added limit {}: {}", config.limits.len() + 1, limit);
+                config.limits.push(limit);
+                save_config(config, config_dir)?;
+            }
+        }
+
+        LimitCommand::Remove { index } => {
+            if *index == 0 || *index > config.limits.len() {
+                return Err(format!("error: no limit {}, see `laches limit list`", index).into());
+            }
+            let limit = config.limits.remove(index - 1);
+            save_config(config, config_dir)?;
+            println!("removed limit {}: {}", index, limit);
+        }
+
+        LimitCommand::List => {
+            if config.limits.is_empty() {
+                println!("no limits");
+                return Ok(());
+            }
+            let now = chrono::Local::now();
+            println!("limits:");
+            for (i, limit) in config.limits.iter().enumerate() {
+                println!("  {:>2}. {}", i + 1, limit);
+                let Some(usage) = usage(db, limit, now)? else {
+                    continue;
+                };
+                let line = format!(
+                    "      {} ({}%)",
+                    usage.summary(),
+                    usage.used_secs * 100 / usage.allowed_secs.max(1)
+                );
+                match usage.alert() {
+                    Some(Alert::Exceeded) => println!("{}", line.red()),
+                    Some(Alert::Warning(_)) => println!("{}", line.yellow()),
+                    None => println!("{}", line.dimmed()),
+                }
+            }
+        }
+
+        LimitCommand::Clear => {
+            config.limits.clear();
+            save_config(config, config_dir)?;
+            println!("cleared limits");
+        }
+    }
+
+    Ok(())
+}
@@ -1,6 +1,9 @@
-use crate::db::{date_range_for_day, last_n_days_range, today_range, Database};
-use crate::utils::format_uptime;
+use crate::category::{build_tree, flatten};
+use crate::config::machine_label;
+use crate::db::{date_range_for_day, last_n_days_range, today_range, Database, SessionKind};
+use crate::utils::{format_duration_hm, format_uptime, heat_char};
 use colored::Colorize;
+use std::collections::HashMap;
 use std::error::Error;
 
 /// Resolve CLI time-range flags into (start, end, label) strings.
@@ -55,9 +58,25 @@ pub fn print_process_summaries(
     label: &str,
     tag_filter: Option<&str>,
     verbose: bool,
+    by_machine: bool,
 ) -> Result<(), Box<dyn Error>> {
     let summaries = db.query_process_summaries(start, end, tag_filter)?;
 
+    // per-process "host time, host time" column, only for merged databases
+    let mut machine_breakdown: HashMap<String, Vec<String>> = HashMap::new();
+    if by_machine {
+        for m in db.query_machine_summaries(start, end, tag_filter)? {
+            machine_breakdown
+                .entry(m.process_name)
+                .or_default()
+                .push(format!(
+                    "{} {}",
+                    machine_label(&m.machine),
+                    format_uptime(m.total_seconds as u64)
+                ));
+        }
+    }
+
     if summaries.is_empty() {
         println!("no tracked data for this period.");
         return Ok(());
@@ -91,11 +110,14 @@ pub fn print_process_summaries(
         };
 
         let tags = db.get_tags(&s.process_name).unwrap_or_default();
-        let tag_str = if tags.is_empty() {
+        let mut tag_str = if tags.is_empty() {
             String::new()
         } else {
             format!(" [{}]", tags.join(", "))
         };
+        if let Some(machines) = machine_breakdown.get(&s.process_name) {
+            tag_str.push_str(&format!("  ({})", machines.join(", ")));
+        }
 
         if verbose {
             println!(
@@ -137,6 +159,131 @@ pub fn print_process_summaries(
     Ok(())
 }
 
+/// Print active time rolled up by category. Each session counts towards
+/// one category only, so the top-level totals add up to the overall total.
+pub fn print_category_tree(
+    db: &Database,
+    start: &str,
+    end: &str,
+    label: &str,
+    verbose: bool,
+) -> Result<(), Box<dyn Error>> {
+    let tree = build_tree(&db.query_category_totals(start, end)?);
+
+    if tree.is_empty() {
+        println!("no tracked data for this period.");
+        return Ok(());
+    }
+
+    let total: i64 = tree.iter().map(|n| n.total_seconds).sum();
+
+    println!("{}", format!("{} (by category)", label).bold().cyan());
+    println!();
+
+    for (depth, node) in flatten(&tree) {
+        let indent = "  ".repeat(depth);
+        let pct = if total > 0 {
+            (node.total_seconds as f64 / total as f64 * 100.0) as u32
+        } else {
+            0
+        };
+        let name = format!("{:<32}", format!("{}{}", indent, node.name));
+        let name = if depth == 0 {
+            name.bold()
+        } else {
+            name.normal()
+        };
+        println!(
+            "  {} {:>10}  {:>3}%",
+            name,
+            format_uptime(node.total_seconds as u64),
+            pct
+        );
+
+        if verbose {
+            for (process, secs) in &node.processes {
+                println!(
+                    "  {}",
+                    format!(
+                        "{:<32} {:>10}",
+                        format!("{}  - {}", indent, process),
+                        format_uptime(*secs as u64)
+                    )
+                    .dimmed()
+                );
+            }
+        }
+    }
+
+    println!();
+    println!(
+        "  {} categories, {} total",
+        tree.len(),
+        format_uptime(total as u64)
+    );
+
+    Ok(())
+}
+
+/// Print active time as an hour-of-day by weekday heatmap.
+pub fn print_heatmap(
+    db: &Database,
+    start: &str,
+    end: &str,
+    label: &str,
+) -> Result<(), Box<dyn Error>> {
+    let totals = db.query_weekday_hour_totals(start, end)?;
+    let max = totals.iter().flatten().copied().max().unwrap_or(0);
+
+    if max == 0 {
+        println!("no tracked data for this period.");
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!("{} (by hour and weekday)", label).bold().cyan()
+    );
+    println!();
+
+    let hours: String = (0..24)
+        .step_by(3)
+        .map(|h| format!("{:02}    ", h))
+        .collect();
+    println!("       {}", hours.trim_end().dimmed());
+
+    const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+    for (day, row) in WEEKDAYS.iter().zip(&totals) {
+        let cells: String = row
+            .iter()
+            .map(|secs| {
+                let cell = heat_char(*secs, max).to_string().repeat(2);
+                if *secs > 0 {
+                    cell.green().to_string()
+                } else {
+                    cell.dimmed().to_string()
+                }
+            })
+            .collect();
+        let day_total: i64 = row.iter().sum();
+        println!("  {}  {}  {:>8}", day, cells, format_duration_hm(day_total));
+    }
+
+    let (busiest_day, busiest_hour) = (0..7)
+        .flat_map(|d| (0..24).map(move |h| (d, h)))
+        .max_by_key(|&(d, h)| (totals[d][h], std::cmp::Reverse((d, h))))
+        .unwrap_or((0, 0));
+    println!();
+    println!(
+        "  busiest hour: {} {:02}:00, {} in total",
+        WEEKDAYS[busiest_day],
+        busiest_hour,
+        format_duration_hm(max)
+    );
+
+    Ok(())
+}
+
 /// Print individual sessions for a time range.
 pub fn print_sessions(
     db: &Database,
@@ -155,16 +302,18 @@ pub fn print_sessions(
     println!();
 
     for s in &sessions {
-        if s.idle {
+        // paused time is shown so the gap it leaves is explained
+        if s.idle && s.kind != SessionKind::Paused {
             continue;
         }
 
-        let start_short = s.start_time.get(11..16).unwrap_or(&s.start_time);
+        let start_short =
+            crate::utils::local_hm(&s.start_time).unwrap_or_else(|| s.start_time.clone());
         let end_short = s
             .end_time
-            .as_ref()
-            .and_then(|e| e.get(11..16))
-            .unwrap_or("now");
+            .as_deref()
+            .and_then(crate::utils::local_hm)
+            .unwrap_or_else(|| "now".to_string());
 
         let duration = if let Some(ref et) = s.end_time {
             match crate::utils::session_duration_secs(&s.start_time, et) {
@@ -177,11 +326,33 @@ pub fn print_sessions(
 
         let title = s.window_title.as_deref().unwrap_or("");
         let title_display = crate::utils::truncate_str(title, 40);
+        let machine = s
+            .machine
+            .as_deref()
+            .map(|m| format!("{:<12} ", machine_label(m)))
+            .unwrap_or_default();
+        // ids are per machine database, so only local sessions get one
+        let id = if s.machine.is_none() {
+            format!("{:>6}  ", format!("#{}", s.id))
+        } else {
+            String::new()
+        };
+
+        if s.kind == SessionKind::Paused {
+            let line = format!(
+                "  {}{}-{}  {}{:<22} {:>8}",
+                id, start_short, end_short, machine, "(paused)", duration
+            );
+            println!("{}", line.dimmed());
+            continue;
+        }
 
         println!(
-            "  {}-{}  {:<22} {:>8}  {}",
+            "  {}{}-{}  {}{:<22} {:>8}  {}",
+            id.dimmed(),
             start_short,
             end_short,
+            machine,
             s.process_name,
             duration,
             title_display.dimmed(),
@@ -197,12 +368,13 @@ pub fn print_sessions(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::local_hm;
 
     #[test]
     fn test_resolve_today() {
         let (s, e, label) = resolve_time_range(true, false, false, None, None).unwrap();
-        assert!(s.ends_with("T00:00:00"));
-        assert!(e.ends_with("T00:00:00"));
+        assert_eq!(local_hm(&s).as_deref(), Some("00:00"));
+        assert_eq!(local_hm(&e).as_deref(), Some("00:00"));
         assert_eq!(label, "today's usage");
     }
 
@@ -222,8 +394,7 @@ mod tests {
     fn test_resolve_specific_date() {
         let (s, e, label) =
             resolve_time_range(false, false, false, Some("2026-04-01"), None).unwrap();
-        assert_eq!(s, "2026-04-01T00:00:00");
-        assert_eq!(e, "2026-04-02T00:00:00");
+        assert_eq!((s, e), date_range_for_day("2026-04-01").unwrap());
         assert_eq!(label, "usage for 2026-04-01");
     }
 
@@ -231,8 +402,8 @@ mod tests {
     fn test_resolve_date_range() {
         let (s, e, label) =
             resolve_time_range(false, false, false, None, Some("2026-04-01..2026-04-03")).unwrap();
-        assert_eq!(s, "2026-04-01T00:00:00");
-        assert_eq!(e, "2026-04-04T00:00:00");
+        assert_eq!(s, date_range_for_day("2026-04-01").unwrap().0);
+        assert_eq!(e, date_range_for_day("2026-04-04").unwrap().0);
         assert_eq!(label, "2026-04-01 to 2026-04-03");
     }
 
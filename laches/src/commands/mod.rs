@@ -1,5 +1,18 @@
+pub mod api;
 pub mod autostart;
+pub mod category;
+pub mod daemon;
 pub mod data;
+pub mod edit;
+pub mod export;
 pub mod filtering;
+pub mod focus;
+pub mod goals;
+pub mod import;
+pub mod limits;
 pub mod list;
+pub mod report;
+pub mod rules;
+pub mod storage;
 pub mod summary;
+pub mod titles;
@@ -0,0 +1,84 @@
+use crate::cli::ReportFormat;
+use crate::config::{Config, RuleAction};
+use crate::db::Database;
+use crate::report::{build, Period};
+use crate::titles::TitleNormalizer;
+use chrono::{Local, NaiveDate, Utc};
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+/// Pick a report format from the output file extension, defaulting to markdown.
+pub fn format_from_path(path: &str) -> ReportFormat {
+    let ext = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match ext.as_deref() {
+        Some("html" | "htm") => ReportFormat::Html,
+        _ => ReportFormat::Markdown,
+    }
+}
+
+/// Handle `laches report`: write a report of the last week, month or a
+/// date range to a file or standard output.
+pub fn handle_report(
+    db: &Database,
+    config: &Config,
+    month: bool,
+    range: Option<&str>,
+    format: Option<ReportFormat>,
+    output: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    let today = Local::now().date_naive();
+    let period = match range {
+        Some(r) => parse_range(r)?,
+        None if month => Period::last_days(today, 30),
+        None => Period::last_days(today, 7),
+    };
+
+    // explicit tags and the ones `tag` rules attach
+    let mut tags: BTreeSet<String> = db.get_all_tags()?.into_iter().map(|(_, t)| t).collect();
+    tags.extend(
+        config
+            .rules
+            .iter()
+            .filter(|r| r.action == RuleAction::Tag)
+            .filter_map(|r| r.tag.clone()),
+    );
+    let tags: Vec<String> = tags.into_iter().collect();
+
+    let normalizer = TitleNormalizer::new(&config.title_extractors)?;
+    let report = build(db, period, &tags, &normalizer, Utc::now())?;
+    let format = format.unwrap_or_else(|| output.map_or(ReportFormat::Markdown, format_from_path));
+    let write = |mut out: &mut dyn Write| match format {
+        ReportFormat::Markdown => report.write_markdown(&mut out),
+        ReportFormat::Html => report.write_html(&mut out),
+    };
+
+    match output {
+        Some(path) => {
+            let mut out = BufWriter::new(File::create(path)?);
+            write(&mut out)?;
+            out.flush()?;
+            println!("report for {} written to '{}'", period, path);
+        }
+        None => write(&mut io::stdout().lock())?,
+    }
+    Ok(())
+}
+
+/// Parse `YYYY-MM-DD..YYYY-MM-DD` into a period.
+fn parse_range(range: &str) -> Result<Period, Box<dyn Error>> {
+    let parse = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok();
+    let Some((first, last)) = range.split_once("..") else {
+        return Err("error: range must be YYYY-MM-DD..YYYY-MM-DD".into());
+    };
+    let first = parse(first).ok_or("error: invalid start date in range")?;
+    let last = parse(last).ok_or("error: invalid end date in range")?;
+    if last < first {
+        return Err("error: range ends before it starts".into());
+    }
+    Ok(Period { first, last })
+}
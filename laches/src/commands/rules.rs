@@ -0,0 +1,98 @@
+use crate::cli::RuleCommand;
+use crate::commands::filtering::CompiledFilter;
+use crate::config::{save_config, validate_rule, Config, Rule};
+use crate::rules::CompiledRules;
+use std::error::Error;
+use std::path::Path;
+
+/// Handle `laches rule` subcommands, saving any change to config.toml.
+pub fn handle_rule_command(
+    config: &mut Config,
+    config_dir: &Path,
+    command: &RuleCommand,
+) -> Result<(), Box<dyn Error>> {
+    match command {
+        RuleCommand::Add {
+            pattern,
+            field,
+            kind,
+            action,
+            tag,
+            category,
+            process,
+        } => {
+            let rule = Rule {
+                field: field.clone().into(),
+                kind: kind.clone().into(),
+                pattern: pattern.clone(),
+                action: action.clone().into(),
+                tag: tag.clone(),
+                category: category.as_deref().and_then(crate::category::normalize),
+                process: process.clone(),
+            };
+            validate_rule(&rule).map_err(|e| format!("error: {}", e))?;
+
+            if config.rules.contains(&rule) {
+                println!("rule already exists: {}", rule);
+            } else {
+                println!("added rule {}: {}", config.rules.len() + 1, rule);
+                config.rules.push(rule);
+                save_config(config, config_dir)?;
+            }
+        }
+
+        RuleCommand::Remove { index } => {
+            if *index == 0 || *index > config.rules.len() {
+                return Err(format!("error: no rule {}, see `laches rule list`", index).into());
+            }
+            let rule = config.rules.remove(index - 1);
+            save_config(config, config_dir)?;
+            println!("removed rule {}: {}", index, rule);
+        }
+
+        RuleCommand::List => {
+            if config.rules.is_empty() {
+                println!("no rules");
+            } else {
+                println!("rules:");
+                for (i, rule) in config.rules.iter().enumerate() {
+                    println!("  {:>2}. {}", i + 1, rule);
+                }
+            }
+        }
+
+        RuleCommand::Clear => {
+            config.rules.clear();
+            save_config(config, config_dir)?;
+            println!("cleared rules");
+        }
+
+        RuleCommand::Test {
+            process,
+            title,
+            exe,
+        } => {
+            let filter = CompiledFilter::new(
+                config.filtering.mode.clone(),
+                &config.filtering.whitelist,
+                &config.filtering.blacklist,
+            )
+            .with_rules(&config.rules);
+            let tracked = filter.should_track_window(process, exe.as_deref(), title.as_deref());
+            println!("tracked: {}", if tracked { "yes" } else { "no" });
+
+            let rules = CompiledRules::new(&config.rules);
+            let tags = rules.tags_for(process, exe.as_deref(), title.as_deref());
+            if tags.is_empty() {
+                println!("tags: none");
+            } else {
+                println!("tags: {}", tags.join(", "));
+            }
+            if let Some(category) = rules.category_for(process, exe.as_deref(), title.as_deref()) {
+                println!("category: {}", category);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use crate::config::{data_dir, list_machine_dbs, save_config, Config};
+use crate::error::LachesError;
+use crate::process::is_daemon_running;
+use crate::utils::confirm;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// SQLite sidecar files that must travel with a database in WAL mode.
+const SIDECAR_SUFFIXES: [&str; 2] = ["-wal", "-shm"];
+
+/// Point `storage.data_dir` at a new directory, offering to move the
+/// existing databases there. Refuses to run while the daemon is writing.
+pub fn set_store_path(
+    config: &mut Config,
+    config_dir: &Path,
+    path: &str,
+) -> Result<(), Box<dyn Error>> {
+    let target = std::path::absolute(path)?;
+    let current = data_dir(config_dir, config);
+
+    if target == current {
+        println!("data is already stored in '{}'", target.display());
+        return Ok(());
+    }
+
+    if is_daemon_running(config_dir) {
+        return Err("error: laches_mon is running. stop it first with `laches stop`".into());
+    }
+
+    fs::create_dir_all(&target)?;
+
+    let existing = list_machine_dbs(&current)?;
+    if !existing.is_empty() {
+        let prompt = format!(
+            "move {} database(s) from '{}' to '{}'? [y/N] ",
+            existing.len(),
+            current.display(),
+            target.display()
+        );
+        if confirm(&prompt) {
+            let moved = move_databases(&current, &target)?;
+            println!("moved {} database(s)", moved);
+        } else {
+            println!(
+                "warning: existing data left in '{}' and will no longer be read",
+                current.display()
+            );
+        }
+    }
+
+    config.storage.data_dir = Some(target.clone());
+    save_config(config, config_dir)?;
+    println!("data directory set to '{}'", target.display());
+    Ok(())
+}
+
+/// Move every `.db` file (and its WAL/SHM sidecars) from `from` to `to`.
+///
+/// All destinations are checked before anything is touched so an existing
+/// file is never overwritten. Returns the number of databases moved.
+pub fn move_databases(from: &Path, to: &Path) -> Result<usize, LachesError> {
+    let dbs = list_machine_dbs(from)?;
+
+    let mut files: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for (_, db_path) in &dbs {
+        let mut candidates = vec![db_path.clone()];
+        for suffix in SIDECAR_SUFFIXES {
+            let mut sidecar = db_path.clone().into_os_string();
+            sidecar.push(suffix);
+            candidates.push(PathBuf::from(sidecar));
+        }
+
+        for src in candidates.into_iter().filter(|p| p.exists()) {
+            let name = src
+                .file_name()
+                .ok_or_else(|| LachesError::InvalidInput(format!("bad path: {}", src.display())))?;
+            let dest = to.join(name);
+            if dest.exists() {
+                return Err(LachesError::InvalidInput(format!(
+                    "'{}' already exists, refusing to overwrite",
+                    dest.display()
+                )));
+            }
+            files.push((src, dest));
+        }
+    }
+
+    for (src, dest) in &files {
+        move_file(src, dest)?;
+    }
+
+    Ok(dbs.len())
+}
+
+/// Rename a file, falling back to copy + delete when crossing filesystems.
+fn move_file(src: &Path, dest: &Path) -> Result<(), LachesError> {
+    if fs::rename(src, dest).is_ok() {
+        return Ok(());
+    }
+    fs::copy(src, dest)?;
+    fs::remove_file(src)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_move_databases_with_sidecars() {
+        let tmp = TempDir::new().unwrap();
+        let from = tmp.path().join("old");
+        let to = tmp.path().join("new");
+        fs::create_dir_all(&from).unwrap();
+        fs::create_dir_all(&to).unwrap();
+
+        fs::write(from.join("HOME_a.db"), "db").unwrap();
+        fs::write(from.join("HOME_a.db-wal"), "wal").unwrap();
+        fs::write(from.join("HOME_a.db-shm"), "shm").unwrap();
+        fs::write(from.join("WORK_b.db"), "db").unwrap();
+        fs::write(from.join("notes.txt"), "keep").unwrap();
+
+        let moved = move_databases(&from, &to).unwrap();
+        assert_eq!(moved, 2);
+
+        assert!(to.join("HOME_a.db").exists());
+        assert!(to.join("HOME_a.db-wal").exists());
+        assert!(to.join("HOME_a.db-shm").exists());
+        assert!(to.join("WORK_b.db").exists());
+        assert!(!from.join("HOME_a.db").exists());
+        assert!(!from.join("HOME_a.db-wal").exists());
+        assert!(from.join("notes.txt").exists());
+    }
+
+    #[test]
+    fn test_move_databases_refuses_to_overwrite() {
+        let tmp = TempDir::new().unwrap();
+        let from = tmp.path().join("old");
+        let to = tmp.path().join("new");
+        fs::create_dir_all(&from).unwrap();
+        fs::create_dir_all(&to).unwrap();
+
+        fs::write(from.join("HOME_a.db"), "old").unwrap();
+        fs::write(from.join("WORK_b.db"), "old").unwrap();
+        fs::write(to.join("WORK_b.db"), "existing").unwrap();
+
+        assert!(move_databases(&from, &to).is_err());
+
+        // nothing was moved
+        assert!(from.join("HOME_a.db").exists());
+        assert!(!to.join("HOME_a.db").exists());
+        assert_eq!(
+            fs::read_to_string(to.join("WORK_b.db")).unwrap(),
+            "existing"
+        );
+    }
+}
@@ -1,5 +1,8 @@
-use crate::db::{date_range_for_day, last_n_days_range, today_range, Database};
-use crate::utils::format_uptime;
+use crate::db::{
+    date_range_for_day, format_timestamp, last_n_days_range, today_range, Database, SessionKind,
+};
+use crate::focus::{score, secs_until, FocusScore};
+use crate::utils::{format_duration_hm, format_uptime, local_hm};
 use colored::Colorize;
 use std::error::Error;
 
@@ -7,7 +10,18 @@ use std::error::Error;
 pub fn print_summary(db: &Database) -> Result<(), Box<dyn Error>> {
     let (today_start, today_end) = today_range();
     let today_active = db.query_total_active_seconds(&today_start, &today_end)?;
-    let today_idle = db.query_total_idle_seconds(&today_start, &today_end)?;
+    let mut away = Vec::new();
+    for kind in [
+        SessionKind::Idle,
+        SessionKind::Locked,
+        SessionKind::Suspended,
+        SessionKind::Paused,
+    ] {
+        let secs = db.query_total_kind_seconds(&today_start, &today_end, kind)?;
+        if secs > 0 {
+            away.push(format!("{} {}", format_uptime(secs as u64), kind));
+        }
+    }
     let summaries = db.query_process_summaries(&today_start, &today_end, None)?;
 
     // yesterday
@@ -23,10 +37,10 @@ pub fn print_summary(db: &Database) -> Result<(), Box<dyn Error>> {
     let week_avg = week_total / 7;
 
     // header
-    let idle_str = if today_idle > 0 {
-        format!(" ({}idle)", format_uptime(today_idle as u64) + " ")
-    } else {
+    let idle_str = if away.is_empty() {
         String::new()
+    } else {
+        format!(" ({})", away.join(", "))
     };
     println!(
         "{}",
@@ -34,6 +48,7 @@ pub fn print_summary(db: &Database) -> Result<(), Box<dyn Error>> {
             .bold()
             .cyan()
     );
+    print_focus(db, &today_start, &today_end)?;
     println!();
 
     // top 5 processes
@@ -98,3 +113,43 @@ pub fn print_summary(db: &Database) -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+/// One line on today's focus blocks and the one running now, if any.
+fn print_focus(db: &Database, today_start: &str, today_end: &str) -> Result<(), Box<dyn Error>> {
+    let now = chrono::Local::now();
+    let running = db.current_focus_block(&format_timestamp(&now))?;
+    let mut blocks = db.query_focus_blocks(today_start, today_end)?;
+    // a block started before midnight still counts while it runs
+    if let Some(r) = running.as_ref().filter(|r| !blocks.contains(r)) {
+        blocks.insert(0, r.clone());
+    }
+    if blocks.is_empty() {
+        return Ok(());
+    }
+    let mut total = FocusScore::default();
+    let mut live = None;
+    for block in &blocks {
+        let s = score(db, block, now)?;
+        total.focused_secs += s.focused_secs;
+        total.distracted_secs += s.distracted_secs;
+        if running.as_ref().is_some_and(|r| r.id == block.id) {
+            live = Some((block, s));
+        }
+    }
+    let mut line = format!(
+        "focus: {} block{}, {}",
+        blocks.len(),
+        if blocks.len() == 1 { "" } else { "s" },
+        total
+    );
+    if let Some((block, s)) = live {
+        line.push_str(&format!(
+            "  |  running until {} ({} left): {}",
+            local_hm(block.end()).unwrap_or_default(),
+            format_duration_hm(secs_until(block.end(), now)),
+            s
+        ));
+    }
+    println!("{}", line);
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use crate::db::Database;
+use crate::titles::{group, TitleNormalizer};
+use crate::utils::{format_uptime, truncate_str};
+use colored::Colorize;
+use std::error::Error;
+
+/// Print a process's active time grouped by window title, busiest first.
+/// Without a normalizer every raw title is its own group. `range` is the
+/// (start, end, label) from `resolve_time_range`.
+pub fn print_titles(
+    db: &Database,
+    process: &str,
+    (start, end, label): (&str, &str, &str),
+    normalizer: Option<&TitleNormalizer>,
+    verbose: bool,
+    limit: usize,
+) -> Result<(), Box<dyn Error>> {
+    let groups = group(
+        &db.query_title_summaries(start, end, Some(process))?,
+        normalizer,
+    );
+
+    if groups.is_empty() {
+        println!(
+            "no window titles recorded for '{}' in this period.",
+            process
+        );
+        return Ok(());
+    }
+
+    let total: i64 = groups.iter().map(|g| g.total_seconds).sum();
+    println!(
+        "{}",
+        format!("{} - {} (by title)", process, label).bold().cyan()
+    );
+    println!();
+
+    for (i, g) in groups.iter().take(limit).enumerate() {
+        let pct = (g.total_seconds as f64 / total.max(1) as f64 * 100.0) as u32;
+        println!(
+            "  {:>2}. {:<48} {:>10}  {:>3}%",
+            i + 1,
+            truncate_str(&g.name, 48),
+            format_uptime(g.total_seconds as u64),
+            pct
+        );
+
+        if verbose {
+            for (title, secs) in &g.titles {
+                println!(
+                    "      {}",
+                    format!(
+                        "{:<48} {:>10}",
+                        truncate_str(title, 46),
+                        format_uptime(*secs as u64)
+                    )
+                    .dimmed()
+                );
+            }
+        }
+    }
+
+    println!();
+    let hidden = groups.len().saturating_sub(limit);
+    if hidden > 0 {
+        println!(
+            "  {} titles ({} more not shown), {} total",
+            groups.len(),
+            hidden,
+            format_uptime(total as u64)
+        );
+    } else {
+        println!(
+            "  {} titles, {} total",
+            groups.len(),
+            format_uptime(total as u64)
+        );
+    }
+
+    Ok(())
+}
@@ -1,8 +1,9 @@
 use crate::error::LachesError;
+use chrono::{Datelike, NaiveDate, Weekday};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 const CONFIG_NAME: &str = "config.toml";
@@ -30,6 +31,42 @@ impl fmt::Display for FilterMode {
     }
 }
 
+/// Which focus tracking backend the daemon uses on Linux.
+/// Ignored on other platforms, which only have one backend each.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackerBackend {
+    /// Pick a backend from the session environment.
+    #[default]
+    Auto,
+    /// X11 / XWayland via EWMH properties.
+    X11,
+    /// wlroots `foreign-toplevel-management` protocol.
+    Wlroots,
+    /// sway (or i3) IPC socket.
+    Sway,
+    /// Hyprland IPC socket.
+    Hyprland,
+    /// GNOME Shell extension over D-Bus.
+    Gnome,
+    /// KWin script reporting over D-Bus.
+    Kde,
+}
+
+impl fmt::Display for TrackerBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrackerBackend::Auto => write!(f, "auto"),
+            TrackerBackend::X11 => write!(f, "x11"),
+            TrackerBackend::Wlroots => write!(f, "wlroots"),
+            TrackerBackend::Sway => write!(f, "sway"),
+            TrackerBackend::Hyprland => write!(f, "hyprland"),
+            TrackerBackend::Gnome => write!(f, "gnome"),
+            TrackerBackend::Kde => write!(f, "kde"),
+        }
+    }
+}
+
 /// A filter pattern that can be either an exact string match or a regex.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct FilterPattern {
@@ -64,6 +101,116 @@ impl fmt::Display for FilterPattern {
     }
 }
 
+/// Which part of the focused window a rule matches against.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleField {
+    /// The normalized process name.
+    #[default]
+    Process,
+    /// The window title.
+    Title,
+    /// The full executable path.
+    Exe,
+}
+
+impl fmt::Display for RuleField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleField::Process => write!(f, "process"),
+            RuleField::Title => write!(f, "title"),
+            RuleField::Exe => write!(f, "exe"),
+        }
+    }
+}
+
+/// How a rule pattern is interpreted.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchKind {
+    /// Whole-string comparison.
+    #[default]
+    Exact,
+    /// Case-insensitive shell glob (`*` and `?`).
+    Glob,
+    /// Regular expression, matched anywhere in the value.
+    Regex,
+}
+
+impl fmt::Display for MatchKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatchKind::Exact => write!(f, "exact"),
+            MatchKind::Glob => write!(f, "glob"),
+            MatchKind::Regex => write!(f, "regex"),
+        }
+    }
+}
+
+/// What a matching rule does.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleAction {
+    /// Record the window, regardless of the filter mode.
+    Track,
+    /// Do not record the window, regardless of the filter mode.
+    Ignore,
+    /// Attach `tag` to matching sessions when querying.
+    Tag,
+    /// File matching sessions under `category` when querying, ahead of
+    /// the process's own category.
+    Category,
+}
+
+impl fmt::Display for RuleAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleAction::Track => write!(f, "track"),
+            RuleAction::Ignore => write!(f, "ignore"),
+            RuleAction::Tag => write!(f, "tag"),
+            RuleAction::Category => write!(f, "category"),
+        }
+    }
+}
+
+/// A rule matching on the process name, window title or executable path.
+/// Track/ignore rules are checked in order before the filter mode; tag
+/// rules derive tags for sessions at query time.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Rule {
+    #[serde(default)]
+    pub field: RuleField,
+    #[serde(default, rename = "match")]
+    pub kind: MatchKind,
+    pub pattern: String,
+    pub action: RuleAction,
+    /// Tag to attach, required for `tag` rules.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// Category path (`work/clientA/dev`), required for `category` rules.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Only apply to this process (exact name).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process: Option<String>,
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} '{}'", self.field, self.kind, self.pattern)?;
+        if let Some(process) = &self.process {
+            write!(f, " in {}", process)?;
+        }
+        match (&self.action, &self.tag, &self.category) {
+            (RuleAction::Tag, Some(tag), _) => write!(f, " -> tag '{}'", tag),
+            (RuleAction::Category, _, Some(category)) => {
+                write!(f, " -> category '{}'", category)
+            }
+            (action, _, _) => write!(f, " -> {}", action),
+        }
+    }
+}
+
 /// Top-level configuration, stored as config.toml.
 /// This is separate from the data (SQLite) -- config is small, rarely changes,
 /// and should not be mixed with time-series data.
@@ -71,6 +218,18 @@ impl fmt::Display for FilterPattern {
 pub struct Config {
     pub daemon: DaemonConfig,
     pub filtering: FilteringConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<Rule>,
+    #[serde(default)]
+    pub api: ApiConfig,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub limits: Vec<Limit>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub goals: Vec<Goal>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub title_extractors: Vec<TitleExtractor>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -79,6 +238,9 @@ pub struct DaemonConfig {
     pub check_interval: u64,
     /// Seconds of no input before the user is considered idle.
     pub idle_timeout: u64,
+    /// Focus tracking backend (Linux only). `auto` detects it at startup.
+    #[serde(default)]
+    pub backend: TrackerBackend,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -90,31 +252,267 @@ pub struct FilteringConfig {
     pub blacklist: Vec<FilterPattern>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct StorageConfig {
+    /// Directory holding the per-machine databases. Defaults to
+    /// `<config dir>/data` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_dir: Option<PathBuf>,
+}
+
+/// How often a limit's count starts over.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LimitPeriod {
+    /// Every local day.
+    #[default]
+    Daily,
+    /// Every week, starting on Monday.
+    Weekly,
+}
+
+impl fmt::Display for LimitPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitPeriod::Daily => write!(f, "daily"),
+            LimitPeriod::Weekly => write!(f, "weekly"),
+        }
+    }
+}
+
+pub(crate) fn default_limit_warnings() -> Vec<u8> {
+    vec![80]
+}
+
+/// A cap on active time for one process, one tag, or everything when
+/// neither is set. The daemon notifies at each `warn` percentage and once
+/// the limit is exceeded.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Limit {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub period: LimitPeriod,
+    /// Allowed time per period, e.g. `2h` or `1h30m`.
+    pub limit: String,
+    /// Percentages of the limit to warn at.
+    #[serde(default = "default_limit_warnings")]
+    pub warn: Vec<u8>,
+}
+
+impl Limit {
+    /// Allowed seconds per period, or None if `limit` doesn't parse.
+    pub fn allowed_secs(&self) -> Option<i64> {
+        crate::utils::parse_duration_hms(&self.limit).map(|d| d.num_seconds())
+    }
+
+    /// What the limit counts: `firefox`, `tag 'social'` or `total`.
+    pub fn target(&self) -> String {
+        describe_target(&self.process, &self.tag)
+    }
+}
+
+fn describe_target(process: &Option<String>, tag: &Option<String>) -> String {
+    match (process, tag) {
+        (Some(process), _) => process.clone(),
+        (None, Some(tag)) => format!("tag '{}'", tag),
+        (None, None) => "total".to_string(),
+    }
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} {}", self.target(), self.limit, self.period)?;
+        if !self.warn.is_empty() {
+            let warn: Vec<String> = self.warn.iter().map(|p| format!("{}%", p)).collect();
+            write!(f, ", warn at {}", warn.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Which days a goal applies to.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum GoalDays {
+    #[default]
+    Daily,
+    /// Monday to Friday.
+    Weekdays,
+    /// Saturday and Sunday.
+    Weekends,
+}
+
+impl GoalDays {
+    pub fn includes(&self, date: NaiveDate) -> bool {
+        let weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+        match self {
+            GoalDays::Daily => true,
+            GoalDays::Weekdays => !weekend,
+            GoalDays::Weekends => weekend,
+        }
+    }
+}
+
+impl fmt::Display for GoalDays {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoalDays::Daily => write!(f, "every day"),
+            GoalDays::Weekdays => write!(f, "on weekdays"),
+            GoalDays::Weekends => write!(f, "on weekends"),
+        }
+    }
+}
+
+/// A daily target for one process, one tag, or all tracked time: at
+/// least or at most so much active time on each day in `days`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Goal {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at_least: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at_most: Option<String>,
+    #[serde(default)]
+    pub days: GoalDays,
+}
+
+impl Goal {
+    /// What the goal counts, like [`Limit::target`].
+    pub fn target(&self) -> String {
+        describe_target(&self.process, &self.tag)
+    }
+
+    /// Whether the goal is a minimum (`at_least`) rather than a maximum.
+    pub fn is_minimum(&self) -> bool {
+        self.at_least.is_some()
+    }
+
+    /// The `at_least` or `at_most` time in seconds, or None if it doesn't
+    /// parse.
+    pub fn required_secs(&self) -> Option<i64> {
+        let amount = self.at_least.as_deref().or(self.at_most.as_deref())?;
+        crate::utils::parse_duration_hms(amount).map(|d| d.num_seconds())
+    }
+
+    /// Whether `secs` of active time in a day meets the goal.
+    pub fn passes(&self, secs: i64) -> bool {
+        match self.required_secs() {
+            Some(required) if self.is_minimum() => secs >= required,
+            Some(required) => secs <= required,
+            None => false,
+        }
+    }
+}
+
+impl fmt::Display for Goal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.at_least, &self.at_most) {
+            (Some(amount), _) => write!(f, "{}: at least {}", self.target(), amount)?,
+            (None, Some(amount)) => write!(f, "{}: at most {}", self.target(), amount)?,
+            (None, None) => write!(f, "{}", self.target())?,
+        }
+        write!(f, " {}", self.days)
+    }
+}
+
+/// Pulls the part of a window title worth grouping by out of it, e.g. the
+/// project and file from an editor's title. Tried in order, ahead of the
+/// built-in extractors.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TitleExtractor {
+    pub pattern: String,
+    /// What a match becomes, with `$1` or `${name}` for the pattern's
+    /// groups. Defaults to the first group, or the whole match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replace: Option<String>,
+    /// Only apply to this process. Case and a trailing `.exe` are ignored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process: Option<String>,
+}
+
+/// Port the local HTTP API listens on unless `api.port` is set.
+pub const DEFAULT_API_PORT: u16 = 7419;
+
+fn default_api_port() -> u16 {
+    DEFAULT_API_PORT
+}
+
+/// The daemon's local HTTP API. It only ever listens on the loopback
+/// interface, and every request must carry `token`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ApiConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_api_port")]
+    pub port: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_API_PORT,
+            token: None,
+        }
+    }
+}
+
+/// Generate a random API token.
+pub fn generate_api_token() -> String {
+    Uuid::new_v4().simple().to_string()
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             daemon: DaemonConfig {
                 check_interval: 2,
                 idle_timeout: 300,
+                backend: TrackerBackend::Auto,
             },
             filtering: FilteringConfig {
                 mode: FilterMode::Default,
                 whitelist: Vec::new(),
                 blacklist: Vec::new(),
             },
+            storage: StorageConfig::default(),
+            rules: Vec::new(),
+            api: ApiConfig::default(),
+            limits: Vec::new(),
+            goals: Vec::new(),
+            title_extractors: Vec::new(),
         }
     }
 }
 
+/// Path of config.toml in `config_dir`.
+pub fn config_path(config_dir: &Path) -> PathBuf {
+    config_dir.join(CONFIG_NAME)
+}
+
+/// Load and validate config from disk. Unlike `load_or_create_config`, a
+/// missing file is an error.
+pub fn load_config(config_dir: &Path) -> Result<Config, LachesError> {
+    let content = fs::read_to_string(config_path(config_dir))?;
+    let config: Config = toml::from_str(&content)?;
+    validate_config(&config)?;
+    Ok(config)
+}
+
 /// Load config from disk, or create the default if it doesn't exist.
 pub fn load_or_create_config(config_dir: &Path) -> Result<Config, LachesError> {
-    let config_path = config_dir.join(CONFIG_NAME);
+    let config_path = config_path(config_dir);
 
     if config_path.exists() {
-        let content = fs::read_to_string(&config_path)?;
-        let config: Config = toml::from_str(&content)?;
-        validate_config(&config)?;
-        Ok(config)
+        load_config(config_dir)
     } else {
         let config = Config::default();
         save_config(&config, config_dir)?;
@@ -146,14 +544,116 @@ fn validate_config(config: &Config) -> Result<(), LachesError> {
             })?;
         }
     }
+    for rule in &config.rules {
+        validate_rule(rule)?;
+    }
+    for limit in &config.limits {
+        validate_limit(limit)?;
+    }
+    for goal in &config.goals {
+        validate_goal(goal)?;
+    }
+    for extractor in &config.title_extractors {
+        regex::Regex::new(&extractor.pattern).map_err(|e| {
+            LachesError::Config(format!("invalid regex '{}': {}", extractor.pattern, e))
+        })?;
+    }
+    if config.api.enabled && config.api.port == 0 {
+        return Err(LachesError::Config(
+            "api.port must be greater than 0".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Check that a rule's pattern compiles and tag and category rules name
+/// their target.
+pub fn validate_rule(rule: &Rule) -> Result<(), LachesError> {
+    if rule.kind == MatchKind::Regex {
+        regex::Regex::new(&rule.pattern)
+            .map_err(|e| LachesError::Config(format!("invalid regex '{}': {}", rule.pattern, e)))?;
+    }
+    if rule.action == RuleAction::Tag && rule.tag.as_deref().is_none_or(str::is_empty) {
+        return Err(LachesError::Config(format!(
+            "rule '{}' has action 'tag' but no tag",
+            rule.pattern
+        )));
+    }
+    if rule.action == RuleAction::Category
+        && rule
+            .category
+            .as_deref()
+            .and_then(crate::category::normalize)
+            .is_none()
+    {
+        return Err(LachesError::Config(format!(
+            "rule '{}' has action 'category' but no category",
+            rule.pattern
+        )));
+    }
+    Ok(())
+}
+
+/// Check that a limit has a valid length, counts at most one target, and
+/// warns at percentages below 100.
+pub fn validate_limit(limit: &Limit) -> Result<(), LachesError> {
+    if limit.process.is_some() && limit.tag.is_some() {
+        return Err(LachesError::Config(format!(
+            "limit '{}' sets both a process and a tag",
+            limit.limit
+        )));
+    }
+    if limit.allowed_secs().is_none() {
+        return Err(LachesError::Config(format!(
+            "invalid limit '{}', use e.g. 30m, 2h or 1h30m",
+            limit.limit
+        )));
+    }
+    if let Some(p) = limit.warn.iter().find(|p| **p == 0 || **p >= 100) {
+        return Err(LachesError::Config(format!(
+            "limit warnings must be between 1 and 99 percent, got {}",
+            p
+        )));
+    }
+    Ok(())
+}
+
+/// Check that a goal has exactly one valid `at_least` or `at_most` time
+/// and counts at most one target.
+pub fn validate_goal(goal: &Goal) -> Result<(), LachesError> {
+    if goal.process.is_some() && goal.tag.is_some() {
+        return Err(LachesError::Config(format!(
+            "goal '{}' sets both a process and a tag",
+            goal
+        )));
+    }
+    let amount = match (&goal.at_least, &goal.at_most) {
+        (Some(amount), None) | (None, Some(amount)) => amount,
+        _ => {
+            return Err(LachesError::Config(format!(
+                "goal '{}' needs exactly one of at_least and at_most",
+                goal
+            )))
+        }
+    };
+    if goal.required_secs().is_none() {
+        return Err(LachesError::Config(format!(
+            "invalid goal time '{}', use e.g. 30m, 4h or 1h30m",
+            amount
+        )));
+    }
     Ok(())
 }
 
+/// Write config.toml. Goes through a temporary file and a rename so the
+/// daemon, which reloads on change, never reads a half-written file.
 pub fn save_config(config: &Config, config_dir: &Path) -> Result<(), LachesError> {
     fs::create_dir_all(config_dir)?;
-    let config_path = config_dir.join(CONFIG_NAME);
+    let config_path = config_path(config_dir);
+    let tmp_path = config_path.with_extension("toml.tmp");
     let content = toml::to_string_pretty(config)?;
-    fs::write(&config_path, content)?;
+    fs::write(&tmp_path, content)?;
+    fs::rename(&tmp_path, &config_path)?;
     Ok(())
 }
 
@@ -238,13 +738,51 @@ pub fn get_machine_id(config_dir: &Path) -> String {
 }
 
 /// Get the data directory for per-machine database files.
-pub fn data_dir(config_dir: &Path) -> std::path::PathBuf {
+/// Honors `storage.data_dir` and falls back to `<config dir>/data`.
+pub fn data_dir(config_dir: &Path, config: &Config) -> PathBuf {
+    config
+        .storage
+        .data_dir
+        .clone()
+        .unwrap_or_else(|| default_data_dir(config_dir))
+}
+
+/// The data directory used when `storage.data_dir` is not set.
+pub fn default_data_dir(config_dir: &Path) -> PathBuf {
     config_dir.join("data")
 }
 
 /// Get the database path for the current machine.
-pub fn machine_db_path(config_dir: &Path, machine_id: &str) -> std::path::PathBuf {
-    data_dir(config_dir).join(format!("{}.db", machine_id))
+pub fn machine_db_path(config_dir: &Path, config: &Config, machine_id: &str) -> PathBuf {
+    data_dir(config_dir, config).join(format!("{}.db", machine_id))
+}
+
+/// List every per-machine database in the data directory as
+/// (machine id, path) pairs, sorted by machine id.
+pub fn list_machine_dbs(data_dir: &Path) -> Result<Vec<(String, PathBuf)>, LachesError> {
+    if !data_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut dbs: Vec<(String, PathBuf)> = fs::read_dir(data_dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "db"))
+        .filter_map(|p| {
+            let stem = p.file_stem()?.to_string_lossy().into_owned();
+            Some((stem, p))
+        })
+        .collect();
+    dbs.sort();
+    Ok(dbs)
+}
+
+/// Short display name for a machine id ("HOSTNAME_uuid" -> "HOSTNAME").
+pub fn machine_label(machine_id: &str) -> &str {
+    match machine_id.rsplit_once('_') {
+        Some((host, _)) if !host.is_empty() => host,
+        _ => machine_id,
+    }
 }
 
 #[cfg(test)]
@@ -283,6 +821,17 @@ mod tests {
         assert!(tmp.path().join(CONFIG_NAME).exists());
     }
 
+    #[test]
+    fn test_load_config_does_not_create() {
+        let tmp = TempDir::new().unwrap();
+        assert!(load_config(tmp.path()).is_err());
+        assert!(!config_path(tmp.path()).exists());
+
+        save_config(&Config::default(), tmp.path()).unwrap();
+        assert!(load_config(tmp.path()).is_ok());
+        assert!(!tmp.path().join("config.toml.tmp").exists());
+    }
+
     #[test]
     fn test_config_roundtrip_with_data() {
         let tmp = TempDir::new().unwrap();
@@ -294,6 +843,7 @@ mod tests {
             FilterPattern::exact("code"),
         ];
         config.daemon.idle_timeout = 600;
+        config.daemon.backend = TrackerBackend::Sway;
 
         save_config(&config, tmp.path()).unwrap();
         let loaded = load_or_create_config(tmp.path()).unwrap();
@@ -303,6 +853,7 @@ mod tests {
         assert_eq!(loaded.filtering.whitelist[0].pattern, "firefox");
         assert!(!loaded.filtering.whitelist[0].is_regex);
         assert_eq!(loaded.daemon.idle_timeout, 600);
+        assert_eq!(loaded.daemon.backend, TrackerBackend::Sway);
     }
 
     #[test]
@@ -342,17 +893,156 @@ mod tests {
         assert!(validate_config(&config).is_err());
     }
 
+    #[test]
+    fn test_limits_parse_and_validate() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(
+            tmp.path().join(CONFIG_NAME),
+            "[daemon]\ncheck_interval = 2\nidle_timeout = 300\n\n[filtering]\nmode = \"default\"\n\n\
+             [[limits]]\nprocess = \"firefox\"\nlimit = \"1h30m\"\n\n\
+             [[limits]]\ntag = \"social\"\nperiod = \"weekly\"\nlimit = \"5h\"\nwarn = [50, 90]\n",
+        )
+        .unwrap();
+        let config = load_config(tmp.path()).unwrap();
+        assert_eq!(config.limits[0].period, LimitPeriod::Daily);
+        assert_eq!(config.limits[0].allowed_secs(), Some(5400));
+        assert_eq!(
+            config.limits[0].to_string(),
+            "firefox: 1h30m daily, warn at 80%"
+        );
+        assert_eq!(
+            config.limits[1].to_string(),
+            "tag 'social': 5h weekly, warn at 50%, 90%"
+        );
+
+        let mut bad = config.limits[0].clone();
+        bad.limit = "soon".to_string();
+        assert!(validate_limit(&bad).is_err());
+        bad = config.limits[0].clone();
+        bad.tag = Some("web".to_string());
+        assert!(validate_limit(&bad).is_err());
+        bad = config.limits[0].clone();
+        bad.warn = vec![100];
+        assert!(validate_limit(&bad).is_err());
+    }
+
+    #[test]
+    fn test_goals_validate_and_apply() {
+        let dev = Goal {
+            process: None,
+            tag: Some("dev".to_string()),
+            at_least: Some("4h".to_string()),
+            at_most: None,
+            days: GoalDays::Weekdays,
+        };
+        assert!(validate_goal(&dev).is_ok());
+        assert_eq!(dev.to_string(), "tag 'dev': at least 4h on weekdays");
+        assert!(dev.passes(4 * 3600) && !dev.passes(3 * 3600));
+
+        let social = Goal {
+            at_least: None,
+            at_most: Some("1h".to_string()),
+            days: GoalDays::Daily,
+            ..dev.clone()
+        };
+        assert!(social.passes(3600) && !social.passes(3601));
+
+        let both = Goal {
+            at_most: Some("1h".to_string()),
+            ..dev.clone()
+        };
+        assert!(validate_goal(&both).is_err());
+        let neither = Goal {
+            at_least: None,
+            ..dev.clone()
+        };
+        assert!(validate_goal(&neither).is_err());
+
+        // 2026-04-03 is a friday
+        let friday = NaiveDate::from_ymd_opt(2026, 4, 3).unwrap();
+        let saturday = friday.succ_opt().unwrap();
+        assert!(GoalDays::Weekdays.includes(friday) && !GoalDays::Weekdays.includes(saturday));
+        assert!(GoalDays::Weekends.includes(saturday) && GoalDays::Daily.includes(friday));
+    }
+
     #[test]
     fn test_validate_accepts_valid_config() {
         let config = Config::default();
         assert!(validate_config(&config).is_ok());
     }
 
+    #[test]
+    fn test_validate_rejects_invalid_title_extractor() {
+        let mut config = Config::default();
+        config.title_extractors.push(TitleExtractor {
+            pattern: "[unclosed".to_string(),
+            replace: None,
+            process: None,
+        });
+        assert!(validate_config(&config).is_err());
+    }
+
     #[test]
     fn test_machine_db_path() {
         let tmp = TempDir::new().unwrap();
-        let path = machine_db_path(tmp.path(), "IBBY_abc123");
+        let path = machine_db_path(tmp.path(), &Config::default(), "IBBY_abc123");
         assert!(path.to_str().unwrap().contains("data"));
         assert!(path.to_str().unwrap().ends_with("IBBY_abc123.db"));
     }
+
+    #[test]
+    fn test_custom_data_dir() {
+        let tmp = TempDir::new().unwrap();
+        let synced = tmp.path().join("synced");
+
+        let mut config = Config::default();
+        config.storage.data_dir = Some(synced.clone());
+        save_config(&config, tmp.path()).unwrap();
+
+        let loaded = load_or_create_config(tmp.path()).unwrap();
+        assert_eq!(data_dir(tmp.path(), &loaded), synced);
+        assert_eq!(
+            machine_db_path(tmp.path(), &loaded, "IBBY_abc123"),
+            synced.join("IBBY_abc123.db")
+        );
+    }
+
+    #[test]
+    fn test_config_without_storage_section_loads() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(
+            tmp.path().join(CONFIG_NAME),
+            "[daemon]\ncheck_interval = 2\nidle_timeout = 300\n\n[filtering]\nmode = \"default\"\n",
+        )
+        .unwrap();
+
+        let loaded = load_or_create_config(tmp.path()).unwrap();
+        assert!(loaded.storage.data_dir.is_none());
+        assert_eq!(loaded.daemon.backend, TrackerBackend::Auto);
+        assert_eq!(loaded.api, ApiConfig::default());
+        assert_eq!(data_dir(tmp.path(), &loaded), tmp.path().join("data"));
+    }
+
+    #[test]
+    fn test_list_machine_dbs() {
+        let tmp = TempDir::new().unwrap();
+        let data = default_data_dir(tmp.path());
+        assert!(list_machine_dbs(&data).unwrap().is_empty());
+
+        fs::create_dir_all(&data).unwrap();
+        fs::write(data.join("WORK_b.db"), "").unwrap();
+        fs::write(data.join("HOME_a.db"), "").unwrap();
+        fs::write(data.join("HOME_a.db-wal"), "").unwrap();
+
+        let dbs = list_machine_dbs(&data).unwrap();
+        let ids: Vec<&str> = dbs.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["HOME_a", "WORK_b"]);
+    }
+
+    #[test]
+    fn test_machine_label() {
+        assert_eq!(machine_label("IBBY_0b5c-41d2"), "IBBY");
+        assert_eq!(machine_label("my_host_0b5c"), "my_host");
+        assert_eq!(machine_label("nouuid"), "nouuid");
+    }
 }
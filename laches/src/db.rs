@@ -1,14 +1,87 @@
-use chrono::{Local, NaiveDate};
-use rusqlite::{params, Connection, Result as SqlResult};
-use std::path::Path;
-
-const SCHEMA_VERSION: i32 = 1;
+use crate::category::UNCATEGORIZED;
+use crate::config::Rule;
+use crate::rules::CompiledRules;
+use chrono::{
+    DateTime, Datelike, Local, LocalResult, NaiveDate, NaiveDateTime, Offset, TimeZone, Timelike,
+    Utc,
+};
+use rusqlite::backup::Backup;
+use rusqlite::functions::FunctionFlags;
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension, Result as SqlResult};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+const SCHEMA_VERSION: i32 = 7;
+
+/// Session timestamps are UTC with millisecond precision. The offset the
+/// session was recorded at is kept separately in `utc_offset`.
+pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3fZ";
+
+/// Per-session duration in milliseconds, clipped to the `?1..?2` range so
+/// sessions crossing a boundary only count the part inside it. Totals sum
+/// these and divide once, so per-row rounding does not add up.
+const CLIPPED_MS_SQL: &str = "CAST(ROUND((MIN(julianday(COALESCE(end_time, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))), julianday(?2)) - MAX(julianday(start_time), julianday(?1))) * 86400000) AS INTEGER)";
+
+const CLIPPED_MS_SQL_PREFIXED: &str = "CAST(ROUND((MIN(julianday(COALESCE(s.end_time, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))), julianday(?2)) - MAX(julianday(s.start_time), julianday(?1))) * 86400000) AS INTEGER)";
+
+/// Sessions overlapping the `?1..?2` range: those starting inside it plus
+/// those started earlier that were still running at `?1`.
+const OVERLAPS_SQL: &str = "start_time < ?2 AND (start_time >= ?1 OR COALESCE(end_time, strftime('%Y-%m-%dT%H:%M:%fZ', 'now')) > ?1)";
+
+const OVERLAPS_SQL_PREFIXED: &str = "s.start_time < ?2 AND (s.start_time >= ?1 OR COALESCE(s.end_time, strftime('%Y-%m-%dT%H:%M:%fZ', 'now')) > ?1)";
+
+/// Sessions carrying tag `?3`, either on their process or derived from a
+/// tag rule.
+const TAGGED_SQL_PREFIXED: &str =
+    "(s.process_name IN (SELECT process_name FROM tags WHERE tag = ?3) \
+     OR rule_tag(?3, s.process_name, s.exe_path, s.window_title))";
+
+/// An active session's process and its (start, end), clipped to a range.
+type ActiveSpan = (String, DateTime<Utc>, DateTime<Utc>);
+
+/// What a session records. Everything but `Active` is time away from the
+/// machine and is excluded from usage totals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionKind {
+    Active,
+    Idle,
+    Locked,
+    Suspended,
+    /// Tracking was paused by the user (`laches pause`).
+    Paused,
+}
 
-pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+impl SessionKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SessionKind::Active => "active",
+            SessionKind::Idle => "idle",
+            SessionKind::Locked => "locked",
+            SessionKind::Suspended => "suspended",
+            SessionKind::Paused => "paused",
+        }
+    }
 
-const DURATION_SECS_SQL: &str = "CAST(ROUND((julianday(COALESCE(end_time, datetime('now', 'localtime'))) - julianday(start_time)) * 86400) AS INTEGER)";
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "active" => Some(SessionKind::Active),
+            "idle" => Some(SessionKind::Idle),
+            "locked" => Some(SessionKind::Locked),
+            "suspended" => Some(SessionKind::Suspended),
+            "paused" => Some(SessionKind::Paused),
+            _ => None,
+        }
+    }
+}
 
-const DURATION_SECS_SQL_PREFIXED: &str = "CAST(ROUND((julianday(COALESCE(s.end_time, datetime('now', 'localtime'))) - julianday(s.start_time)) * 86400) AS INTEGER)";
+impl fmt::Display for SessionKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
 
 /// A recorded session of focused window usage.
 #[derive(Debug, Clone)]
@@ -19,7 +92,66 @@ pub struct Session {
     pub window_title: Option<String>,
     pub start_time: String,
     pub end_time: Option<String>,
+    /// True for every kind other than `Active`.
     pub idle: bool,
+    pub kind: SessionKind,
+    /// Seconds east of UTC where the session was recorded.
+    pub utc_offset: Option<i32>,
+    /// Machine the session was recorded on. Only set for merged databases.
+    pub machine: Option<String>,
+}
+
+/// A timed focus block started with `laches focus`. Only the listed
+/// processes count as focused time while it runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FocusBlock {
+    pub id: i64,
+    pub start_time: String,
+    /// When the block is due to end.
+    pub planned_end: String,
+    /// Set when the block was stopped before `planned_end`.
+    pub end_time: Option<String>,
+    pub allowed: Vec<String>,
+    /// Length of the break that follows the block, 0 for none.
+    pub break_secs: i64,
+}
+
+impl FocusBlock {
+    /// When the block ended or will end.
+    pub fn end(&self) -> &str {
+        self.end_time.as_deref().unwrap_or(&self.planned_end)
+    }
+
+    pub fn allows(&self, process_name: &str) -> bool {
+        self.allowed.iter().any(|p| p == process_name)
+    }
+}
+
+fn map_focus_block_row(row: &rusqlite::Row) -> SqlResult<FocusBlock> {
+    Ok(FocusBlock {
+        id: row.get(0)?,
+        start_time: row.get(1)?,
+        planned_end: row.get(2)?,
+        end_time: row.get(3)?,
+        allowed: row
+            .get::<_, String>(4)?
+            .split(',')
+            .filter(|p| !p.is_empty())
+            .map(str::to_string)
+            .collect(),
+        break_secs: row.get(5)?,
+    })
+}
+
+const FOCUS_BLOCK_COLUMNS: &str = "id, start_time, planned_end, end_time, allowed, break_secs";
+
+/// A goal's recorded outcome for one local day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalResult {
+    /// Local date, `YYYY-MM-DD`.
+    pub date: String,
+    pub seconds: i64,
+    pub passed: bool,
 }
 
 /// Aggregated process usage over a time range.
@@ -31,6 +163,39 @@ pub struct ProcessSummary {
     pub active_days: i64,
 }
 
+/// Per-machine share of a process's usage (merged databases only).
+#[derive(Debug, Clone)]
+pub struct MachineSummary {
+    pub machine: String,
+    pub process_name: String,
+    pub total_seconds: i64,
+}
+
+/// Active time of one process within one category. Every session is
+/// counted in exactly one category.
+#[derive(Debug, Clone)]
+pub struct CategoryTotal {
+    pub category: String,
+    pub process_name: String,
+    pub total_seconds: i64,
+}
+
+/// Active time of one process under one window title.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TitleSummary {
+    pub process_name: String,
+    pub window_title: String,
+    pub total_seconds: i64,
+}
+
+/// Active time of one process on one local day.
+#[derive(Debug, Clone)]
+pub struct DailyProcessTotal {
+    pub date: String,
+    pub process_name: String,
+    pub total_seconds: i64,
+}
+
 /// Map a database row to a Session struct.
 /// Used by all session-returning queries to avoid duplication.
 fn map_session_row(row: &rusqlite::Row) -> SqlResult<Session> {
@@ -42,12 +207,17 @@ fn map_session_row(row: &rusqlite::Row) -> SqlResult<Session> {
         start_time: row.get(4)?,
         end_time: row.get(5)?,
         idle: row.get::<_, i32>(6)? != 0,
+        machine: row.get(7)?,
+        kind: SessionKind::parse(&row.get::<_, String>(8)?).unwrap_or(SessionKind::Idle),
+        utc_offset: row.get(9)?,
     })
 }
 
 /// Owns a SQLite connection and provides all data operations.
 pub struct Database {
     conn: Connection,
+    /// Set when this is a read-only merge of several machine databases.
+    merged: bool,
 }
 
 impl Database {
@@ -56,8 +226,12 @@ impl Database {
         let conn = Connection::open(path)?;
         conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA foreign_keys=ON;")?;
 
-        let db = Database { conn };
+        let db = Database {
+            conn,
+            merged: false,
+        };
         db.migrate()?;
+        db.set_rules(&[])?;
         Ok(db)
     }
 
@@ -65,11 +239,169 @@ impl Database {
     #[cfg(test)]
     pub fn open_memory() -> SqlResult<Self> {
         let conn = Connection::open_in_memory()?;
-        let db = Database { conn };
+        let db = Database {
+            conn,
+            merged: false,
+        };
         db.migrate()?;
+        db.set_rules(&[])?;
         Ok(db)
     }
 
+    /// Build a read-only view over several machine databases.
+    ///
+    /// Every source is opened read-only and its sessions and tags are copied
+    /// into an in-memory database with an extra `machine` column, so all the
+    /// regular query methods aggregate across machines unchanged. Sources
+    /// from an older schema are migrated in a temporary in-memory copy;
+    /// sources from a newer one are skipped and their machine ids returned
+    /// alongside the database so the caller can warn about them.
+    pub fn open_merged(sources: &[(String, PathBuf)]) -> SqlResult<(Self, Vec<String>)> {
+        let conn = Connection::open_in_memory()?;
+        let db = Database { conn, merged: true };
+        db.migrate()?;
+        db.set_rules(&[])?;
+        db.conn
+            .execute_batch("ALTER TABLE sessions ADD COLUMN machine TEXT;")?;
+
+        let mut skipped = Vec::new();
+        for (machine, path) in sources {
+            let src = Connection::open_with_flags(
+                path,
+                OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+            )?;
+            let version: i32 = src
+                .query_row(
+                    "SELECT COALESCE(MAX(version), 0) FROM schema_version",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap_or(0);
+            if version > SCHEMA_VERSION {
+                skipped.push(machine.clone());
+                continue;
+            }
+
+            let mut copy = Connection::open_in_memory()?;
+            Backup::new(&src, &mut copy)?.run_to_completion(1024, Duration::ZERO, None)?;
+            let copy = Database {
+                conn: copy,
+                merged: false,
+            };
+            copy.migrate()?;
+            db.copy_from(&copy.conn, machine)?;
+        }
+
+        Ok((db, skipped))
+    }
+
+    /// Copy all sessions and tags from `src` into this (merged) database.
+    fn copy_from(&self, src: &Connection, machine: &str) -> SqlResult<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO sessions
+                    (process_name, exe_path, window_title, start_time, end_time, idle, kind,
+                     utc_offset, machine)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?;
+            let mut stmt = src.prepare(
+                "SELECT process_name, exe_path, window_title, start_time, end_time, idle, kind,
+                        utc_offset
+                 FROM sessions",
+            )?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                insert.execute(params![
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                    row.get::<_, i32>(5)?,
+                    row.get::<_, String>(6)?,
+                    row.get::<_, Option<i32>>(7)?,
+                    machine,
+                ])?;
+            }
+
+            let mut insert_tag =
+                tx.prepare("INSERT OR IGNORE INTO tags (process_name, tag) VALUES (?1, ?2)")?;
+            let mut stmt = src.prepare("SELECT process_name, tag FROM tags")?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                insert_tag.execute(params![row.get::<_, String>(0)?, row.get::<_, String>(1)?])?;
+            }
+
+            let mut insert_category = tx.prepare(
+                "INSERT OR IGNORE INTO categories (process_name, category) VALUES (?1, ?2)",
+            )?;
+            let mut stmt = src.prepare("SELECT process_name, category FROM categories")?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                insert_category
+                    .execute(params![row.get::<_, String>(0)?, row.get::<_, String>(1)?])?;
+            }
+        }
+        tx.commit()
+    }
+
+    /// Use `rules` when deriving tags and categories at query time. They
+    /// are exposed to SQL as `rule_tag(tag, process_name, exe_path,
+    /// window_title)` and `rule_category(process_name, exe_path,
+    /// window_title)`.
+    pub fn set_rules(&self, rules: &[Rule]) -> SqlResult<()> {
+        let rules = Arc::new(CompiledRules::new(rules));
+        let category_rules = Arc::clone(&rules);
+        self.conn.create_scalar_function(
+            "rule_category",
+            3,
+            FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+            move |ctx| {
+                let process_name: String = ctx.get(0)?;
+                let exe_path: Option<String> = ctx.get(1)?;
+                let window_title: Option<String> = ctx.get(2)?;
+                Ok(category_rules
+                    .category_for(&process_name, exe_path.as_deref(), window_title.as_deref())
+                    .map(str::to_string))
+            },
+        )?;
+        self.conn.create_scalar_function(
+            "rule_tag",
+            4,
+            FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+            move |ctx| {
+                let tag: String = ctx.get(0)?;
+                let process_name: String = ctx.get(1)?;
+                let exe_path: Option<String> = ctx.get(2)?;
+                let window_title: Option<String> = ctx.get(3)?;
+                Ok(rules.has_tag(
+                    &tag,
+                    &process_name,
+                    exe_path.as_deref(),
+                    window_title.as_deref(),
+                ))
+            },
+        )
+    }
+
+    /// Whether this database is a read-only merge of several machines.
+    pub fn is_merged(&self) -> bool {
+        self.merged
+    }
+
+    /// Column list for session-returning queries. The `machine` column
+    /// only exists in merged databases.
+    fn session_columns(&self) -> &'static str {
+        if self.merged {
+            "id, process_name, exe_path, window_title, start_time, end_time, idle, machine, kind, \
+             utc_offset"
+        } else {
+            "id, process_name, exe_path, window_title, start_time, end_time, idle, NULL, kind, \
+             utc_offset"
+        }
+    }
+
     fn migrate(&self) -> SqlResult<()> {
         self.conn.execute_batch(
             "CREATE TABLE IF NOT EXISTS schema_version (
@@ -111,6 +443,71 @@ impl Database {
             )?;
         }
 
+        if version < 2 {
+            // explicit session kinds; `idle` stays set for every non-active kind
+            self.conn.execute_batch(
+                "ALTER TABLE sessions ADD COLUMN kind TEXT NOT NULL DEFAULT 'active';
+                UPDATE sessions SET kind = 'idle' WHERE idle = 1;
+                INSERT INTO schema_version (version) VALUES (2);",
+            )?;
+        }
+
+        if version < 3 {
+            // millisecond timestamps; whole-second rows get an explicit .000
+            self.conn.execute_batch(
+                "UPDATE sessions SET start_time = start_time || '.000'
+                    WHERE length(start_time) = 19;
+                UPDATE sessions SET end_time = end_time || '.000'
+                    WHERE length(end_time) = 19;
+                INSERT INTO schema_version (version) VALUES (3);",
+            )?;
+        }
+
+        if version < 4 {
+            self.migrate_to_utc()?;
+        }
+
+        if version < 5 {
+            // one primary category per process, e.g. 'work/clientA/dev'
+            self.conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS categories (
+                    process_name TEXT PRIMARY KEY,
+                    category TEXT NOT NULL
+                );
+                INSERT INTO schema_version (version) VALUES (5);",
+            )?;
+        }
+
+        if version < 6 {
+            // `allowed` is a comma-separated list of process names
+            self.conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS focus_blocks (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    start_time TEXT NOT NULL,
+                    planned_end TEXT NOT NULL,
+                    end_time TEXT,
+                    allowed TEXT NOT NULL,
+                    break_secs INTEGER NOT NULL DEFAULT 0
+                );
+                INSERT INTO schema_version (version) VALUES (6);",
+            )?;
+        }
+
+        if version < 7 {
+            // one row per goal and local day it applied on; `goal` is the
+            // goal as written by its Display impl
+            self.conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS goals (
+                    goal TEXT NOT NULL,
+                    date TEXT NOT NULL,
+                    seconds INTEGER NOT NULL,
+                    passed INTEGER NOT NULL,
+                    PRIMARY KEY (goal, date)
+                );
+                INSERT INTO schema_version (version) VALUES (7);",
+            )?;
+        }
+
         if version > SCHEMA_VERSION {
             return Err(rusqlite::Error::InvalidParameterName(format!(
                 "database schema version {} is newer than supported version {}. update lachesis to open this database",
@@ -121,6 +518,45 @@ impl Database {
         Ok(())
     }
 
+    /// Schema v4: convert local timestamps to UTC and record the offset.
+    ///
+    /// Old rows carry no offset, so each is interpreted in the system time
+    /// zone as of its own date, which gets DST right for anyone who has not
+    /// changed zones since recording.
+    fn migrate_to_utc(&self) -> SqlResult<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute_batch("ALTER TABLE sessions ADD COLUMN utc_offset INTEGER;")?;
+        {
+            let mut stmt = tx.prepare("SELECT id, start_time, end_time FROM sessions")?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                    ))
+                })?
+                .collect::<SqlResult<Vec<_>>>()?;
+
+            let mut update = tx.prepare(
+                "UPDATE sessions SET start_time = ?1, end_time = ?2, utc_offset = ?3
+                 WHERE id = ?4",
+            )?;
+            for (id, start, end) in rows {
+                let Some((start, offset)) = local_to_utc(&start) else {
+                    continue;
+                };
+                let end = end
+                    .as_deref()
+                    .and_then(local_to_utc)
+                    .map(|(end, _)| format_timestamp(&end));
+                update.execute(params![format_timestamp(&start), end, offset, id])?;
+            }
+        }
+        tx.execute("INSERT INTO schema_version (version) VALUES (4)", [])?;
+        tx.commit()
+    }
+
     // -- session operations --
 
     /// Start a new session. Returns the session id.
@@ -129,30 +565,111 @@ impl Database {
         process_name: &str,
         exe_path: Option<&str>,
         window_title: Option<&str>,
-        idle: bool,
+        kind: SessionKind,
     ) -> SqlResult<i64> {
-        let now = Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+        let now = Local::now();
         self.conn.execute(
-            "INSERT INTO sessions (process_name, exe_path, window_title, start_time, idle)
-             VALUES (?1, ?2, ?3, ?4, ?5)",
-            params![process_name, exe_path, window_title, now, idle as i32],
+            "INSERT INTO sessions
+                (process_name, exe_path, window_title, start_time, idle, kind, utc_offset)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                process_name,
+                exe_path,
+                window_title,
+                format_timestamp(&now),
+                (kind != SessionKind::Active) as i32,
+                kind.as_str(),
+                now.offset().local_minus_utc()
+            ],
         )?;
         Ok(self.conn.last_insert_rowid())
     }
 
+    /// Record an already finished session, e.g. a suspend noticed on resume.
+    pub fn insert_session(
+        &self,
+        process_name: &str,
+        kind: SessionKind,
+        start_time: &str,
+        end_time: &str,
+    ) -> SqlResult<i64> {
+        let utc_offset = parse_timestamp(start_time).map(|t| {
+            Local
+                .offset_from_utc_datetime(&t.naive_utc())
+                .local_minus_utc()
+        });
+        insert_row(
+            &self.conn,
+            &Session {
+                id: 0,
+                process_name: process_name.to_string(),
+                exe_path: None,
+                window_title: None,
+                start_time: start_time.to_string(),
+                end_time: Some(end_time.to_string()),
+                idle: kind != SessionKind::Active,
+                kind,
+                utc_offset,
+                machine: None,
+            },
+        )
+    }
+
+    /// Insert finished sessions from an import, skipping any that overlap
+    /// a session already in the database, including ones inserted earlier
+    /// in the same call. Timestamps must already be in the stored format.
+    /// Returns how many were inserted.
+    pub fn import_sessions(&self, sessions: &[Session]) -> SqlResult<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut inserted = 0;
+        {
+            let mut overlapping = tx.prepare(
+                "SELECT EXISTS(
+                    SELECT 1 FROM sessions
+                    WHERE start_time = ?1
+                       OR (start_time < ?2
+                           AND COALESCE(end_time, strftime('%Y-%m-%dT%H:%M:%fZ', 'now')) > ?1))",
+            )?;
+            for s in sessions {
+                let end = s.end_time.as_deref().unwrap_or(&s.start_time);
+                let exists: bool =
+                    overlapping.query_row(params![s.start_time, end], |row| row.get(0))?;
+                if exists {
+                    continue;
+                }
+                insert_row(
+                    &tx,
+                    &Session {
+                        end_time: Some(end.to_string()),
+                        ..s.clone()
+                    },
+                )?;
+                inserted += 1;
+            }
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+
     /// End a session by setting its end_time to now.
     pub fn end_session(&self, session_id: i64) -> SqlResult<()> {
-        let now = Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+        self.end_session_at(session_id, &format_timestamp(&Utc::now()))
+    }
+
+    /// End a session at a given time, e.g. the moment the machine suspended.
+    /// The end is clamped so it never precedes the session's start.
+    pub fn end_session_at(&self, session_id: i64, end_time: &str) -> SqlResult<()> {
         self.conn.execute(
-            "UPDATE sessions SET end_time = ?1 WHERE id = ?2 AND end_time IS NULL",
-            params![now, session_id],
+            "UPDATE sessions SET end_time = MAX(start_time, ?1)
+             WHERE id = ?2 AND end_time IS NULL",
+            params![end_time, session_id],
         )?;
         Ok(())
     }
 
     /// Close all open sessions (used on daemon shutdown).
     pub fn close_all_open_sessions(&self) -> SqlResult<usize> {
-        let now = Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+        let now = format_timestamp(&Utc::now());
         let count = self.conn.execute(
             "UPDATE sessions SET end_time = ?1 WHERE end_time IS NULL",
             params![now],
@@ -162,10 +679,10 @@ impl Database {
 
     /// Get the currently open session (if any).
     pub fn get_open_session(&self) -> SqlResult<Option<Session>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, process_name, exe_path, window_title, start_time, end_time, idle
-             FROM sessions WHERE end_time IS NULL LIMIT 1",
-        )?;
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM sessions WHERE end_time IS NULL LIMIT 1",
+            self.session_columns()
+        ))?;
         let mut rows = stmt.query_map([], map_session_row)?;
         match rows.next() {
             Some(Ok(session)) => Ok(Some(session)),
@@ -174,6 +691,141 @@ impl Database {
         }
     }
 
+    /// Get one session by id.
+    pub fn get_session(&self, id: i64) -> SqlResult<Option<Session>> {
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM sessions WHERE id = ?1",
+                    self.session_columns()
+                ),
+                [id],
+                map_session_row,
+            )
+            .optional()
+    }
+
+    /// Record a session with the given fields, e.g. a manual entry. The id
+    /// and machine are ignored. Returns the new session's id.
+    pub fn add_session(&self, s: &Session) -> SqlResult<i64> {
+        insert_row(&self.conn, s)
+    }
+
+    /// Overwrite the session with id `s.id` with the other fields of `s`.
+    /// Returns false if there is no such session.
+    pub fn update_session(&self, s: &Session) -> SqlResult<bool> {
+        let changed = self.conn.execute(
+            "UPDATE sessions
+             SET process_name = ?1, exe_path = ?2, window_title = ?3, start_time = ?4,
+                 end_time = ?5, idle = ?6, kind = ?7, utc_offset = ?8
+             WHERE id = ?9",
+            params![
+                s.process_name,
+                s.exe_path,
+                s.window_title,
+                s.start_time,
+                s.end_time,
+                (s.kind != SessionKind::Active) as i32,
+                s.kind.as_str(),
+                s.utc_offset,
+                s.id
+            ],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Delete one session. Returns false if there is no such session.
+    pub fn delete_session(&self, id: i64) -> SqlResult<bool> {
+        Ok(self
+            .conn
+            .execute("DELETE FROM sessions WHERE id = ?1", [id])?
+            > 0)
+    }
+
+    /// Run `f` in a transaction that is committed only if it returns Ok,
+    /// so edits spanning several sessions apply all or nothing.
+    pub fn transaction<T, E: From<rusqlite::Error>>(
+        &self,
+        f: impl FnOnce(&Self) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let tx = self.conn.unchecked_transaction()?;
+        let value = f(self)?;
+        tx.commit()?;
+        Ok(value)
+    }
+
+    /// Record a focus block. Returns its id.
+    pub fn start_focus_block(
+        &self,
+        start_time: &str,
+        planned_end: &str,
+        allowed: &[String],
+        break_secs: i64,
+    ) -> SqlResult<i64> {
+        self.conn.execute(
+            "INSERT INTO focus_blocks (start_time, planned_end, allowed, break_secs)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![start_time, planned_end, allowed.join(","), break_secs],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn get_focus_block(&self, id: i64) -> SqlResult<Option<FocusBlock>> {
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM focus_blocks WHERE id = ?1",
+                    FOCUS_BLOCK_COLUMNS
+                ),
+                [id],
+                map_focus_block_row,
+            )
+            .optional()
+    }
+
+    /// The focus block running at `now`, if any.
+    pub fn current_focus_block(&self, now: &str) -> SqlResult<Option<FocusBlock>> {
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM focus_blocks
+                     WHERE start_time <= ?1 AND COALESCE(end_time, planned_end) > ?1
+                     ORDER BY start_time DESC LIMIT 1",
+                    FOCUS_BLOCK_COLUMNS
+                ),
+                [now],
+                map_focus_block_row,
+            )
+            .optional()
+    }
+
+    /// Stop a running focus block at `end_time`. Returns false if there is
+    /// no such block or it already ended.
+    pub fn end_focus_block(&self, id: i64, end_time: &str) -> SqlResult<bool> {
+        let changed = self.conn.execute(
+            "UPDATE focus_blocks SET end_time = MAX(start_time, ?1)
+             WHERE id = ?2 AND end_time IS NULL AND planned_end > ?1",
+            params![end_time, id],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Focus blocks started in a date range, oldest first.
+    pub fn query_focus_blocks(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> SqlResult<Vec<FocusBlock>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM focus_blocks
+             WHERE start_time >= ?1 AND start_time < ?2
+             ORDER BY start_time",
+            FOCUS_BLOCK_COLUMNS
+        ))?;
+        let rows = stmt.query_map(params![start_date, end_date], map_focus_block_row)?;
+        rows.collect()
+    }
+
     /// Query process summaries for a date range, optionally filtered by tag.
     pub fn query_process_summaries(
         &self,
@@ -184,29 +836,28 @@ impl Database {
         let query = if tag_filter.is_some() {
             format!(
                 "SELECT s.process_name,
-                        SUM({}) as total_seconds,
+                        SUM({}) / 1000 as total_seconds,
                         COUNT(*) as session_count,
-                        COUNT(DISTINCT date(s.start_time)) as active_days
+                        COUNT(DISTINCT date(MAX(s.start_time, ?1), 'localtime')) as active_days
                  FROM sessions s
-                 JOIN tags t ON s.process_name = t.process_name
-                 WHERE s.start_time >= ?1 AND s.start_time < ?2
-                   AND s.idle = 0 AND t.tag = ?3
+                 WHERE {}
+                   AND s.idle = 0 AND {}
                  GROUP BY s.process_name
                  ORDER BY total_seconds DESC",
-                DURATION_SECS_SQL_PREFIXED
+                CLIPPED_MS_SQL_PREFIXED, OVERLAPS_SQL_PREFIXED, TAGGED_SQL_PREFIXED
             )
         } else {
             format!(
                 "SELECT process_name,
-                        SUM({}) as total_seconds,
+                        SUM({}) / 1000 as total_seconds,
                         COUNT(*) as session_count,
-                        COUNT(DISTINCT date(start_time)) as active_days
+                        COUNT(DISTINCT date(MAX(start_time, ?1), 'localtime')) as active_days
                  FROM sessions
-                 WHERE start_time >= ?1 AND start_time < ?2
+                 WHERE {}
                    AND idle = 0
                  GROUP BY process_name
                  ORDER BY total_seconds DESC",
-                DURATION_SECS_SQL
+                CLIPPED_MS_SQL, OVERLAPS_SQL
             )
         };
 
@@ -233,58 +884,318 @@ impl Database {
     /// Get total active (non-idle) seconds for a date range.
     pub fn query_total_active_seconds(&self, start_date: &str, end_date: &str) -> SqlResult<i64> {
         let sql = format!(
-            "SELECT COALESCE(SUM({}), 0) FROM sessions \
-             WHERE start_time >= ?1 AND start_time < ?2 AND idle = 0",
-            DURATION_SECS_SQL
+            "SELECT COALESCE(SUM({}), 0) / 1000 FROM sessions \
+             WHERE {} AND idle = 0",
+            CLIPPED_MS_SQL, OVERLAPS_SQL
         );
         self.conn
             .query_row(&sql, params![start_date, end_date], |row| row.get(0))
     }
 
+    /// Active seconds in a date range for one process, one tag, or
+    /// everything when neither is given.
+    pub fn query_target_seconds(
+        &self,
+        start_date: &str,
+        end_date: &str,
+        process: Option<&str>,
+        tag: Option<&str>,
+    ) -> SqlResult<i64> {
+        match (process, tag) {
+            (Some(process), _) => Ok(self
+                .query_process_summaries(start_date, end_date, None)?
+                .into_iter()
+                .find(|s| s.process_name == process)
+                .map_or(0, |s| s.total_seconds)),
+            (None, Some(tag)) => Ok(self
+                .query_process_summaries(start_date, end_date, Some(tag))?
+                .iter()
+                .map(|s| s.total_seconds)
+                .sum()),
+            (None, None) => self.query_total_active_seconds(start_date, end_date),
+        }
+    }
+
     /// Get total idle seconds for a date range.
     pub fn query_total_idle_seconds(&self, start_date: &str, end_date: &str) -> SqlResult<i64> {
+        self.query_total_kind_seconds(start_date, end_date, SessionKind::Idle)
+    }
+
+    /// Get total seconds of one session kind for a date range.
+    pub fn query_total_kind_seconds(
+        &self,
+        start_date: &str,
+        end_date: &str,
+        kind: SessionKind,
+    ) -> SqlResult<i64> {
         let sql = format!(
-            "SELECT COALESCE(SUM({}), 0) FROM sessions \
-             WHERE start_time >= ?1 AND start_time < ?2 AND idle = 1",
-            DURATION_SECS_SQL
+            "SELECT COALESCE(SUM({}), 0) / 1000 FROM sessions \
+             WHERE {} AND kind = ?3",
+            CLIPPED_MS_SQL, OVERLAPS_SQL
         );
         self.conn
-            .query_row(&sql, params![start_date, end_date], |row| row.get(0))
+            .query_row(&sql, params![start_date, end_date, kind.as_str()], |row| {
+                row.get(0)
+            })
     }
 
-    /// Get individual sessions for a date range.
+    /// Get individual sessions overlapping a date range. Sessions are
+    /// returned whole; callers clip them to the range if needed.
     pub fn query_sessions(&self, start_date: &str, end_date: &str) -> SqlResult<Vec<Session>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, process_name, exe_path, window_title, start_time, end_time, idle
-             FROM sessions
-             WHERE start_time >= ?1 AND start_time < ?2
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM sessions
+             WHERE {}
              ORDER BY start_time DESC",
-        )?;
+            self.session_columns(),
+            OVERLAPS_SQL
+        ))?;
 
         let rows = stmt.query_map(params![start_date, end_date], map_session_row)?;
         rows.collect()
     }
 
     /// Get daily active totals for a date range, returned as (date_label, seconds) pairs.
-    /// Uses a single aggregated query instead of per-day lookups.
+    /// Sessions are clipped to the range and split at local midnight, so a
+    /// session running past midnight counts towards both days.
     pub fn query_daily_totals(
         &self,
         start_date: &str,
         end_date: &str,
     ) -> SqlResult<Vec<(String, i64)>> {
+        let mut daily_ms: BTreeMap<NaiveDate, i64> = BTreeMap::new();
+        for ((day, _), ms) in self.daily_process_ms(start_date, end_date)? {
+            *daily_ms.entry(day).or_insert(0) += ms;
+        }
+
+        Ok(daily_ms
+            .into_iter()
+            .map(|(day, ms)| (day.format("%Y-%m-%d").to_string(), ms / 1000))
+            .collect())
+    }
+
+    /// Get active totals per local day and process for a date range, split
+    /// at local midnight like `query_daily_totals`.
+    pub fn query_daily_process_totals(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> SqlResult<Vec<DailyProcessTotal>> {
+        Ok(self
+            .daily_process_ms(start_date, end_date)?
+            .into_iter()
+            .map(|((day, process_name), ms)| DailyProcessTotal {
+                date: day.format("%Y-%m-%d").to_string(),
+                process_name,
+                total_seconds: ms / 1000,
+            })
+            .collect())
+    }
+
+    /// Get active totals per local hour of each day in a date range, as
+    /// (date_label, seconds per hour) pairs. Sessions are split at every
+    /// hour boundary, so a session from 09:30 to 11:15 counts 30, 60 and
+    /// 15 minutes towards hours 9, 10 and 11.
+    pub fn query_hourly_totals(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> SqlResult<Vec<(String, [i64; 24])>> {
+        Ok(self
+            .hourly_ms(start_date, end_date)?
+            .into_iter()
+            .map(|(day, ms)| (day.format("%Y-%m-%d").to_string(), ms.map(|ms| ms / 1000)))
+            .collect())
+    }
+
+    /// Get active totals per local weekday and hour of day for a date
+    /// range, Monday first, split at hour boundaries like
+    /// `query_hourly_totals`.
+    pub fn query_weekday_hour_totals(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> SqlResult<[[i64; 24]; 7]> {
+        let mut totals = [[0; 24]; 7];
+        for (day, ms) in self.hourly_ms(start_date, end_date)? {
+            let row = &mut totals[day.weekday().num_days_from_monday() as usize];
+            for (total, ms) in row.iter_mut().zip(ms) {
+                *total += ms;
+            }
+        }
+        Ok(totals.map(|row| row.map(|ms| ms / 1000)))
+    }
+
+    /// Active milliseconds per local day and hour for a date range.
+    fn hourly_ms(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> SqlResult<BTreeMap<NaiveDate, [i64; 24]>> {
+        let mut totals: BTreeMap<NaiveDate, [i64; 24]> = BTreeMap::new();
+        for (_, start, end) in self.active_spans(start_date, end_date)? {
+            let mut cursor = start;
+            while cursor < end {
+                let local = cursor.with_timezone(&Local);
+                // local hours start at a whole minute, even at half-hour offsets
+                let into_hour = chrono::Duration::seconds(
+                    i64::from(local.minute()) * 60 + i64::from(local.second()),
+                ) + chrono::Duration::nanoseconds(i64::from(local.nanosecond()));
+                let next_hour = (cursor - into_hour + chrono::Duration::hours(1)).min(end);
+                totals.entry(local.date_naive()).or_insert([0; 24])[local.hour() as usize] +=
+                    (next_hour - cursor).num_milliseconds();
+                cursor = next_hour;
+            }
+        }
+        Ok(totals)
+    }
+
+    /// Active milliseconds per (local day, process) for a date range.
+    fn daily_process_ms(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> SqlResult<BTreeMap<(NaiveDate, String), i64>> {
+        let mut totals = BTreeMap::new();
+        for (process_name, start, end) in self.active_spans(start_date, end_date)? {
+            let mut cursor = start;
+            while cursor < end {
+                let day = cursor.with_timezone(&Local).date_naive();
+                let next_day = day
+                    .succ_opt()
+                    .and_then(local_day_start)
+                    .map_or(end, |next| next.min(end));
+                *totals.entry((day, process_name.clone())).or_insert(0) +=
+                    (next_day - cursor).num_milliseconds();
+                cursor = next_day;
+            }
+        }
+        Ok(totals)
+    }
+
+    /// Active sessions overlapping a date range as (process, start, end),
+    /// clipped to the range. Open sessions run until now.
+    fn active_spans(&self, start_date: &str, end_date: &str) -> SqlResult<Vec<ActiveSpan>> {
+        let (Some(range_start), Some(range_end)) =
+            (parse_timestamp(start_date), parse_timestamp(end_date))
+        else {
+            return Ok(Vec::new());
+        };
+
         let sql = format!(
-            "SELECT date(start_time) as day, COALESCE(SUM({}), 0) \
-             FROM sessions \
-             WHERE start_time >= ?1 AND start_time < ?2 AND idle = 0 \
-             GROUP BY day ORDER BY day",
-            DURATION_SECS_SQL
+            "SELECT process_name, start_time, end_time FROM sessions WHERE {} AND idle = 0",
+            OVERLAPS_SQL
         );
         let mut stmt = self.conn.prepare(&sql)?;
-
         let rows = stmt.query_map(params![start_date, end_date], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
+        })?;
+
+        let now = Utc::now();
+        let mut spans = Vec::new();
+        for row in rows {
+            let (process_name, start, end) = row?;
+            let Some(start) = parse_timestamp(&start) else {
+                continue;
+            };
+            let end = end.as_deref().and_then(parse_timestamp).unwrap_or(now);
+            let (start, end) = (start.max(range_start), end.min(range_end));
+            if start < end {
+                spans.push((process_name, start, end));
+            }
+        }
+        Ok(spans)
+    }
+
+    /// Get per-machine, per-process active totals for a date range.
+    /// Only meaningful for merged databases; returns nothing otherwise.
+    pub fn query_machine_summaries(
+        &self,
+        start_date: &str,
+        end_date: &str,
+        tag_filter: Option<&str>,
+    ) -> SqlResult<Vec<MachineSummary>> {
+        if !self.merged {
+            return Ok(Vec::new());
+        }
+
+        let sql = format!(
+            "SELECT s.machine, s.process_name, SUM({}) / 1000 as total_seconds
+             FROM sessions s
+             WHERE {} AND s.idle = 0 AND (?3 IS NULL OR {})
+             GROUP BY s.machine, s.process_name
+             ORDER BY total_seconds DESC",
+            CLIPPED_MS_SQL_PREFIXED, OVERLAPS_SQL_PREFIXED, TAGGED_SQL_PREFIXED
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![start_date, end_date, tag_filter], |row| {
+            Ok(MachineSummary {
+                machine: row.get(0)?,
+                process_name: row.get(1)?,
+                total_seconds: row.get(2)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Get active totals per (category, process) for a date range. A
+    /// matching category rule wins over the process's assigned category;
+    /// sessions with neither are `uncategorized`.
+    pub fn query_category_totals(
+        &self,
+        start_date: &str,
+        end_date: &str,
+    ) -> SqlResult<Vec<CategoryTotal>> {
+        let sql = format!(
+            "SELECT COALESCE(rule_category(s.process_name, s.exe_path, s.window_title),
+                             c.category, ?3) as cat,
+                    s.process_name, SUM({}) / 1000 as total_seconds
+             FROM sessions s
+             LEFT JOIN categories c ON c.process_name = s.process_name
+             WHERE {} AND s.idle = 0
+             GROUP BY cat, s.process_name
+             ORDER BY total_seconds DESC",
+            CLIPPED_MS_SQL_PREFIXED, OVERLAPS_SQL_PREFIXED
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![start_date, end_date, UNCATEGORIZED], |row| {
+            Ok(CategoryTotal {
+                category: row.get(0)?,
+                process_name: row.get(1)?,
+                total_seconds: row.get(2)?,
+            })
         })?;
+        rows.collect()
+    }
 
+    /// Get active totals per (process, window title) for a date range,
+    /// optionally for one process. Sessions without a title are left out.
+    pub fn query_title_summaries(
+        &self,
+        start_date: &str,
+        end_date: &str,
+        process: Option<&str>,
+    ) -> SqlResult<Vec<TitleSummary>> {
+        let sql = format!(
+            "SELECT process_name, window_title, SUM({}) / 1000 as total_seconds
+             FROM sessions
+             WHERE {} AND idle = 0 AND window_title IS NOT NULL AND window_title != ''
+               AND (?3 IS NULL OR process_name = ?3)
+             GROUP BY process_name, window_title
+             ORDER BY total_seconds DESC",
+            CLIPPED_MS_SQL, OVERLAPS_SQL
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![start_date, end_date, process], |row| {
+            Ok(TitleSummary {
+                process_name: row.get(0)?,
+                window_title: row.get(1)?,
+                total_seconds: row.get(2)?,
+            })
+        })?;
         rows.collect()
     }
 
@@ -306,9 +1217,52 @@ impl Database {
         self.conn.execute_batch(
             "DELETE FROM sessions;
              DELETE FROM tags;
-             DELETE FROM schema_version;
-             INSERT INTO schema_version (version) VALUES (1);",
-        )
+             DELETE FROM categories;
+             DELETE FROM focus_blocks;
+             DELETE FROM goals;
+             DELETE FROM schema_version;",
+        )?;
+        self.conn.execute(
+            "INSERT INTO schema_version (version) VALUES (?1)",
+            params![SCHEMA_VERSION],
+        )?;
+        Ok(())
+    }
+
+    // -- goal operations --
+
+    /// Record a goal's outcome for a day, replacing any earlier one.
+    pub fn record_goal_result(&self, goal: &str, result: &GoalResult) -> SqlResult<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO goals (goal, date, seconds, passed) VALUES (?1, ?2, ?3, ?4)",
+            params![goal, result.date, result.seconds, result.passed as i32],
+        )?;
+        Ok(())
+    }
+
+    /// A goal's recorded outcomes, oldest first.
+    pub fn query_goal_results(&self, goal: &str) -> SqlResult<Vec<GoalResult>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT date, seconds, passed FROM goals WHERE goal = ?1 ORDER BY date")?;
+        let rows = stmt.query_map([goal], |row| {
+            Ok(GoalResult {
+                date: row.get(0)?,
+                seconds: row.get(1)?,
+                passed: row.get::<_, i32>(2)? != 0,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// The first and last day a goal's outcome was recorded for.
+    pub fn goal_date_range(&self, goal: &str) -> SqlResult<Option<(String, String)>> {
+        let (first, last): (Option<String>, Option<String>) = self.conn.query_row(
+            "SELECT MIN(date), MAX(date) FROM goals WHERE goal = ?1",
+            [goal],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok(first.zip(last))
     }
 
     // -- tag operations --
@@ -350,9 +1304,50 @@ impl Database {
         rows.collect()
     }
 
+    // -- category operations --
+
+    /// Set the primary category of a process, replacing any previous one.
+    pub fn set_category(&self, process_name: &str, category: &str) -> SqlResult<()> {
+        self.conn.execute(
+            "INSERT INTO categories (process_name, category) VALUES (?1, ?2)
+             ON CONFLICT(process_name) DO UPDATE SET category = excluded.category",
+            params![process_name, category],
+        )?;
+        Ok(())
+    }
+
+    /// Remove the category of a process.
+    pub fn clear_category(&self, process_name: &str) -> SqlResult<bool> {
+        let count = self.conn.execute(
+            "DELETE FROM categories WHERE process_name = ?1",
+            params![process_name],
+        )?;
+        Ok(count > 0)
+    }
+
+    /// Get the category assigned to a process.
+    pub fn get_category(&self, process_name: &str) -> SqlResult<Option<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT category FROM categories WHERE process_name = ?1")?;
+        let mut rows = stmt.query_map(params![process_name], |row| row.get(0))?;
+        rows.next().transpose()
+    }
+
+    /// List every (process_name, category) assignment.
+    pub fn get_all_categories(&self) -> SqlResult<Vec<(String, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT process_name, category FROM categories ORDER BY category, process_name",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        rows.collect()
+    }
+
     pub fn get_earliest_session_date(&self) -> SqlResult<Option<String>> {
         self.conn.query_row(
-            "SELECT date(MIN(start_time)) FROM sessions WHERE idle = 0",
+            "SELECT date(MIN(start_time), 'localtime') FROM sessions WHERE idle = 0",
             [],
             |row| row.get(0),
         )
@@ -375,35 +1370,144 @@ impl Database {
         if let (Some(start), Some(end)) = (start_date, end_date) {
             self.query_sessions(start, end)
         } else {
-            let mut stmt = self.conn.prepare(
-                "SELECT id, process_name, exe_path, window_title, start_time, end_time, idle
-                 FROM sessions ORDER BY start_time DESC",
-            )?;
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT {} FROM sessions ORDER BY start_time DESC",
+                self.session_columns()
+            ))?;
             let rows = stmt.query_map([], map_session_row)?;
             rows.collect()
         }
     }
+
+    /// Stream sessions oldest first, optionally limited to those
+    /// overlapping a date range, without loading them all into memory.
+    /// Returns the number of sessions passed to `f`.
+    pub fn for_each_session<E>(
+        &self,
+        range: Option<(&str, &str)>,
+        mut f: impl FnMut(Session) -> Result<(), E>,
+    ) -> Result<usize, E>
+    where
+        E: From<rusqlite::Error>,
+    {
+        let filter = if range.is_some() { OVERLAPS_SQL } else { "1" };
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM sessions WHERE {} ORDER BY start_time",
+            self.session_columns(),
+            filter
+        ))?;
+        let mut rows = match range {
+            Some((start, end)) => stmt.query(params![start, end])?,
+            None => stmt.query([])?,
+        };
+
+        let mut count = 0;
+        while let Some(row) = rows.next()? {
+            f(map_session_row(row)?)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+/// Format an instant as a stored (UTC) session timestamp.
+pub fn format_timestamp<Tz: TimeZone>(time: &DateTime<Tz>) -> String {
+    time.with_timezone(&Utc)
+        .format(TIMESTAMP_FORMAT)
+        .to_string()
+}
+
+/// Parse a stored session timestamp. A timestamp without the trailing `Z`
+/// is also read as UTC.
+pub fn parse_timestamp(s: &str) -> Option<DateTime<Utc>> {
+    let s = s.strip_suffix('Z').unwrap_or(s);
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .map(|t| t.and_utc())
+}
+
+/// Parse a stored session timestamp into the report time zone, for display.
+pub fn local_time(s: &str) -> Option<DateTime<Local>> {
+    parse_timestamp(s).map(|t| t.with_timezone(&Local))
+}
+
+/// Interpret a pre-v4 naive local timestamp in the system time zone.
+/// Returns the UTC instant and the offset (seconds east of UTC) used.
+pub(crate) fn local_to_utc(s: &str) -> Option<(DateTime<Utc>, i32)> {
+    let naive = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f").ok()?;
+    let local = match Local.from_local_datetime(&naive) {
+        LocalResult::Single(t) | LocalResult::Ambiguous(t, _) => t,
+        // inside a DST gap: use the offset in effect just after it
+        LocalResult::None => {
+            let offset = Local.offset_from_utc_datetime(&naive).fix();
+            Local.from_utc_datetime(&(naive - offset))
+        }
+    };
+    Some((local.with_timezone(&Utc), local.offset().local_minus_utc()))
+}
+
+/// Insert `s` as a new session row. The id and machine are ignored, and
+/// `idle` follows from the kind. Returns the new row's id.
+fn insert_row(conn: &Connection, s: &Session) -> SqlResult<i64> {
+    conn.prepare_cached(
+        "INSERT INTO sessions
+            (process_name, exe_path, window_title, start_time, end_time, idle, kind,
+             utc_offset)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    )?
+    .execute(params![
+        s.process_name,
+        s.exe_path,
+        s.window_title,
+        s.start_time,
+        s.end_time,
+        (s.kind != SessionKind::Active) as i32,
+        s.kind.as_str(),
+        s.utc_offset
+    ])?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Start of a local calendar day, as a UTC instant. Days that start inside
+/// a DST gap begin at the first valid local time.
+fn local_day_start(date: NaiveDate) -> Option<DateTime<Utc>> {
+    let midnight = date.and_hms_opt(0, 0, 0)?;
+    let start = match Local.from_local_datetime(&midnight) {
+        LocalResult::Single(t) | LocalResult::Ambiguous(t, _) => t,
+        LocalResult::None => (1..=4).find_map(|h| {
+            Local
+                .from_local_datetime(&(midnight + chrono::Duration::hours(h)))
+                .earliest()
+        })?,
+    };
+    Some(start.with_timezone(&Utc))
+}
+
+/// UTC bounds covering local days `first..=last`.
+fn local_days_range(first: NaiveDate, last: NaiveDate) -> Option<(String, String)> {
+    let start = local_day_start(first)?;
+    let end = local_day_start(last.succ_opt()?)?;
+    Some((format_timestamp(&start), format_timestamp(&end)))
 }
 
-/// Helper: get the start-of-day and start-of-next-day strings for a date.
+/// Helper: get the UTC bounds of a local day (`YYYY-MM-DD`). The day may
+/// be 23 or 25 hours long across a DST change.
 pub fn date_range_for_day(date: &str) -> Option<(String, String)> {
     let parsed = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
-    let next = parsed.succ_opt()?;
-    Some((format!("{}T00:00:00", parsed), format!("{}T00:00:00", next)))
+    local_days_range(parsed, parsed)
 }
 
 /// Helper: get date range for "today".
 pub fn today_range() -> (String, String) {
-    let today = Local::now().format("%Y-%m-%d").to_string();
-    date_range_for_day(&today).unwrap()
+    let today = Local::now().date_naive();
+    local_days_range(today, today).unwrap()
 }
 
 /// Helper: get date range for the last N days (inclusive of today).
 pub fn last_n_days_range(n: i64) -> (String, String) {
     let today = Local::now().date_naive();
     let start = today - chrono::Duration::days(n - 1);
-    let end = today + chrono::Duration::days(1);
-    (format!("{}T00:00:00", start), format!("{}T00:00:00", end))
+    local_days_range(start, today).unwrap()
 }
 
 #[cfg(test)]
@@ -421,7 +1525,7 @@ mod tests {
                 row.get(0)
             })
             .unwrap();
-        assert_eq!(version, 1);
+        assert_eq!(version, SCHEMA_VERSION);
     }
 
     #[test]
@@ -429,7 +1533,12 @@ mod tests {
         let db = Database::open_memory().unwrap();
 
         let id = db
-            .start_session("firefox", Some("/usr/bin/firefox"), Some("GitHub"), false)
+            .start_session(
+                "firefox",
+                Some("/usr/bin/firefox"),
+                Some("GitHub"),
+                SessionKind::Active,
+            )
             .unwrap();
         assert!(id > 0);
 
@@ -451,8 +1560,10 @@ mod tests {
     fn test_close_all_open_sessions() {
         let db = Database::open_memory().unwrap();
 
-        db.start_session("firefox", None, None, false).unwrap();
-        db.start_session("code", None, None, false).unwrap();
+        db.start_session("firefox", None, None, SessionKind::Active)
+            .unwrap();
+        db.start_session("code", None, None, SessionKind::Active)
+            .unwrap();
 
         let count = db.close_all_open_sessions().unwrap();
         assert_eq!(count, 2);
@@ -494,27 +1605,27 @@ mod tests {
         db.conn
             .execute(
                 "INSERT INTO sessions (process_name, start_time, end_time, idle)
-                 VALUES ('firefox', '2026-04-01T10:00:00', '2026-04-01T11:00:00', 0)",
+                 VALUES ('firefox', '2026-04-01T10:00:00.000Z', '2026-04-01T11:00:00.000Z', 0)",
                 [],
             )
             .unwrap();
         db.conn
             .execute(
                 "INSERT INTO sessions (process_name, start_time, end_time, idle)
-                 VALUES ('firefox', '2026-04-01T14:00:00', '2026-04-01T14:30:00', 0)",
+                 VALUES ('firefox', '2026-04-01T14:00:00.000Z', '2026-04-01T14:30:00.000Z', 0)",
                 [],
             )
             .unwrap();
         db.conn
             .execute(
                 "INSERT INTO sessions (process_name, start_time, end_time, idle)
-                 VALUES ('code', '2026-04-01T10:00:00', '2026-04-01T12:00:00', 0)",
+                 VALUES ('code', '2026-04-01T10:00:00.000Z', '2026-04-01T12:00:00.000Z', 0)",
                 [],
             )
             .unwrap();
 
         let summaries = db
-            .query_process_summaries("2026-04-01T00:00:00", "2026-04-02T00:00:00", None)
+            .query_process_summaries("2026-04-01T00:00:00.000Z", "2026-04-02T00:00:00.000Z", None)
             .unwrap();
 
         assert_eq!(summaries.len(), 2);
@@ -535,20 +1646,20 @@ mod tests {
         db.conn
             .execute(
                 "INSERT INTO sessions (process_name, start_time, end_time, idle)
-                 VALUES ('firefox', '2026-04-01T10:00:00', '2026-04-01T11:00:00', 0)",
+                 VALUES ('firefox', '2026-04-01T10:00:00.000Z', '2026-04-01T11:00:00.000Z', 0)",
                 [],
             )
             .unwrap();
         db.conn
             .execute(
                 "INSERT INTO sessions (process_name, start_time, end_time, idle)
-                 VALUES ('idle', '2026-04-01T11:00:00', '2026-04-01T11:30:00', 1)",
+                 VALUES ('idle', '2026-04-01T11:00:00.000Z', '2026-04-01T11:30:00.000Z', 1)",
                 [],
             )
             .unwrap();
 
         let summaries = db
-            .query_process_summaries("2026-04-01T00:00:00", "2026-04-02T00:00:00", None)
+            .query_process_summaries("2026-04-01T00:00:00.000Z", "2026-04-02T00:00:00.000Z", None)
             .unwrap();
 
         assert_eq!(summaries.len(), 1);
@@ -562,14 +1673,14 @@ mod tests {
         db.conn
             .execute(
                 "INSERT INTO sessions (process_name, start_time, end_time, idle)
-                 VALUES ('firefox', '2026-04-01T10:00:00', '2026-04-01T11:00:00', 0)",
+                 VALUES ('firefox', '2026-04-01T10:00:00.000Z', '2026-04-01T11:00:00.000Z', 0)",
                 [],
             )
             .unwrap();
         db.conn
             .execute(
                 "INSERT INTO sessions (process_name, start_time, end_time, idle)
-                 VALUES ('code', '2026-04-01T10:00:00', '2026-04-01T12:00:00', 0)",
+                 VALUES ('code', '2026-04-01T10:00:00.000Z', '2026-04-01T12:00:00.000Z', 0)",
                 [],
             )
             .unwrap();
@@ -577,13 +1688,145 @@ mod tests {
         db.add_tag("code", "work").unwrap();
 
         let summaries = db
-            .query_process_summaries("2026-04-01T00:00:00", "2026-04-02T00:00:00", Some("work"))
+            .query_process_summaries(
+                "2026-04-01T00:00:00.000Z",
+                "2026-04-02T00:00:00.000Z",
+                Some("work"),
+            )
             .unwrap();
 
         assert_eq!(summaries.len(), 1);
         assert_eq!(summaries[0].process_name, "code");
     }
 
+    #[test]
+    fn test_tag_rules_match_window_titles() {
+        use crate::config::{MatchKind, RuleAction, RuleField};
+
+        let db = Database::open_memory().unwrap();
+        for (title, start, end) in [
+            (
+                "PROJ-1 - Jira",
+                "2026-04-01T10:00:00.000Z",
+                "2026-04-01T11:00:00.000Z",
+            ),
+            (
+                "Cats - YouTube",
+                "2026-04-01T11:00:00.000Z",
+                "2026-04-01T11:30:00.000Z",
+            ),
+        ] {
+            db.conn
+                .execute(
+                    "INSERT INTO sessions (process_name, window_title, start_time, end_time, idle)
+                     VALUES ('firefox', ?1, ?2, ?3, 0)",
+                    params![title, start, end],
+                )
+                .unwrap();
+        }
+        db.set_rules(&[Rule {
+            field: RuleField::Title,
+            kind: MatchKind::Glob,
+            pattern: "*jira*".to_string(),
+            action: RuleAction::Tag,
+            tag: Some("work".to_string()),
+            category: None,
+            process: Some("firefox".to_string()),
+        }])
+        .unwrap();
+
+        let summaries = db
+            .query_process_summaries(
+                "2026-04-01T00:00:00.000Z",
+                "2026-04-02T00:00:00.000Z",
+                Some("work"),
+            )
+            .unwrap();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].total_seconds, 3600);
+        assert_eq!(summaries[0].session_count, 1);
+    }
+
+    #[test]
+    fn test_category_totals_count_each_session_once() {
+        use crate::config::{MatchKind, RuleAction, RuleField};
+
+        let db = Database::open_memory().unwrap();
+        for (process, title, start, end) in [
+            (
+                "firefox",
+                "PROJ-1 - Jira",
+                "2026-04-01T10:00:00.000Z",
+                "2026-04-01T11:00:00.000Z",
+            ),
+            (
+                "firefox",
+                "Cats - YouTube",
+                "2026-04-01T11:00:00.000Z",
+                "2026-04-01T11:30:00.000Z",
+            ),
+            (
+                "code",
+                "main.rs",
+                "2026-04-01T12:00:00.000Z",
+                "2026-04-01T14:00:00.000Z",
+            ),
+            (
+                "slack",
+                "general",
+                "2026-04-01T14:00:00.000Z",
+                "2026-04-01T14:10:00.000Z",
+            ),
+        ] {
+            db.conn
+                .execute(
+                    "INSERT INTO sessions (process_name, window_title, start_time, end_time, idle)
+                     VALUES (?1, ?2, ?3, ?4, 0)",
+                    params![process, title, start, end],
+                )
+                .unwrap();
+        }
+        db.set_category("firefox", "personal").unwrap();
+        db.set_category("code", "work/clientA/dev").unwrap();
+        db.add_tag("firefox", "work").unwrap();
+        db.set_rules(&[Rule {
+            field: RuleField::Title,
+            kind: MatchKind::Glob,
+            pattern: "*jira*".to_string(),
+            action: RuleAction::Category,
+            tag: None,
+            category: Some("work/clientA".to_string()),
+            process: None,
+        }])
+        .unwrap();
+
+        let mut totals: Vec<(String, String, i64)> = db
+            .query_category_totals("2026-04-01T00:00:00.000Z", "2026-04-02T00:00:00.000Z")
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.category, t.process_name, t.total_seconds))
+            .collect();
+        totals.sort();
+        assert_eq!(
+            totals,
+            vec![
+                ("personal".to_string(), "firefox".to_string(), 1800),
+                ("uncategorized".to_string(), "slack".to_string(), 600),
+                ("work/clientA".to_string(), "firefox".to_string(), 3600),
+                ("work/clientA/dev".to_string(), "code".to_string(), 7200),
+            ]
+        );
+
+        assert_eq!(
+            db.get_category("code").unwrap().as_deref(),
+            Some("work/clientA/dev")
+        );
+        db.set_category("code", "work").unwrap();
+        assert_eq!(db.get_category("code").unwrap().as_deref(), Some("work"));
+        assert!(db.clear_category("code").unwrap());
+        assert!(db.get_category("code").unwrap().is_none());
+    }
+
     #[test]
     fn test_delete_sessions_by_range() {
         let db = Database::open_memory().unwrap();
@@ -591,33 +1834,34 @@ mod tests {
         db.conn
             .execute(
                 "INSERT INTO sessions (process_name, start_time, end_time, idle)
-                 VALUES ('firefox', '2026-04-01T10:00:00', '2026-04-01T11:00:00', 0)",
+                 VALUES ('firefox', '2026-04-01T10:00:00.000Z', '2026-04-01T11:00:00.000Z', 0)",
                 [],
             )
             .unwrap();
         db.conn
             .execute(
                 "INSERT INTO sessions (process_name, start_time, end_time, idle)
-                 VALUES ('firefox', '2026-04-02T10:00:00', '2026-04-02T11:00:00', 0)",
+                 VALUES ('firefox', '2026-04-02T10:00:00.000Z', '2026-04-02T11:00:00.000Z', 0)",
                 [],
             )
             .unwrap();
 
         let deleted = db
-            .delete_sessions("2026-04-01T00:00:00", "2026-04-02T00:00:00")
+            .delete_sessions("2026-04-01T00:00:00.000Z", "2026-04-02T00:00:00.000Z")
             .unwrap();
         assert_eq!(deleted, 1);
 
         let all = db.export_sessions(None, None).unwrap();
         assert_eq!(all.len(), 1);
-        assert_eq!(all[0].start_time, "2026-04-02T10:00:00");
+        assert_eq!(all[0].start_time, "2026-04-02T10:00:00.000Z");
     }
 
     #[test]
     fn test_reset() {
         let db = Database::open_memory().unwrap();
 
-        db.start_session("firefox", None, None, false).unwrap();
+        db.start_session("firefox", None, None, SessionKind::Active)
+            .unwrap();
         db.add_tag("firefox", "browser").unwrap();
 
         db.reset().unwrap();
@@ -632,8 +1876,11 @@ mod tests {
     #[test]
     fn test_date_range_helpers() {
         let (start, end) = date_range_for_day("2026-04-01").unwrap();
-        assert_eq!(start, "2026-04-01T00:00:00");
-        assert_eq!(end, "2026-04-02T00:00:00");
+        assert!(start.ends_with('Z') && end.ends_with('Z'));
+        // bounds are local midnights, whatever the system zone is
+        let fmt = |s: &str| local_time(s).unwrap().format("%Y-%m-%d %H:%M").to_string();
+        assert_eq!(fmt(&start), "2026-04-01 00:00");
+        assert_eq!(fmt(&end), "2026-04-02 00:00");
 
         assert!(date_range_for_day("invalid").is_none());
     }
@@ -645,21 +1892,21 @@ mod tests {
         db.conn
             .execute(
                 "INSERT INTO sessions (process_name, start_time, end_time, idle)
-                 VALUES ('firefox', '2026-04-01T10:00:00', '2026-04-01T11:00:00', 0)",
+                 VALUES ('firefox', '2026-04-01T10:00:00.000Z', '2026-04-01T11:00:00.000Z', 0)",
                 [],
             )
             .unwrap();
         db.conn
             .execute(
                 "INSERT INTO sessions (process_name, start_time, end_time, idle)
-                 VALUES ('code', '2026-04-01T10:00:00', '2026-04-01T12:00:00', 0)",
+                 VALUES ('code', '2026-04-01T10:00:00.000Z', '2026-04-01T12:00:00.000Z', 0)",
                 [],
             )
             .unwrap();
         db.conn
             .execute(
                 "INSERT INTO sessions (process_name, start_time, end_time, idle)
-                 VALUES ('firefox', '2026-04-02T10:00:00', '2026-04-02T11:00:00', 0)",
+                 VALUES ('firefox', '2026-04-02T10:00:00.000Z', '2026-04-02T11:00:00.000Z', 0)",
                 [],
             )
             .unwrap();
@@ -675,29 +1922,342 @@ mod tests {
         db.conn
             .execute(
                 "INSERT INTO sessions (process_name, start_time, end_time, idle)
-                 VALUES ('firefox', '2026-04-01T10:00:00', '2026-04-01T11:00:00', 0)",
+                 VALUES ('firefox', '2026-04-01T10:00:00.000Z', '2026-04-01T11:00:00.000Z', 0)",
                 [],
             )
             .unwrap();
         db.conn
             .execute(
-                "INSERT INTO sessions (process_name, start_time, end_time, idle)
-                 VALUES ('idle', '2026-04-01T11:00:00', '2026-04-01T11:30:00', 1)",
+                "INSERT INTO sessions (process_name, start_time, end_time, idle, kind)
+                 VALUES ('idle', '2026-04-01T11:00:00.000Z', '2026-04-01T11:30:00.000Z', 1, 'idle')",
                 [],
             )
             .unwrap();
 
         let active = db
-            .query_total_active_seconds("2026-04-01T00:00:00", "2026-04-02T00:00:00")
+            .query_total_active_seconds("2026-04-01T00:00:00.000Z", "2026-04-02T00:00:00.000Z")
             .unwrap();
         assert_eq!(active, 3600);
 
         let idle = db
-            .query_total_idle_seconds("2026-04-01T00:00:00", "2026-04-02T00:00:00")
+            .query_total_idle_seconds("2026-04-01T00:00:00.000Z", "2026-04-02T00:00:00.000Z")
             .unwrap();
         assert_eq!(idle, 1800);
     }
 
+    #[test]
+    fn test_session_kinds_are_kept_apart() {
+        let db = Database::open_memory().unwrap();
+        db.insert_session(
+            "locked",
+            SessionKind::Locked,
+            "2026-04-01T12:00:00.000Z",
+            "2026-04-01T12:10:00.000Z",
+        )
+        .unwrap();
+        db.insert_session(
+            "suspended",
+            SessionKind::Suspended,
+            "2026-04-01T12:10:00.000Z",
+            "2026-04-01T14:10:00.000Z",
+        )
+        .unwrap();
+
+        let (start, end) = ("2026-04-01T00:00:00.000Z", "2026-04-02T00:00:00.000Z");
+        assert_eq!(db.query_total_active_seconds(start, end).unwrap(), 0);
+        assert_eq!(db.query_total_idle_seconds(start, end).unwrap(), 0);
+        assert_eq!(
+            db.query_total_kind_seconds(start, end, SessionKind::Locked)
+                .unwrap(),
+            600
+        );
+        assert_eq!(
+            db.query_total_kind_seconds(start, end, SessionKind::Suspended)
+                .unwrap(),
+            7200
+        );
+
+        let sessions = db.query_sessions(start, end).unwrap();
+        assert_eq!(sessions[0].kind, SessionKind::Suspended);
+        assert!(sessions.iter().all(|s| s.idle));
+    }
+
+    #[test]
+    fn test_paused_sessions_are_kept_apart() {
+        let db = Database::open_memory().unwrap();
+        let (start, end) = date_range_for_day("2026-04-01").unwrap();
+        let noon = local_day_start(NaiveDate::from_ymd_opt(2026, 4, 1).unwrap()).unwrap()
+            + chrono::Duration::hours(12);
+        db.insert_session(
+            "code",
+            SessionKind::Active,
+            &format_timestamp(&noon),
+            &format_timestamp(&(noon + chrono::Duration::minutes(10))),
+        )
+        .unwrap();
+        db.insert_session(
+            "paused",
+            SessionKind::Paused,
+            &format_timestamp(&(noon + chrono::Duration::minutes(10))),
+            &format_timestamp(&(noon + chrono::Duration::minutes(40))),
+        )
+        .unwrap();
+
+        assert_eq!(db.query_total_active_seconds(&start, &end).unwrap(), 600);
+        assert_eq!(db.query_total_idle_seconds(&start, &end).unwrap(), 0);
+        assert_eq!(
+            db.query_total_kind_seconds(&start, &end, SessionKind::Paused)
+                .unwrap(),
+            1800
+        );
+
+        let sessions = db.query_sessions(&start, &end).unwrap();
+        assert_eq!(sessions[0].kind, SessionKind::Paused);
+        assert!(sessions[0].idle);
+        assert!(sessions[0].utc_offset.is_some());
+    }
+
+    #[test]
+    fn test_end_session_at() {
+        let db = Database::open_memory().unwrap();
+        let id = db
+            .start_session("firefox", None, None, SessionKind::Active)
+            .unwrap();
+        let start = db.get_open_session().unwrap().unwrap().start_time;
+
+        // an end before the start collapses to an empty session
+        db.end_session_at(id, "2000-01-01T00:00:00.000Z").unwrap();
+        let session = db.export_sessions(None, None).unwrap().remove(0);
+        assert_eq!(session.end_time.as_deref(), Some(start.as_str()));
+        assert_eq!(session.kind, SessionKind::Active);
+    }
+
+    #[test]
+    fn test_import_skips_overlapping_sessions() {
+        let db = Database::open_memory().unwrap();
+        db.insert_session(
+            "code",
+            SessionKind::Active,
+            "2026-04-01T10:00:00.000Z",
+            "2026-04-01T11:00:00.000Z",
+        )
+        .unwrap();
+
+        let session = |start: &str, end: &str| Session {
+            id: 0,
+            process_name: "firefox".to_string(),
+            exe_path: None,
+            window_title: Some("docs".to_string()),
+            start_time: start.to_string(),
+            end_time: Some(end.to_string()),
+            idle: false,
+            kind: SessionKind::Active,
+            utc_offset: Some(0),
+            machine: None,
+        };
+        let imported = db
+            .import_sessions(&[
+                // overlaps the existing session
+                session("2026-04-01T10:30:00.000Z", "2026-04-01T11:30:00.000Z"),
+                // touches it without overlapping
+                session("2026-04-01T11:00:00.000Z", "2026-04-01T11:15:00.000Z"),
+                // duplicate within the import
+                session("2026-04-01T11:00:00.000Z", "2026-04-01T11:15:00.000Z"),
+                session("2026-04-01T12:00:00.000Z", "2026-04-01T12:00:00.000Z"),
+            ])
+            .unwrap();
+        assert_eq!(imported, 2);
+
+        let all = db.export_sessions(None, None).unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[1].window_title.as_deref(), Some("docs"));
+    }
+
+    #[test]
+    fn test_millisecond_totals_do_not_drift() {
+        let db = Database::open_memory().unwrap();
+        for (start, end) in [
+            ("2026-04-01T10:00:00.000Z", "2026-04-01T10:00:00.400Z"),
+            ("2026-04-01T10:00:00.400Z", "2026-04-01T10:00:00.800Z"),
+            ("2026-04-01T10:00:00.800Z", "2026-04-01T10:00:01.200Z"),
+            ("2026-04-01T10:00:01.200Z", "2026-04-01T10:00:01.600Z"),
+            ("2026-04-01T10:00:01.600Z", "2026-04-01T10:00:02.000Z"),
+        ] {
+            db.insert_session("code", SessionKind::Active, start, end)
+                .unwrap();
+        }
+
+        // each session rounds to zero seconds on its own
+        let summaries = db
+            .query_process_summaries("2026-04-01T00:00:00.000Z", "2026-04-02T00:00:00.000Z", None)
+            .unwrap();
+        assert_eq!(summaries[0].total_seconds, 2);
+        assert_eq!(summaries[0].session_count, 5);
+    }
+
+    #[test]
+    fn test_sessions_are_split_at_local_midnight() {
+        let db = Database::open_memory().unwrap();
+        let midnight = local_day_start(NaiveDate::from_ymd_opt(2026, 4, 2).unwrap()).unwrap();
+        let start = format_timestamp(&(midnight - chrono::Duration::minutes(30)));
+        let end = format_timestamp(&(midnight + chrono::Duration::minutes(90)));
+        db.insert_session("code", SessionKind::Active, &start, &end)
+            .unwrap();
+
+        let (first, _) = date_range_for_day("2026-04-01").unwrap();
+        let (_, last) = date_range_for_day("2026-04-02").unwrap();
+        assert_eq!(
+            db.query_daily_totals(&first, &last).unwrap(),
+            vec![
+                ("2026-04-01".to_string(), 1800),
+                ("2026-04-02".to_string(), 5400)
+            ]
+        );
+
+        // the second day counts only its part, even though the session started earlier
+        let (day_start, day_end) = date_range_for_day("2026-04-02").unwrap();
+        assert_eq!(
+            db.query_total_active_seconds(&day_start, &day_end).unwrap(),
+            5400
+        );
+        let summaries = db
+            .query_process_summaries(&day_start, &day_end, None)
+            .unwrap();
+        assert_eq!(summaries[0].total_seconds, 5400);
+        assert_eq!(summaries[0].active_days, 1);
+        assert_eq!(db.query_sessions(&day_start, &day_end).unwrap().len(), 1);
+
+        let (day_start, day_end) = date_range_for_day("2026-04-01").unwrap();
+        assert_eq!(
+            db.query_total_active_seconds(&day_start, &day_end).unwrap(),
+            1800
+        );
+    }
+
+    #[test]
+    fn test_sessions_are_split_at_hour_boundaries() {
+        let db = Database::open_memory().unwrap();
+        let at = |day: u32, hour: u32, minute: u32| {
+            let date = NaiveDate::from_ymd_opt(2026, 4, day).unwrap();
+            local_day_start(date).unwrap()
+                + chrono::Duration::hours(hour.into())
+                + chrono::Duration::minutes(minute.into())
+        };
+        // thursday 09:30 to 11:15, and across midnight into friday
+        for (start, end) in [(at(2, 9, 30), at(2, 11, 15)), (at(2, 23, 40), at(3, 0, 20))] {
+            db.insert_session(
+                "code",
+                SessionKind::Active,
+                &format_timestamp(&start),
+                &format_timestamp(&end),
+            )
+            .unwrap();
+        }
+        // the following thursday, same hour
+        db.insert_session(
+            "code",
+            SessionKind::Active,
+            &format_timestamp(&at(9, 10, 0)),
+            &format_timestamp(&at(9, 10, 30)),
+        )
+        .unwrap();
+        db.insert_session(
+            "code",
+            SessionKind::Idle,
+            &format_timestamp(&at(2, 12, 0)),
+            &format_timestamp(&at(2, 13, 0)),
+        )
+        .unwrap();
+
+        let (first, _) = date_range_for_day("2026-04-02").unwrap();
+        let (_, last) = date_range_for_day("2026-04-09").unwrap();
+        let hourly = db.query_hourly_totals(&first, &last).unwrap();
+        assert_eq!(hourly.len(), 3);
+        assert_eq!(hourly[0].0, "2026-04-02");
+        assert_eq!(&hourly[0].1[9..13], &[1800, 3600, 900, 0]);
+        assert_eq!(hourly[0].1[23], 1200);
+        assert_eq!(hourly[1].1[0], 1200);
+
+        let weekly = db.query_weekday_hour_totals(&first, &last).unwrap();
+        assert_eq!(weekly[3][10], 3600 + 1800);
+        assert_eq!(weekly[3][23], 1200);
+        assert_eq!(weekly[4][0], 1200);
+        let total: i64 = weekly.iter().flatten().sum();
+        assert_eq!(total, 105 * 60 + 40 * 60 + 30 * 60);
+    }
+
+    #[test]
+    fn test_start_session_has_millisecond_timestamp() {
+        let db = Database::open_memory().unwrap();
+        db.start_session("code", None, None, SessionKind::Active)
+            .unwrap();
+        let start = db.get_open_session().unwrap().unwrap().start_time;
+        assert_eq!(start.len(), "2026-04-01T10:00:00.000Z".len());
+        assert!(parse_timestamp(&start).is_some());
+    }
+
+    #[test]
+    fn test_parse_timestamp_accepts_both_precisions() {
+        let ms = parse_timestamp("2026-04-01T10:00:00.250Z").unwrap();
+        let secs = parse_timestamp("2026-04-01T10:00:00").unwrap();
+        assert_eq!((ms - secs).num_milliseconds(), 250);
+        assert_eq!(format_timestamp(&ms), "2026-04-01T10:00:00.250Z");
+        assert!(parse_timestamp("2026-04-01").is_none());
+    }
+
+    #[test]
+    fn test_migrate_v1_derives_kind_from_idle() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("v1.db");
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE schema_version (version INTEGER NOT NULL);
+                 CREATE TABLE sessions (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    process_name TEXT NOT NULL,
+                    exe_path TEXT,
+                    window_title TEXT,
+                    start_time TEXT NOT NULL,
+                    end_time TEXT,
+                    idle INTEGER NOT NULL DEFAULT 0
+                 );
+                 CREATE TABLE tags (
+                    process_name TEXT NOT NULL,
+                    tag TEXT NOT NULL,
+                    PRIMARY KEY (process_name, tag)
+                 );
+                 INSERT INTO schema_version (version) VALUES (1);
+                 INSERT INTO sessions (process_name, start_time, end_time, idle)
+                 VALUES ('idle', '2026-04-01T11:00:00', '2026-04-01T11:30:00', 1);",
+            )
+            .unwrap();
+        }
+
+        // older machine databases are migrated in memory when merged
+        let (merged, skipped) =
+            Database::open_merged(&[("OLD_d".to_string(), path.clone())]).unwrap();
+        assert!(skipped.is_empty());
+        assert_eq!(
+            merged.export_sessions(None, None).unwrap()[0].kind,
+            SessionKind::Idle
+        );
+
+        let db = Database::open(&path).unwrap();
+        let session = db.export_sessions(None, None).unwrap().remove(0);
+        assert_eq!(session.kind, SessionKind::Idle);
+        assert!(session.idle);
+        // converted to UTC from the local time it was recorded in
+        let local = |s: &str| local_time(s).unwrap().naive_local().to_string();
+        assert_eq!(local(&session.start_time), "2026-04-01 11:00:00");
+        assert_eq!(
+            local(session.end_time.as_deref().unwrap()),
+            "2026-04-01 11:30:00"
+        );
+        assert!(session.start_time.ends_with('Z'));
+        assert!(session.utc_offset.is_some());
+    }
+
     #[test]
     fn test_open_file_db() {
         let tmp = tempfile::TempDir::new().unwrap();
@@ -705,7 +2265,8 @@ mod tests {
 
         {
             let db = Database::open(&db_path).unwrap();
-            db.start_session("firefox", None, None, false).unwrap();
+            db.start_session("firefox", None, None, SessionKind::Active)
+                .unwrap();
         }
 
         // reopen and verify data persisted
@@ -716,4 +2277,121 @@ mod tests {
             assert_eq!(open.unwrap().process_name, "firefox");
         }
     }
+
+    #[test]
+    fn test_open_merged_aggregates_machines() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let home = tmp.path().join("HOME_a.db");
+        let work = tmp.path().join("WORK_b.db");
+
+        for (path, secs_end) in [(&home, "11:00:00"), (&work, "10:30:00")] {
+            let db = Database::open(path).unwrap();
+            db.conn
+                .execute(
+                    "INSERT INTO sessions (process_name, start_time, end_time, idle)
+                     VALUES ('firefox', '2026-04-01T10:00:00.000Z', ?1, 0)",
+                    params![format!("2026-04-01T{}.000Z", secs_end)],
+                )
+                .unwrap();
+            db.add_tag("firefox", "browser").unwrap();
+        }
+
+        let sources = vec![
+            ("HOME_a".to_string(), home.clone()),
+            ("WORK_b".to_string(), work.clone()),
+        ];
+        let (merged, skipped) = Database::open_merged(&sources).unwrap();
+        assert!(skipped.is_empty());
+        assert!(merged.is_merged());
+
+        let summaries = merged
+            .query_process_summaries("2026-04-01T00:00:00.000Z", "2026-04-02T00:00:00.000Z", None)
+            .unwrap();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].total_seconds, 5400);
+        assert_eq!(summaries[0].session_count, 2);
+        assert_eq!(summaries[0].active_days, 1);
+
+        let by_machine = merged
+            .query_machine_summaries("2026-04-01T00:00:00.000Z", "2026-04-02T00:00:00.000Z", None)
+            .unwrap();
+        assert_eq!(by_machine.len(), 2);
+        assert_eq!(by_machine[0].machine, "HOME_a");
+        assert_eq!(by_machine[0].total_seconds, 3600);
+
+        let sessions = merged.export_sessions(None, None).unwrap();
+        assert!(sessions.iter().all(|s| s.machine.is_some()));
+        assert_eq!(merged.get_tags("firefox").unwrap(), vec!["browser"]);
+    }
+
+    #[test]
+    fn test_open_merged_skips_other_schema_versions() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("OLD_c.db");
+        {
+            let db = Database::open(&path).unwrap();
+            db.conn
+                .execute("UPDATE schema_version SET version = 99", [])
+                .unwrap();
+        }
+
+        let (merged, skipped) = Database::open_merged(&[("OLD_c".to_string(), path)]).unwrap();
+        assert_eq!(skipped, vec!["OLD_c"]);
+        assert!(merged.export_sessions(None, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_single_database_has_no_machine() {
+        let db = Database::open_memory().unwrap();
+        db.start_session("firefox", None, None, SessionKind::Active)
+            .unwrap();
+        assert!(!db.is_merged());
+        assert!(db.get_open_session().unwrap().unwrap().machine.is_none());
+        assert!(db
+            .query_machine_summaries("2000-01-01T00:00:00.000Z", "2100-01-01T00:00:00.000Z", None)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_focus_blocks() {
+        let db = Database::open_memory().unwrap();
+        let allowed = vec!["code".to_string(), "alacritty".to_string()];
+        let id = db
+            .start_focus_block(
+                "2026-04-01T09:00:00.000Z",
+                "2026-04-01T09:50:00.000Z",
+                &allowed,
+                600,
+            )
+            .unwrap();
+
+        let block = db
+            .current_focus_block("2026-04-01T09:10:00.000Z")
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.id, id);
+        assert_eq!(block.allowed, allowed);
+        assert!(block.allows("code") && !block.allows("firefox"));
+        assert_eq!(block.end(), "2026-04-01T09:50:00.000Z");
+        assert!(db
+            .current_focus_block("2026-04-01T09:50:00.000Z")
+            .unwrap()
+            .is_none());
+
+        // stopping early moves the end; a stopped block can't be stopped again
+        assert!(db.end_focus_block(id, "2026-04-01T09:20:00.000Z").unwrap());
+        assert!(!db.end_focus_block(id, "2026-04-01T09:30:00.000Z").unwrap());
+        let block = db.get_focus_block(id).unwrap().unwrap();
+        assert_eq!(block.end(), "2026-04-01T09:20:00.000Z");
+        assert!(db
+            .current_focus_block("2026-04-01T09:30:00.000Z")
+            .unwrap()
+            .is_none());
+
+        let blocks = db
+            .query_focus_blocks("2026-04-01T00:00:00.000Z", "2026-04-02T00:00:00.000Z")
+            .unwrap();
+        assert_eq!(blocks, vec![block]);
+    }
 }
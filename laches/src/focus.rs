@@ -0,0 +1,332 @@
+//! Focus blocks: scoring them from recorded sessions, and the events the
+//! daemon notifies about while one runs.
+
+use crate::db::{format_timestamp, local_time, Database, FocusBlock};
+use crate::utils::format_duration_hm;
+use chrono::{DateTime, Duration, Local};
+use rusqlite::Result as SqlResult;
+use std::fmt;
+
+/// How long a process outside the allow list has to stay focused before
+/// the daemon nudges, so a quick look at a chat doesn't trigger one.
+pub const DISTRACTION_GRACE_SECS: i64 = 60;
+
+/// Active time in a focus block, split by the allow list.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FocusScore {
+    pub focused_secs: i64,
+    pub distracted_secs: i64,
+    /// Time per process outside the allow list, longest first.
+    pub distractions: Vec<(String, i64)>,
+}
+
+impl FocusScore {
+    /// Share of active time spent in allowed processes, as a percentage.
+    /// None until anything was tracked.
+    pub fn percent(&self) -> Option<i64> {
+        let total = self.focused_secs + self.distracted_secs;
+        (total > 0).then(|| self.focused_secs * 100 / total)
+    }
+}
+
+impl fmt::Display for FocusScore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.percent() {
+            Some(p) => write!(
+                f,
+                "{}% focused, {} distracted",
+                p,
+                format_duration_hm(self.distracted_secs)
+            ),
+            None => write!(f, "nothing tracked yet"),
+        }
+    }
+}
+
+/// Score `block` from the sessions recorded during it, up to `now` for a
+/// block that is still running.
+pub fn score(db: &Database, block: &FocusBlock, now: DateTime<Local>) -> SqlResult<FocusScore> {
+    let now = format_timestamp(&now);
+    let end = block.end().min(now.as_str());
+    let mut score = FocusScore::default();
+    for s in db.query_process_summaries(&block.start_time, end, None)? {
+        if block.allows(&s.process_name) {
+            score.focused_secs += s.total_seconds;
+        } else if s.total_seconds > 0 {
+            score.distracted_secs += s.total_seconds;
+            score.distractions.push((s.process_name, s.total_seconds));
+        }
+    }
+    Ok(score)
+}
+
+/// Seconds from `now` until `timestamp`, or 0 once it passed.
+pub fn secs_until(timestamp: &str, now: DateTime<Local>) -> i64 {
+    local_time(timestamp).map_or(0, |t| (t - now).num_seconds().max(0))
+}
+
+/// Something the daemon notifies about during a focus block.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FocusEvent {
+    /// A process outside the allow list has been focused for a while.
+    Distracted { process: String, left_secs: i64 },
+    /// The block ran its full length.
+    BlockEnded {
+        block: FocusBlock,
+        score: FocusScore,
+    },
+    /// The break after a block is over.
+    BreakOver,
+}
+
+impl FocusEvent {
+    /// Notification title and body.
+    pub fn message(&self) -> (String, String) {
+        match self {
+            FocusEvent::Distracted { process, left_secs } => (
+                format!("distracted by {}", process),
+                format!(
+                    "{} isn't on the allow list, {} left in this focus block",
+                    process,
+                    format_duration_hm(*left_secs)
+                ),
+            ),
+            FocusEvent::BlockEnded { block, score } => {
+                let length = match (local_time(&block.start_time), local_time(block.end())) {
+                    (Some(start), Some(end)) => format_duration_hm((end - start).num_seconds()),
+                    _ => String::new(),
+                };
+                let mut body = match score.percent() {
+                    Some(p) => format!("{} block, {}% focused", length, p),
+                    None => format!("{} block, nothing tracked", length),
+                };
+                if block.break_secs > 0 {
+                    body.push_str(&format!(
+                        ". take a {} break",
+                        format_duration_hm(block.break_secs)
+                    ));
+                }
+                ("focus block done".to_string(), body)
+            }
+            FocusEvent::BreakOver => (
+                "break over".to_string(),
+                "start the next block with `laches focus`".to_string(),
+            ),
+        }
+    }
+}
+
+/// Follows the running focus block across daemon polls and reports each
+/// event once.
+#[derive(Default)]
+pub struct FocusWatch {
+    block: Option<FocusBlock>,
+    /// Process outside the allow list that has the focus, and since when.
+    distracted: Option<(String, DateTime<Local>)>,
+    nudged: bool,
+    break_until: Option<DateTime<Local>>,
+}
+
+impl FocusWatch {
+    /// Check the running block against `focused`, the process currently
+    /// being recorded as active, and return what happened since the last
+    /// call.
+    pub fn update(
+        &mut self,
+        db: &Database,
+        focused: Option<&str>,
+        now: DateTime<Local>,
+    ) -> SqlResult<Vec<FocusEvent>> {
+        let mut events = Vec::new();
+        let current = db.current_focus_block(&format_timestamp(&now))?;
+
+        if let Some(watched) = self.block.take() {
+            if current.as_ref().map(|b| b.id) != Some(watched.id) {
+                self.distracted = None;
+                self.nudged = false;
+                // a block stopped early with `laches focus --stop` ends quietly
+                if let Some(block) = db
+                    .get_focus_block(watched.id)?
+                    .filter(|b| b.end_time.is_none())
+                {
+                    self.break_until = local_time(&block.planned_end)
+                        .map(|end| end + Duration::seconds(block.break_secs))
+                        .filter(|_| block.break_secs > 0);
+                    let score = score(db, &block, now)?;
+                    events.push(FocusEvent::BlockEnded { block, score });
+                }
+            }
+        }
+
+        if current.is_some() {
+            self.break_until = None;
+        } else if self.break_until.is_some_and(|until| until <= now) {
+            self.break_until = None;
+            events.push(FocusEvent::BreakOver);
+        }
+
+        if let Some(block) = &current {
+            match focused.filter(|p| !block.allows(p)) {
+                Some(process) => {
+                    let since = match &self.distracted {
+                        Some((p, since)) if p == process => *since,
+                        _ => {
+                            self.nudged = false;
+                            self.distracted = Some((process.to_string(), now));
+                            now
+                        }
+                    };
+                    if !self.nudged && (now - since).num_seconds() >= DISTRACTION_GRACE_SECS {
+                        self.nudged = true;
+                        events.push(FocusEvent::Distracted {
+                            process: process.to_string(),
+                            left_secs: secs_until(block.end(), now),
+                        });
+                    }
+                }
+                None => {
+                    self.distracted = None;
+                    self.nudged = false;
+                }
+            }
+        }
+
+        self.block = current;
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::SessionKind;
+    use chrono::TimeZone;
+
+    fn record(db: &Database, process: &str, start: DateTime<Local>, minutes: i64) {
+        let end = start + Duration::minutes(minutes);
+        db.insert_session(
+            process,
+            SessionKind::Active,
+            &format_timestamp(&start),
+            &format_timestamp(&end),
+        )
+        .unwrap();
+    }
+
+    fn start_block(db: &Database, start: DateTime<Local>, minutes: i64, break_mins: i64) -> i64 {
+        db.start_focus_block(
+            &format_timestamp(&start),
+            &format_timestamp(&(start + Duration::minutes(minutes))),
+            &["code".to_string(), "alacritty".to_string()],
+            break_mins * 60,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_score() {
+        let db = Database::open_memory().unwrap();
+        let start = Local.with_ymd_and_hms(2026, 4, 1, 9, 0, 0).unwrap();
+        let id = start_block(&db, start, 50, 0);
+        record(&db, "firefox", start - Duration::minutes(30), 40);
+        record(&db, "code", start + Duration::minutes(10), 30);
+        record(&db, "alacritty", start + Duration::minutes(40), 5);
+        record(&db, "slack", start + Duration::minutes(45), 10);
+
+        let block = db.get_focus_block(id).unwrap().unwrap();
+        let score = score(&db, &block, start + Duration::hours(2)).unwrap();
+        assert_eq!(score.focused_secs, 35 * 60);
+        assert_eq!(score.distracted_secs, 15 * 60);
+        assert_eq!(
+            score.distractions,
+            vec![("firefox".to_string(), 600), ("slack".to_string(), 300)]
+        );
+        assert_eq!(score.percent(), Some(70));
+
+        // a running block is scored up to now
+        let running = super::score(&db, &block, start + Duration::minutes(20)).unwrap();
+        assert_eq!(running.focused_secs, 10 * 60);
+        assert_eq!(FocusScore::default().percent(), None);
+    }
+
+    #[test]
+    fn test_watch_nudges_once_per_distraction() {
+        let db = Database::open_memory().unwrap();
+        let start = Local.with_ymd_and_hms(2026, 4, 1, 9, 0, 0).unwrap();
+        start_block(&db, start, 50, 0);
+        let mut watch = FocusWatch::default();
+        let at = |mins| start + Duration::minutes(mins);
+
+        assert!(watch.update(&db, Some("code"), at(1)).unwrap().is_empty());
+        assert!(watch
+            .update(&db, Some("firefox"), at(2))
+            .unwrap()
+            .is_empty());
+        let events = watch.update(&db, Some("firefox"), at(3)).unwrap();
+        assert!(matches!(
+            &events[..],
+            [FocusEvent::Distracted { process, left_secs }]
+                if process == "firefox" && *left_secs == 47 * 60
+        ));
+        assert!(watch
+            .update(&db, Some("firefox"), at(4))
+            .unwrap()
+            .is_empty());
+
+        // a new distraction after getting back to work nudges again
+        watch.update(&db, Some("code"), at(5)).unwrap();
+        watch.update(&db, Some("firefox"), at(6)).unwrap();
+        assert_eq!(watch.update(&db, Some("firefox"), at(7)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_watch_reports_block_end_and_break() {
+        let db = Database::open_memory().unwrap();
+        let start = Local.with_ymd_and_hms(2026, 4, 1, 9, 0, 0).unwrap();
+        start_block(&db, start, 50, 10);
+        record(&db, "code", start, 50);
+        let mut watch = FocusWatch::default();
+        let at = |mins| start + Duration::minutes(mins);
+
+        watch.update(&db, Some("code"), at(49)).unwrap();
+        let events = watch.update(&db, None, at(51)).unwrap();
+        let [FocusEvent::BlockEnded { score, .. }] = &events[..] else {
+            panic!("expected the block to end: {:?}", events);
+        };
+        assert_eq!(score.percent(), Some(100));
+        assert_eq!(
+            events[0].message().1,
+            "50m block, 100% focused. take a 10m break"
+        );
+
+        assert!(watch.update(&db, None, at(55)).unwrap().is_empty());
+        assert_eq!(
+            watch.update(&db, None, at(61)).unwrap(),
+            vec![FocusEvent::BreakOver]
+        );
+        assert!(watch.update(&db, None, at(62)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_watch_ignores_stopped_blocks() {
+        let db = Database::open_memory().unwrap();
+        let start = Local.with_ymd_and_hms(2026, 4, 1, 9, 0, 0).unwrap();
+        let id = start_block(&db, start, 50, 10);
+        let mut watch = FocusWatch::default();
+
+        watch
+            .update(&db, None, start + Duration::minutes(1))
+            .unwrap();
+        assert!(db
+            .end_focus_block(id, &format_timestamp(&(start + Duration::minutes(2))))
+            .unwrap());
+        assert!(watch
+            .update(&db, None, start + Duration::minutes(3))
+            .unwrap()
+            .is_empty());
+        assert!(watch
+            .update(&db, None, start + Duration::minutes(65))
+            .unwrap()
+            .is_empty());
+    }
+}
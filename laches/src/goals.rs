@@ -0,0 +1,286 @@
+//! Recording daily goal outcomes and working out streaks from them.
+
+use crate::config::Goal;
+use crate::db::{date_range_for_day, Database, GoalResult};
+use crate::utils::format_duration_hm;
+use chrono::{Duration, NaiveDate};
+use rusqlite::Result as SqlResult;
+
+/// How far back outcomes are filled in for a goal that has none recorded
+/// yet.
+pub const BACKFILL_DAYS: i64 = 30;
+
+/// Active seconds counted toward `goal` on one local day.
+pub fn day_secs(db: &Database, goal: &Goal, date: NaiveDate) -> SqlResult<i64> {
+    let Some((start, end)) = date_range_for_day(&date.format("%Y-%m-%d").to_string()) else {
+        return Ok(0);
+    };
+    db.query_target_seconds(&start, &end, goal.process.as_deref(), goal.tag.as_deref())
+}
+
+/// Record the outcome of every goal for each finished day it applies on
+/// that has none yet. Starts after the last recorded day, or up to
+/// [`BACKFILL_DAYS`] back for a new goal, but never before the first
+/// tracked day. With `recompute`, days already recorded are evaluated
+/// again, e.g. after sessions were edited or tags changed.
+pub fn record(db: &Database, goals: &[Goal], today: NaiveDate, recompute: bool) -> SqlResult<()> {
+    let Some(earliest) = db.get_earliest_session_date()?.and_then(|d| parse_date(&d)) else {
+        return Ok(());
+    };
+
+    db.transaction(|db| {
+        for goal in goals {
+            let key = goal.to_string();
+            let recorded = db
+                .goal_date_range(&key)?
+                .and_then(|(first, last)| parse_date(&first).zip(parse_date(&last)));
+            let mut day = match recorded {
+                Some((first, _)) if recompute => first,
+                Some((_, last)) => last + Duration::days(1),
+                None => earliest.max(today - Duration::days(BACKFILL_DAYS)),
+            };
+            while day < today {
+                if goal.days.includes(day) {
+                    let seconds = day_secs(db, goal, day)?;
+                    let result = GoalResult {
+                        date: day.format("%Y-%m-%d").to_string(),
+                        seconds,
+                        passed: goal.passes(seconds),
+                    };
+                    db.record_goal_result(&key, &result)?;
+                }
+                day += Duration::days(1);
+            }
+        }
+        Ok(())
+    })
+}
+
+fn parse_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()
+}
+
+/// A goal's state on one day, with its streaks and recent history.
+#[derive(Debug, Clone)]
+pub struct GoalProgress {
+    pub goal: Goal,
+    /// Active seconds counted so far on the day.
+    pub secs: i64,
+    pub required_secs: i64,
+    /// Whether the goal applies on the day at all.
+    pub applies: bool,
+    /// Passed days in a row up to the day. The day itself counts once it
+    /// is over, or as soon as a minimum is reached.
+    pub streak: u32,
+    pub best_streak: u32,
+    /// Outcome of each earlier day, oldest first; None where the goal
+    /// doesn't apply or nothing was recorded.
+    pub history: Vec<(NaiveDate, Option<bool>)>,
+}
+
+impl GoalProgress {
+    /// How the day is going, e.g. `2h 30m of 4h 0m, 1h 30m to go`.
+    pub fn describe(&self) -> String {
+        if !self.applies {
+            return "not a goal day".to_string();
+        }
+        let used = format_duration_hm(self.secs);
+        let required = format_duration_hm(self.required_secs);
+        let rest = format_duration_hm((self.required_secs - self.secs).abs());
+        match (self.goal.is_minimum(), self.secs >= self.required_secs) {
+            (true, true) => format!("{} of {}, done", used, required),
+            (true, false) => format!("{} of {}, {} to go", used, required, rest),
+            (false, _) if self.secs > self.required_secs => {
+                format!("{} of at most {}, {} over", used, required, rest)
+            }
+            (false, _) => format!("{} of at most {}, {} to spare", used, required, rest),
+        }
+    }
+}
+
+/// `goal`'s progress on `date`, with `days` days of history before it.
+/// Outcomes come from [`record`], so call that first. `today` decides
+/// whether `date` is still running.
+pub fn progress(
+    db: &Database,
+    goal: &Goal,
+    date: NaiveDate,
+    today: NaiveDate,
+    days: u32,
+) -> SqlResult<GoalProgress> {
+    let secs = day_secs(db, goal, date)?;
+    let applies = goal.days.includes(date);
+    let mut results: Vec<(NaiveDate, bool)> = db
+        .query_goal_results(&goal.to_string())?
+        .into_iter()
+        .filter_map(|r| parse_date(&r.date).map(|d| (d, r.passed)))
+        .filter(|(d, _)| *d <= date)
+        .collect();
+    // a running day only counts toward the streak once a minimum is met;
+    // a maximum can still be broken until midnight
+    if applies && date >= today && goal.is_minimum() && goal.passes(secs) {
+        results.push((date, true));
+    }
+
+    let mut streak = 0;
+    let mut best_streak = 0;
+    for (_, passed) in &results {
+        streak = if *passed { streak + 1 } else { 0 };
+        best_streak = best_streak.max(streak);
+    }
+
+    let history = (1..=i64::from(days))
+        .rev()
+        .map(|back| {
+            let day = date - Duration::days(back);
+            let passed = results.iter().find(|(d, _)| *d == day).map(|(_, p)| *p);
+            (day, passed)
+        })
+        .collect();
+
+    Ok(GoalProgress {
+        goal: goal.clone(),
+        secs,
+        required_secs: goal.required_secs().unwrap_or(0),
+        applies,
+        streak,
+        best_streak,
+        history,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GoalDays;
+    use crate::db::{format_timestamp, SessionKind};
+    use chrono::{DateTime, Local, TimeZone};
+
+    fn goal(tag: Option<&str>, at_least: Option<&str>, at_most: Option<&str>) -> Goal {
+        Goal {
+            process: None,
+            tag: tag.map(str::to_string),
+            at_least: at_least.map(str::to_string),
+            at_most: at_most.map(str::to_string),
+            days: GoalDays::Weekdays,
+        }
+    }
+
+    fn record_session(db: &Database, process: &str, start: DateTime<Local>, minutes: i64) {
+        db.insert_session(
+            process,
+            SessionKind::Active,
+            &format_timestamp(&start),
+            &format_timestamp(&(start + Duration::minutes(minutes))),
+        )
+        .unwrap();
+    }
+
+    fn at(day: u32, hour: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 4, day, hour, 0, 0).unwrap()
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 4, day).unwrap()
+    }
+
+    #[test]
+    fn test_record_and_streaks() {
+        let db = Database::open_memory().unwrap();
+        db.add_tag("code", "dev").unwrap();
+        let dev = goal(Some("dev"), Some("1h"), None);
+        // wed 1 to fri 10; the weekend of the 4th and 5th doesn't apply
+        for day in [1, 2, 6, 8, 9] {
+            record_session(&db, "code", at(day, 9), 90);
+        }
+        record_session(&db, "code", at(7, 9), 30);
+        record_session(&db, "code", at(10, 9), 20);
+
+        record(&db, std::slice::from_ref(&dev), date(10), false).unwrap();
+        let results = db.query_goal_results(&dev.to_string()).unwrap();
+        let passed: Vec<(&str, bool)> = results.iter().map(|r| (&r.date[8..], r.passed)).collect();
+        assert_eq!(
+            passed,
+            vec![
+                ("01", true),
+                ("02", true),
+                ("03", false),
+                ("06", true),
+                ("07", false),
+                ("08", true),
+                ("09", true),
+            ]
+        );
+
+        let p = progress(&db, &dev, date(10), date(10), 7).unwrap();
+        assert_eq!((p.streak, p.best_streak), (2, 2));
+        assert_eq!(p.describe(), "20m of 1h 0m, 40m to go");
+        assert_eq!(p.history.len(), 7);
+        assert_eq!(p.history[0], (date(3), Some(false)));
+        assert_eq!(p.history[1], (date(4), None));
+
+        // reaching a minimum counts today straight away
+        record_session(&db, "code", at(10, 11), 60);
+        let p = progress(&db, &dev, date(10), date(10), 7).unwrap();
+        assert_eq!((p.streak, p.best_streak), (3, 3));
+        assert_eq!(p.describe(), "1h 20m of 1h 0m, done");
+
+        // recording again only adds the days since, unless recomputing
+        record_session(&db, "code", at(7, 12), 30);
+        record(&db, std::slice::from_ref(&dev), date(14), false).unwrap();
+        let results = db.query_goal_results(&dev.to_string()).unwrap();
+        assert_eq!(results.len(), 9);
+        assert_eq!(results[7].date, "2026-04-10");
+        assert!(!results[4].passed);
+        record(&db, std::slice::from_ref(&dev), date(14), true).unwrap();
+        let results = db.query_goal_results(&dev.to_string()).unwrap();
+        assert_eq!(results.len(), 9);
+        assert!(results[4].passed);
+    }
+
+    #[test]
+    fn test_rule_tags_count_toward_goals() {
+        use crate::config::{MatchKind, Rule, RuleAction, RuleField};
+
+        let db = Database::open_memory().unwrap();
+        db.set_rules(&[Rule {
+            field: RuleField::Process,
+            kind: MatchKind::Exact,
+            pattern: "code".to_string(),
+            action: RuleAction::Tag,
+            tag: Some("dev".to_string()),
+            category: None,
+            process: None,
+        }])
+        .unwrap();
+        let dev = goal(Some("dev"), Some("1h"), None);
+        record_session(&db, "code", at(1, 9), 90);
+
+        record(&db, std::slice::from_ref(&dev), date(2), false).unwrap();
+        let results = db.query_goal_results(&dev.to_string()).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].seconds, 5400);
+        assert!(results[0].passed);
+    }
+
+    #[test]
+    fn test_maximum_goals() {
+        let db = Database::open_memory().unwrap();
+        let social = goal(None, None, Some("1h"));
+        record_session(&db, "firefox", at(1, 9), 30);
+        record_session(&db, "firefox", at(2, 9), 90);
+
+        let p = progress(&db, &social, date(2), date(2), 1).unwrap();
+        assert_eq!(p.describe(), "1h 30m of at most 1h 0m, 30m over");
+        // the running day isn't judged yet
+        record(&db, std::slice::from_ref(&social), date(2), false).unwrap();
+        let p = progress(&db, &social, date(2), date(2), 1).unwrap();
+        assert_eq!((p.streak, p.history[0].1), (1, Some(true)));
+
+        record(&db, std::slice::from_ref(&social), date(3), false).unwrap();
+        let p = progress(&db, &social, date(3), date(3), 2).unwrap();
+        assert_eq!(p.streak, 0);
+        assert_eq!(p.best_streak, 1);
+        assert_eq!(p.describe(), "0m of at most 1h 0m, 1h 0m to spare");
+    }
+}
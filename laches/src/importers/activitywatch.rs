@@ -0,0 +1,247 @@
+//! ActivityWatch bucket exports.
+//!
+//! Reads the JSON from "export all buckets" (`{"buckets": {...}}`) or a
+//! single exported bucket. `currentwindow` events become active sessions
+//! and `afkstatus` events with status `afk` become idle sessions; window
+//! events are cut around afk time, since the window watcher keeps
+//! reporting while the user is away.
+
+use super::{into_timeline, process_name, read_json, Imported, ImportedSession};
+use crate::db::SessionKind;
+use crate::error::LachesError;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+use std::path::Path;
+
+/// One event with its span.
+struct Event<'a> {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    data: &'a Value,
+}
+
+/// Read an ActivityWatch export file.
+pub fn read(path: &Path) -> Result<Imported, LachesError> {
+    parse(&read_json(path)?)
+}
+
+fn parse(root: &Value) -> Result<Imported, LachesError> {
+    let buckets: Vec<(&str, &Value)> = match root.get("buckets").and_then(Value::as_object) {
+        Some(buckets) => buckets.iter().map(|(id, b)| (id.as_str(), b)).collect(),
+        None if root.get("events").is_some() => {
+            vec![(
+                root.get("id").and_then(Value::as_str).unwrap_or("bucket"),
+                root,
+            )]
+        }
+        None => return Err("not an activitywatch export: no buckets found".into()),
+    };
+
+    let mut skipped = Vec::new();
+    let mut windows = Vec::new();
+    let mut afk = Vec::new();
+    for (id, bucket) in buckets {
+        let kind = bucket.get("type").and_then(Value::as_str).unwrap_or("");
+        let target = match kind {
+            "currentwindow" => &mut windows,
+            "afkstatus" => &mut afk,
+            _ => {
+                skipped.push(format!("bucket '{}': unsupported type '{}'", id, kind));
+                continue;
+            }
+        };
+        let events = bucket
+            .get("events")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        for (i, event) in events.iter().enumerate() {
+            match parse_event(event) {
+                Ok(event) => target.push(event),
+                Err(e) => skipped.push(format!("bucket '{}' event {}: {}", id, i + 1, e)),
+            }
+        }
+    }
+
+    let mut away: Vec<(DateTime<Utc>, DateTime<Utc>)> = afk
+        .iter()
+        .filter(|e| e.data.get("status").and_then(Value::as_str) == Some("afk"))
+        .map(|e| (e.start, e.end))
+        .collect();
+    away.sort();
+
+    let mut sessions = Vec::new();
+    for &(start, end) in &away {
+        sessions.push(ImportedSession::new(
+            SessionKind::Idle.as_str(),
+            None,
+            SessionKind::Idle,
+            start,
+            end,
+        ));
+    }
+    for event in &windows {
+        let app = process_name(
+            event
+                .data
+                .get("app")
+                .and_then(Value::as_str)
+                .filter(|a| !a.is_empty())
+                .unwrap_or("unknown"),
+        );
+        let title = event
+            .data
+            .get("title")
+            .and_then(Value::as_str)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string);
+        for (start, end) in subtract(event.start, event.end, &away) {
+            sessions.push(ImportedSession::new(
+                &app,
+                title.clone(),
+                SessionKind::Active,
+                start,
+                end,
+            ));
+        }
+    }
+
+    Ok((into_timeline(sessions), skipped))
+}
+
+fn parse_event(event: &Value) -> Result<Event<'_>, String> {
+    let timestamp = event
+        .get("timestamp")
+        .and_then(Value::as_str)
+        .ok_or("missing timestamp")?;
+    let start = DateTime::parse_from_rfc3339(timestamp)
+        .map_err(|_| format!("invalid timestamp '{}'", timestamp))?
+        .with_timezone(&Utc);
+    let duration = event
+        .get("duration")
+        .and_then(Value::as_f64)
+        .filter(|d| d.is_finite() && *d >= 0.0)
+        .ok_or("missing or negative duration")?;
+    Ok(Event {
+        start,
+        end: start + Duration::milliseconds((duration * 1000.0).round() as i64),
+        data: event.get("data").unwrap_or(&Value::Null),
+    })
+}
+
+/// The parts of `start..end` outside every (sorted) interval in `away`.
+fn subtract(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    away: &[(DateTime<Utc>, DateTime<Utc>)],
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let mut pieces = Vec::new();
+    let mut cursor = start;
+    for &(away_start, away_end) in away {
+        if away_end <= cursor || away_start >= end {
+            continue;
+        }
+        if away_start > cursor {
+            pieces.push((cursor, away_start));
+        }
+        cursor = cursor.max(away_end);
+    }
+    if cursor < end {
+        pieces.push((cursor, end));
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_window_events_are_cut_around_afk_time() {
+        let export = json!({
+            "buckets": {
+                "aw-watcher-window_host": {
+                    "type": "currentwindow",
+                    "events": [
+                        {"timestamp": "2026-04-01T10:00:00+00:00", "duration": 1800.0,
+                         "data": {"app": "Firefox.exe", "title": "docs"}},
+                        {"timestamp": "2026-04-01T10:30:00.500000+00:00", "duration": 60,
+                         "data": {"app": "code", "title": ""}},
+                        {"timestamp": "yesterday", "duration": 1, "data": {}}
+                    ]
+                },
+                "aw-watcher-afk_host": {
+                    "type": "afkstatus",
+                    "events": [
+                        {"timestamp": "2026-04-01T10:10:00+00:00", "duration": 600,
+                         "data": {"status": "afk"}},
+                        {"timestamp": "2026-04-01T10:20:00+00:00", "duration": 900,
+                         "data": {"status": "not-afk"}}
+                    ]
+                },
+                "aw-watcher-web-firefox": {"type": "web.tab.current", "events": []}
+            }
+        });
+
+        let (sessions, skipped) = parse(&export).unwrap();
+        assert_eq!(skipped.len(), 2);
+
+        let spans: Vec<(&str, SessionKind, &str, &str)> = sessions
+            .iter()
+            .map(|s| {
+                let s = &s.session;
+                (
+                    s.process_name.as_str(),
+                    s.kind,
+                    s.start_time.as_str(),
+                    s.end_time.as_deref().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (
+                    "firefox",
+                    SessionKind::Active,
+                    "2026-04-01T10:00:00.000Z",
+                    "2026-04-01T10:10:00.000Z"
+                ),
+                (
+                    "idle",
+                    SessionKind::Idle,
+                    "2026-04-01T10:10:00.000Z",
+                    "2026-04-01T10:20:00.000Z"
+                ),
+                (
+                    "firefox",
+                    SessionKind::Active,
+                    "2026-04-01T10:20:00.000Z",
+                    "2026-04-01T10:30:00.000Z"
+                ),
+                (
+                    "code",
+                    SessionKind::Active,
+                    "2026-04-01T10:30:00.500Z",
+                    "2026-04-01T10:31:00.500Z"
+                ),
+            ]
+        );
+        assert_eq!(sessions[0].session.window_title.as_deref(), Some("docs"));
+        assert_eq!(sessions[3].session.window_title, None);
+    }
+
+    #[test]
+    fn test_single_bucket_export() {
+        let bucket = json!({
+            "id": "aw-watcher-window_host",
+            "type": "currentwindow",
+            "events": [{"timestamp": "2026-04-01T12:00:00+02:00", "duration": 5,
+                        "data": {"app": "code", "title": "main.rs"}}]
+        });
+        let (sessions, _) = parse(&bucket).unwrap();
+        assert_eq!(sessions[0].session.start_time, "2026-04-01T10:00:00.000Z");
+        assert!(parse(&json!({"events_": []})).is_err());
+    }
+}
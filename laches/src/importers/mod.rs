@@ -0,0 +1,213 @@
+//! Importers for other time trackers.
+//!
+//! Each adapter turns another tracker's export into finished sessions,
+//! which `laches data import --from` then stores like a lachesis export:
+//! sessions overlapping ones already in the database are skipped.
+
+pub mod activitywatch;
+pub mod rescuetime;
+pub mod wakatime;
+
+use crate::db::{format_timestamp, Session, SessionKind};
+use crate::error::LachesError;
+use crate::platform::normalize_process_name;
+use chrono::{DateTime, Local, TimeZone, Utc};
+use serde_json::{Map, Value};
+use std::io::BufRead;
+use std::path::Path;
+
+/// A session read from an export, with the tags exported alongside it.
+#[derive(Debug, Clone)]
+pub struct ImportedSession {
+    pub session: Session,
+    pub tags: Vec<String>,
+}
+
+impl ImportedSession {
+    /// A finished, untagged session. The UTC offset is the system time
+    /// zone's at `start`, as other trackers don't record where they ran.
+    pub fn new(
+        process_name: &str,
+        window_title: Option<String>,
+        kind: SessionKind,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Self {
+        let utc_offset = Local
+            .offset_from_utc_datetime(&start.naive_utc())
+            .local_minus_utc();
+        ImportedSession {
+            session: Session {
+                id: 0,
+                process_name: process_name.to_string(),
+                exe_path: None,
+                window_title,
+                start_time: format_timestamp(&start),
+                end_time: Some(format_timestamp(&end)),
+                idle: kind != SessionKind::Active,
+                kind,
+                utc_offset: Some(utc_offset),
+                machine: None,
+            },
+            tags: Vec::new(),
+        }
+    }
+}
+
+/// The process name an app reported by another tracker is stored under,
+/// matching locally tracked names: lowercase and without `.exe`, which is
+/// dropped on every platform since exports can come from another machine.
+pub fn process_name(app: &str) -> String {
+    let name = normalize_process_name(app.trim()).to_lowercase();
+    name.strip_suffix(".exe").unwrap_or(&name).to_string()
+}
+
+/// Sessions read by an importer, and a message for every record skipped.
+pub type Imported = (Vec<ImportedSession>, Vec<String>);
+
+/// Sort sessions oldest first and trim each one to start where the
+/// previous one ended, dropping any left empty. Other trackers can record
+/// overlapping events, but only one window has focus at a time.
+pub fn into_timeline(mut sessions: Vec<ImportedSession>) -> Vec<ImportedSession> {
+    sessions.sort_by(|a, b| a.session.start_time.cmp(&b.session.start_time));
+
+    let mut timeline: Vec<ImportedSession> = Vec::with_capacity(sessions.len());
+    for mut s in sessions {
+        let end = s.session.end_time.clone().unwrap_or_default();
+        if let Some(prev_end) = timeline.last().and_then(|p| p.session.end_time.clone()) {
+            if s.session.start_time < prev_end {
+                s.session.start_time = prev_end;
+            }
+        }
+        if s.session.start_time < end {
+            timeline.push(s);
+        }
+    }
+    timeline
+}
+
+/// Read a whole JSON file.
+pub(crate) fn read_json(path: &Path) -> Result<Value, LachesError> {
+    let file = std::fs::File::open(path)?;
+    serde_json::from_reader(std::io::BufReader::new(file))
+        .map_err(|e| LachesError::InvalidInput(format!("'{}': {}", path.display(), e)))
+}
+
+/// Parse RFC 4180 CSV with a header row into one map per record. Every
+/// value is a string, and empty fields are left out.
+pub(crate) fn parse_csv(reader: impl BufRead) -> Result<Vec<Map<String, Value>>, LachesError> {
+    let mut records = Vec::new();
+    let mut record: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+
+    for line in reader.split(b'\n') {
+        let line = String::from_utf8(line?)
+            .map_err(|_| LachesError::InvalidInput("csv is not valid utf-8".to_string()))?;
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (c, in_quotes) {
+                ('"', true) if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                ('"', _) => in_quotes = !in_quotes,
+                (',', false) => record.push(std::mem::take(&mut field)),
+                _ => field.push(c),
+            }
+        }
+        if in_quotes {
+            field.push('\n');
+        } else {
+            if field.ends_with('\r') {
+                field.pop();
+            }
+            record.push(std::mem::take(&mut field));
+            if record.iter().any(|f| !f.is_empty()) {
+                records.push(std::mem::take(&mut record));
+            } else {
+                record.clear();
+            }
+        }
+    }
+    if in_quotes {
+        return Err("unterminated quoted field in csv".into());
+    }
+
+    let mut records = records.into_iter();
+    let header: Vec<String> = records
+        .next()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|h| h.trim().trim_start_matches('\u{feff}').to_string())
+        .collect();
+    Ok(records
+        .map(|fields| {
+            header
+                .iter()
+                .zip(fields)
+                .filter(|(_, v)| !v.is_empty())
+                .map(|(k, v)| (k.clone(), Value::String(v)))
+                .collect()
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        crate::db::parse_timestamp(s).unwrap()
+    }
+
+    #[test]
+    fn test_timeline_trims_overlaps() {
+        let sessions = vec![
+            ImportedSession::new(
+                "b",
+                None,
+                SessionKind::Active,
+                at("2026-04-01T10:20:00Z"),
+                at("2026-04-01T10:40:00Z"),
+            ),
+            ImportedSession::new(
+                "a",
+                None,
+                SessionKind::Active,
+                at("2026-04-01T10:00:00Z"),
+                at("2026-04-01T10:30:00Z"),
+            ),
+            // swallowed by the one before it
+            ImportedSession::new(
+                "c",
+                None,
+                SessionKind::Active,
+                at("2026-04-01T10:25:00Z"),
+                at("2026-04-01T10:35:00Z"),
+            ),
+        ];
+        let timeline: Vec<(String, String)> = into_timeline(sessions)
+            .into_iter()
+            .map(|s| (s.session.process_name, s.session.start_time))
+            .collect();
+        assert_eq!(
+            timeline,
+            vec![
+                ("a".to_string(), "2026-04-01T10:00:00.000Z".to_string()),
+                ("b".to_string(), "2026-04-01T10:30:00.000Z".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_csv_quotes_and_line_breaks() {
+        let csv = "\u{feff}a,b\r\n\"x, \"\"y\"\"\",\"two\r\nlines\"\r\n\r\n1,\r\n";
+        let rows = parse_csv(csv.as_bytes()).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["a"], "x, \"y\"");
+        assert_eq!(rows[0]["b"], "two\r\nlines");
+        assert_eq!(rows[1]["a"], "1");
+        assert!(!rows[1].contains_key("b"));
+    }
+}
@@ -0,0 +1,180 @@
+//! RescueTime CSV exports.
+//!
+//! Columns are found by name, so both the activity log export (a start
+//! and end per row) and the api's interval report (`Date`, `Time Spent
+//! (seconds)`, `Activity`) are read. Interval rows only say how long an
+//! activity was used within the interval, so they are laid out back to
+//! back from its start. RescueTime doesn't report idle time.
+
+use super::{into_timeline, parse_csv, process_name, Imported, ImportedSession};
+use crate::db::{local_to_utc, SessionKind};
+use crate::error::LachesError;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::Path;
+
+const ACTIVITY: &[&str] = &["activity", "activity_name", "application", "app"];
+const DETAILS: &[&str] = &["document", "activity_details", "details", "title"];
+const START: &[&str] = &[
+    "date",
+    "start",
+    "start time",
+    "start_time",
+    "timestamp",
+    "timestamp_start",
+];
+const END: &[&str] = &["end", "end time", "end_time", "timestamp_end"];
+const SECONDS: &[&str] = &["time spent (seconds)", "time spent", "duration", "seconds"];
+
+/// Read a RescueTime CSV export.
+pub fn read(path: &Path) -> Result<Imported, LachesError> {
+    let file = std::fs::File::open(path)?;
+    parse(parse_csv(std::io::BufReader::new(file))?)
+}
+
+fn parse(rows: Vec<Map<String, Value>>) -> Result<Imported, LachesError> {
+    if let Some(first) = rows.first() {
+        if column(first, ACTIVITY).is_none() && column(first, START).is_none() {
+            return Err("not a rescuetime export: no activity or date column".into());
+        }
+    }
+
+    let mut skipped = Vec::new();
+    let mut sessions = Vec::new();
+    // where the next activity of each interval starts
+    let mut cursors: HashMap<DateTime<Utc>, DateTime<Utc>> = HashMap::new();
+    for (i, row) in rows.iter().enumerate() {
+        match parse_row(row, &mut cursors) {
+            Ok(session) => sessions.push(session),
+            Err(e) => skipped.push(format!("row {}: {}", i + 1, e)),
+        }
+    }
+    Ok((into_timeline(sessions), skipped))
+}
+
+fn parse_row(
+    row: &Map<String, Value>,
+    cursors: &mut HashMap<DateTime<Utc>, DateTime<Utc>>,
+) -> Result<ImportedSession, String> {
+    let activity = column(row, ACTIVITY).ok_or("missing activity")?;
+    let details = column(row, DETAILS)
+        .filter(|d| !d.eq_ignore_ascii_case("no details"))
+        .map(str::to_string);
+    let start_text = column(row, START).ok_or("missing start time")?;
+    let start = parse_time(start_text)?;
+
+    let (start, end) = match column(row, END) {
+        Some(end) => (start, parse_time(end)?),
+        None => {
+            let secs: f64 = column(row, SECONDS)
+                .ok_or("missing end time or duration")?
+                .parse()
+                .map_err(|_| "invalid duration".to_string())?;
+            if !secs.is_finite() || secs < 0.0 {
+                return Err("invalid duration".to_string());
+            }
+            let cursor = cursors.entry(start).or_insert(start);
+            let begin = *cursor;
+            *cursor = begin + Duration::milliseconds((secs * 1000.0).round() as i64);
+            (begin, *cursor)
+        }
+    };
+    if end < start {
+        return Err(format!("ends before it starts ({})", start_text));
+    }
+
+    Ok(ImportedSession::new(
+        &process_name(activity),
+        details,
+        SessionKind::Active,
+        start,
+        end,
+    ))
+}
+
+/// First non-empty value among the candidate column names, ignoring case.
+fn column<'a>(row: &'a Map<String, Value>, names: &[&str]) -> Option<&'a str> {
+    row.iter()
+        .filter(|(k, _)| names.iter().any(|n| k.trim().eq_ignore_ascii_case(n)))
+        .find_map(|(_, v)| v.as_str().map(str::trim).filter(|v| !v.is_empty()))
+}
+
+/// Times with an offset are used as is; ones without are local time.
+fn parse_time(s: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Ok(t.with_timezone(&Utc));
+    }
+    if let Ok(t) = DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S %z") {
+        return Ok(t.with_timezone(&Utc));
+    }
+    let naive = s.replace(' ', "T");
+    let naive = if naive.len() == 10 {
+        format!("{}T00:00:00", naive)
+    } else {
+        naive
+    };
+    local_to_utc(&naive)
+        .map(|(t, _)| t)
+        .ok_or_else(|| format!("invalid time '{}'", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(csv: &str) -> Vec<Map<String, Value>> {
+        parse_csv(csv.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_interval_rows_are_laid_out_back_to_back() {
+        let csv = "Date,Time Spent (seconds),Number of People,Activity,Category,Productivity\n\
+                   2026-04-01T10:00:00+00:00,1200,1,code,Editing & IDEs,2\n\
+                   2026-04-01T10:00:00+00:00,600,1,github.com,General Software Development,2\n\
+                   2026-04-01T11:00:00+00:00,abc,1,slack,Instant Message,0\n";
+        let (sessions, skipped) = parse(rows(csv)).unwrap();
+        assert_eq!(skipped.len(), 1);
+
+        let spans: Vec<(&str, &str, &str)> = sessions
+            .iter()
+            .map(|s| {
+                (
+                    s.session.process_name.as_str(),
+                    s.session.start_time.as_str(),
+                    s.session.end_time.as_deref().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (
+                    "code",
+                    "2026-04-01T10:00:00.000Z",
+                    "2026-04-01T10:20:00.000Z"
+                ),
+                (
+                    "github.com",
+                    "2026-04-01T10:20:00.000Z",
+                    "2026-04-01T10:30:00.000Z"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_activity_log_rows() {
+        let csv = "activity_name,activity_details,timestamp_start,timestamp_end\n\
+                   Firefox.exe,lachesis - github,2026-04-01 10:00:00 +0200,2026-04-01 10:15:00 +0200\n\
+                   code,No Details,2026-04-01 10:15:00 +0200,2026-04-01 10:14:00 +0200\n";
+        let (sessions, skipped) = parse(rows(csv)).unwrap();
+        assert_eq!(skipped.len(), 1);
+        let s = &sessions[0].session;
+        assert_eq!(s.process_name, "firefox");
+        assert_eq!(s.window_title.as_deref(), Some("lachesis - github"));
+        assert_eq!(s.start_time, "2026-04-01T08:00:00.000Z");
+
+        assert!(parse(rows("foo,bar\n1,2\n")).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+//! WakaTime data dumps.
+//!
+//! Reads the heartbeats dump (`{"days": [{"heartbeats": [...]}]}`) or a
+//! plain list of heartbeats, as returned by the heartbeats api under
+//! `data`. Heartbeats are joined into sessions the way WakaTime counts
+//! time: consecutive heartbeats less than [`TIMEOUT_SECS`] apart are one
+//! stretch of work, and switching files ends a session at the switch.
+//! WakaTime has no notion of idle time, so gaps are left unrecorded.
+
+use super::{into_timeline, read_json, Imported, ImportedSession};
+use crate::db::SessionKind;
+use crate::error::LachesError;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::path::Path;
+
+/// WakaTime's default keystroke timeout.
+pub const TIMEOUT_SECS: f64 = 15.0 * 60.0;
+
+/// Process name used for heartbeats that don't name their editor.
+const DEFAULT_PROCESS: &str = "wakatime";
+
+struct Heartbeat {
+    time: f64,
+    process: String,
+    title: Option<String>,
+}
+
+/// Read a WakaTime export file.
+pub fn read(path: &Path) -> Result<Imported, LachesError> {
+    parse(&read_json(path)?)
+}
+
+fn parse(root: &Value) -> Result<Imported, LachesError> {
+    let raw: Vec<&Value> = if let Some(days) = root.get("days").and_then(Value::as_array) {
+        if !days.is_empty() && days.iter().all(|d| d.get("heartbeats").is_none()) {
+            return Err(
+                "wakatime export has daily summaries but no heartbeats, export the heartbeats data dump instead"
+                    .into(),
+            );
+        }
+        days.iter()
+            .filter_map(|d| d.get("heartbeats").and_then(Value::as_array))
+            .flatten()
+            .collect()
+    } else if let Some(data) = root.get("data").and_then(Value::as_array) {
+        data.iter().collect()
+    } else if let Some(list) = root.as_array() {
+        list.iter().collect()
+    } else {
+        return Err("not a wakatime export: no heartbeats found".into());
+    };
+
+    let mut skipped = Vec::new();
+    let mut heartbeats = Vec::with_capacity(raw.len());
+    for (i, hb) in raw.into_iter().enumerate() {
+        match parse_heartbeat(hb) {
+            Ok(hb) => heartbeats.push(hb),
+            Err(e) => skipped.push(format!("heartbeat {}: {}", i + 1, e)),
+        }
+    }
+    heartbeats.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+    let mut sessions = Vec::new();
+    let mut current: Option<(&Heartbeat, f64)> = None;
+    for hb in &heartbeats {
+        current = Some(match current {
+            Some((first, last)) if hb.time - last <= TIMEOUT_SECS => {
+                if first.process == hb.process && first.title == hb.title {
+                    (first, hb.time)
+                } else {
+                    push_session(&mut sessions, first, hb.time);
+                    (hb, hb.time)
+                }
+            }
+            Some((first, last)) => {
+                push_session(&mut sessions, first, last);
+                (hb, hb.time)
+            }
+            None => (hb, hb.time),
+        });
+    }
+    if let Some((first, last)) = current {
+        push_session(&mut sessions, first, last);
+    }
+
+    Ok((into_timeline(sessions), skipped))
+}
+
+fn push_session(sessions: &mut Vec<ImportedSession>, first: &Heartbeat, end: f64) {
+    if let (Some(start), Some(end)) = (to_utc(first.time), to_utc(end)) {
+        sessions.push(ImportedSession::new(
+            &first.process,
+            first.title.clone(),
+            SessionKind::Active,
+            start,
+            end,
+        ));
+    }
+}
+
+fn to_utc(secs: f64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_millis((secs * 1000.0).round() as i64)
+}
+
+/// A heartbeat becomes "<project> - <file>" in the editor's process, so
+/// sessions group by editor and the title says what was worked on.
+fn parse_heartbeat(hb: &Value) -> Result<Heartbeat, String> {
+    let time = hb
+        .get("time")
+        .and_then(Value::as_f64)
+        .filter(|t| t.is_finite() && *t > 0.0)
+        .ok_or("missing time")?;
+
+    let text = |key: &str| {
+        hb.get(key)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+    };
+    let process = text("editor")
+        .map(str::to_lowercase)
+        .unwrap_or_else(|| DEFAULT_PROCESS.to_string());
+    let entity = text("entity").map(|e| match text("type") {
+        Some("file") | None => e.rsplit(['/', '\\']).next().unwrap_or(e),
+        Some(_) => e,
+    });
+    let title = match (text("project"), entity) {
+        (Some(project), Some(entity)) => Some(format!("{} - {}", project, entity)),
+        (Some(project), None) => Some(project.to_string()),
+        (None, entity) => entity.map(str::to_string),
+    };
+
+    Ok(Heartbeat {
+        time,
+        process,
+        title,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spans(sessions: &[ImportedSession]) -> Vec<(String, String, String)> {
+        sessions
+            .iter()
+            .map(|s| {
+                (
+                    s.session.window_title.clone().unwrap_or_default(),
+                    s.session.start_time.clone(),
+                    s.session.end_time.clone().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_heartbeats_become_sessions() {
+        // 2026-04-01T10:00:00Z
+        let t0 = 1_775_037_600.0;
+        let hb = |offset: f64, entity: &str| json!({"time": t0 + offset, "entity": entity, "type": "file", "project": "laches"});
+        let export = json!({
+            "user": {},
+            "days": [
+                {"date": "2026-04-01", "heartbeats": [
+                    hb(0.0, "/src/db.rs"),
+                    hb(120.0, "/src/db.rs"),
+                    // file switch: db.rs runs until here
+                    hb(300.0, "/src/main.rs"),
+                    // past the timeout: the lone main.rs heartbeat before it covers no time
+                    hb(300.0 + TIMEOUT_SECS + 1.0, "/src/main.rs"),
+                    hb(300.0 + TIMEOUT_SECS + 61.0, "/src/main.rs"),
+                    {"entity": "no time"}
+                ]}
+            ]
+        });
+
+        let (sessions, skipped) = parse(&export).unwrap();
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(sessions[0].session.process_name, "wakatime");
+        assert_eq!(
+            spans(&sessions),
+            vec![
+                (
+                    "laches - db.rs".to_string(),
+                    "2026-04-01T10:00:00.000Z".to_string(),
+                    "2026-04-01T10:05:00.000Z".to_string()
+                ),
+                (
+                    "laches - main.rs".to_string(),
+                    "2026-04-01T10:20:01.000Z".to_string(),
+                    "2026-04-01T10:21:01.000Z".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_summaries_dump_is_rejected() {
+        let export = json!({"days": [{"date": "2026-04-01", "grand_total": {}}]});
+        assert!(parse(&export).is_err());
+
+        let api = json!({"data": [{"time": 1_775_037_600.0, "editor": "VS Code"}]});
+        let (sessions, _) = parse(&api).unwrap();
+        // a lone heartbeat covers no time
+        assert!(sessions.is_empty());
+    }
+}
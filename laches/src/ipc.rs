@@ -0,0 +1,295 @@
+//! Control channel between the CLI (or TUI) and the daemon.
+//!
+//! The daemon listens on a unix socket, `$XDG_RUNTIME_DIR/lachesis.sock`
+//! (or `lachesis.sock` in the config directory when that is unset). A
+//! client sends one command per connection as a line of text and gets one
+//! line of JSON back: `{"ok": true, ...}` or `{"ok": false, "error": ...}`.
+//!
+//! Only unix platforms have the socket; elsewhere every request fails and
+//! callers fall back to the pid file.
+
+use crate::error::LachesError;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// File name of the control socket.
+pub const SOCKET_NAME: &str = "lachesis.sock";
+
+/// How long a client waits for the daemon to answer.
+pub const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A request to the daemon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Report what is being tracked.
+    Status,
+    /// Stop recording until resumed, or until the given time.
+    Pause(Option<DateTime<Utc>>),
+    /// Start recording again after a pause.
+    Resume,
+    /// Re-read config.toml.
+    Reload,
+    /// Close the open session and exit.
+    Shutdown,
+}
+
+impl Command {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Command::Status => "status",
+            Command::Pause(_) => "pause",
+            Command::Resume => "resume",
+            Command::Reload => "reload",
+            Command::Shutdown => "shutdown",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        let (name, arg) = match s.trim().split_once(' ') {
+            Some((name, arg)) => (name, Some(arg.trim())),
+            None => (s.trim(), None),
+        };
+        if name == "pause" {
+            let until = match arg {
+                Some(arg) => Some(DateTime::parse_from_rfc3339(arg).ok()?.with_timezone(&Utc)),
+                None => None,
+            };
+            return Some(Command::Pause(until));
+        }
+        if arg.is_some() {
+            return None;
+        }
+        match name {
+            "status" => Some(Command::Status),
+            "resume" => Some(Command::Resume),
+            "reload" => Some(Command::Reload),
+            "shutdown" => Some(Command::Shutdown),
+            _ => None,
+        }
+    }
+}
+
+/// The command as sent over the socket, with its argument.
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Command::Pause(Some(until)) => {
+                write!(f, "pause {}", crate::db::format_timestamp(until))
+            }
+            _ => f.write_str(self.as_str()),
+        }
+    }
+}
+
+/// Where the daemon for `config_dir` listens.
+pub fn socket_path(config_dir: &Path) -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir).join(SOCKET_NAME),
+        _ => config_dir.join(SOCKET_NAME),
+    }
+}
+
+/// A successful reply with no fields of its own.
+pub fn reply_ok() -> Value {
+    serde_json::json!({ "ok": true })
+}
+
+/// A failed reply.
+pub fn reply_error(message: &str) -> Value {
+    serde_json::json!({ "ok": false, "error": message })
+}
+
+/// Send `command` to the daemon and return its reply. Fails if nothing is
+/// listening or the daemon reports an error.
+pub fn request(socket: &Path, command: Command) -> Result<Value, LachesError> {
+    request_with_timeout(socket, command, REPLY_TIMEOUT)
+}
+
+#[cfg(unix)]
+pub fn request_with_timeout(
+    socket: &Path,
+    command: Command,
+    timeout: Duration,
+) -> Result<Value, LachesError> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(socket)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    writeln!(stream, "{}", command)?;
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    let reply: Value = serde_json::from_str(&line)
+        .map_err(|e| LachesError::InvalidInput(format!("bad reply from daemon: {}", e)))?;
+    if reply["ok"].as_bool() == Some(true) {
+        Ok(reply)
+    } else {
+        let error = reply["error"].as_str().unwrap_or("unknown error");
+        Err(LachesError::InvalidInput(error.to_string()))
+    }
+}
+
+#[cfg(not(unix))]
+pub fn request_with_timeout(
+    _socket: &Path,
+    _command: Command,
+    _timeout: Duration,
+) -> Result<Value, LachesError> {
+    Err(LachesError::Io(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "the control socket is only available on unix",
+    )))
+}
+
+/// The daemon's end of the control socket.
+#[cfg(unix)]
+pub struct Listener {
+    listener: std::os::unix::net::UnixListener,
+    path: PathBuf,
+}
+
+#[cfg(unix)]
+impl Listener {
+    /// Bind the socket, replacing a stale one left by a daemon that did not
+    /// exit cleanly. Fails if another daemon is still answering on it.
+    pub fn bind(path: &Path) -> std::io::Result<Self> {
+        use std::os::unix::fs::PermissionsExt;
+        use std::os::unix::net::{UnixListener, UnixStream};
+
+        if path.exists() {
+            if UnixStream::connect(path).is_ok() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::AddrInUse,
+                    format!("another daemon is listening on {}", path.display()),
+                ));
+            }
+            std::fs::remove_file(path)?;
+        }
+        let listener = UnixListener::bind(path)?;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        Ok(Listener {
+            listener,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Wait for the next client and read its command. Connections that
+    /// send garbage are answered with an error and skipped.
+    pub fn accept(&self) -> std::io::Result<Connection> {
+        use std::io::{BufRead, BufReader};
+
+        loop {
+            let (stream, _) = self.listener.accept()?;
+            stream.set_read_timeout(Some(REPLY_TIMEOUT))?;
+            stream.set_write_timeout(Some(REPLY_TIMEOUT))?;
+
+            let mut line = String::new();
+            if BufReader::new(&stream).read_line(&mut line).is_err() {
+                continue;
+            }
+            let mut connection = Connection {
+                stream,
+                command: Command::Status,
+            };
+            match Command::parse(&line) {
+                Some(command) => {
+                    connection.command = command;
+                    return Ok(connection);
+                }
+                None => {
+                    connection.reply(&reply_error(&format!("unknown command '{}'", line.trim())))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Listener {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// One client waiting for the answer to its command.
+#[cfg(unix)]
+pub struct Connection {
+    stream: std::os::unix::net::UnixStream,
+    pub command: Command,
+}
+
+#[cfg(unix)]
+impl Connection {
+    /// Send the reply. A client that has gone away is ignored.
+    pub fn reply(mut self, reply: &Value) {
+        use std::io::Write;
+        let _ = writeln!(self.stream, "{}", reply);
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_command_round_trip() {
+        let until = "2026-04-01T17:00:00Z".parse().unwrap();
+        for command in [
+            Command::Status,
+            Command::Pause(None),
+            Command::Pause(Some(until)),
+            Command::Resume,
+            Command::Reload,
+            Command::Shutdown,
+        ] {
+            assert_eq!(Command::parse(&format!("{}\n", command)), Some(command));
+        }
+        assert_eq!(Command::parse("explode"), None);
+        assert_eq!(Command::parse("pause soon"), None);
+        assert_eq!(Command::parse("status now"), None);
+    }
+
+    #[test]
+    fn test_request_and_reply() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join(SOCKET_NAME);
+        let listener = Listener::bind(&path).unwrap();
+        // a second daemon must not steal the socket
+        assert!(Listener::bind(&path).is_err());
+
+        let server = std::thread::spawn(move || {
+            let connection = listener.accept().unwrap();
+            assert_eq!(connection.command, Command::Pause(None));
+            connection.reply(&reply_ok());
+
+            let connection = listener.accept().unwrap();
+            connection.reply(&reply_error("nope"));
+            listener
+        });
+
+        assert_eq!(
+            request(&path, Command::Pause(None)).unwrap()["ok"],
+            Value::Bool(true)
+        );
+        let err = request(&path, Command::Reload).unwrap_err();
+        assert!(err.to_string().contains("nope"));
+
+        drop(server.join().unwrap());
+        assert!(!path.exists());
+        assert!(request(&path, Command::Status).is_err());
+    }
+
+    #[test]
+    fn test_stale_socket_is_replaced() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join(SOCKET_NAME);
+        std::fs::write(&path, "").unwrap();
+        assert!(Listener::bind(&path).is_ok());
+    }
+}
@@ -1,8 +1,18 @@
+pub mod api;
+pub mod category;
 pub mod cli;
 pub mod commands;
 pub mod config;
 pub mod db;
 pub mod error;
+pub mod focus;
+pub mod goals;
+pub mod importers;
+pub mod ipc;
+pub mod limits;
 pub mod platform;
 pub mod process;
+pub mod report;
+pub mod rules;
+pub mod titles;
 pub mod utils;
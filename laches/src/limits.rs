@@ -0,0 +1,259 @@
+//! Checking `[[limits]]` against tracked time.
+
+use crate::config::{Limit, LimitPeriod};
+use crate::db::{date_range_for_day, Database};
+use crate::utils::format_duration_hm;
+use chrono::{DateTime, Datelike, Duration, Local};
+use rusqlite::Result as SqlResult;
+use std::collections::HashMap;
+
+/// How close a limit is to being used up. Ordered, so a later alert
+/// outranks an earlier one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Alert {
+    /// Usage passed this percentage of the limit.
+    Warning(u8),
+    Exceeded,
+}
+
+/// One limit's usage in its current period.
+#[derive(Debug, Clone)]
+pub struct LimitUsage {
+    pub limit: Limit,
+    pub used_secs: i64,
+    pub allowed_secs: i64,
+    /// Local date the period started on, `YYYY-MM-DD`.
+    pub period_start: String,
+}
+
+impl LimitUsage {
+    /// The most severe alert reached, if any.
+    pub fn alert(&self) -> Option<Alert> {
+        if self.used_secs >= self.allowed_secs {
+            return Some(Alert::Exceeded);
+        }
+        self.limit
+            .warn
+            .iter()
+            .filter(|p| self.used_secs * 100 >= self.allowed_secs * i64::from(**p))
+            .max()
+            .map(|p| Alert::Warning(*p))
+    }
+
+    /// Notification title and body for `alert`.
+    pub fn message(&self, alert: Alert) -> (String, String) {
+        let title = match alert {
+            Alert::Warning(p) => format!("{}% of {} limit used", p, self.limit.target()),
+            Alert::Exceeded => format!("{} limit reached", self.limit.target()),
+        };
+        (title, self.summary())
+    }
+
+    /// Usage against the limit, e.g. `1h 5m of 1h 0m today`.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} of {} {}",
+            format_duration_hm(self.used_secs),
+            format_duration_hm(self.allowed_secs),
+            match self.limit.period {
+                LimitPeriod::Daily => "today",
+                LimitPeriod::Weekly => "this week",
+            }
+        )
+    }
+}
+
+/// First local day of the period containing `now`.
+fn period_start(period: LimitPeriod, now: DateTime<Local>) -> chrono::NaiveDate {
+    let today = now.date_naive();
+    match period {
+        LimitPeriod::Daily => today,
+        LimitPeriod::Weekly => {
+            today - Duration::days(i64::from(today.weekday().num_days_from_monday()))
+        }
+    }
+}
+
+/// Active seconds counted against `limit` in its period up to `now`.
+pub fn usage(db: &Database, limit: &Limit, now: DateTime<Local>) -> SqlResult<Option<LimitUsage>> {
+    let Some(allowed_secs) = limit.allowed_secs() else {
+        return Ok(None);
+    };
+    let first = period_start(limit.period, now)
+        .format("%Y-%m-%d")
+        .to_string();
+    let last = now.date_naive().format("%Y-%m-%d").to_string();
+    let (Some((start, _)), Some((_, end))) =
+        (date_range_for_day(&first), date_range_for_day(&last))
+    else {
+        return Ok(None);
+    };
+
+    let used_secs =
+        db.query_target_seconds(&start, &end, limit.process.as_deref(), limit.tag.as_deref())?;
+
+    Ok(Some(LimitUsage {
+        limit: limit.clone(),
+        used_secs,
+        allowed_secs,
+        period_start: first,
+    }))
+}
+
+/// Remembers the alerts already sent, so each one fires once per period
+/// and only when usage moves past a new threshold.
+#[derive(Default)]
+pub struct LimitAlerts {
+    sent: HashMap<String, (String, Alert)>,
+}
+
+impl LimitAlerts {
+    /// Check every limit and return those that reached a new alert since
+    /// the last call, with the alert.
+    pub fn check(
+        &mut self,
+        db: &Database,
+        limits: &[Limit],
+        now: DateTime<Local>,
+    ) -> SqlResult<Vec<(LimitUsage, Alert)>> {
+        let mut fresh = Vec::new();
+        for limit in limits {
+            let Some(usage) = usage(db, limit, now)? else {
+                continue;
+            };
+            let Some(alert) = usage.alert() else {
+                continue;
+            };
+            let key = limit.to_string();
+            let already = self
+                .sent
+                .get(&key)
+                .is_some_and(|(period, sent)| *period == usage.period_start && *sent >= alert);
+            if !already {
+                self.sent.insert(key, (usage.period_start.clone(), alert));
+                fresh.push((usage, alert));
+            }
+        }
+        Ok(fresh)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{format_timestamp, SessionKind};
+    use chrono::TimeZone;
+
+    fn limit(process: Option<&str>, tag: Option<&str>, period: LimitPeriod) -> Limit {
+        Limit {
+            process: process.map(str::to_string),
+            tag: tag.map(str::to_string),
+            period,
+            limit: "1h".to_string(),
+            warn: vec![50, 80],
+        }
+    }
+
+    fn record(db: &Database, process: &str, start: DateTime<Local>, minutes: i64) {
+        let end = start + Duration::minutes(minutes);
+        db.insert_session(
+            process,
+            SessionKind::Active,
+            &format_timestamp(&start),
+            &format_timestamp(&end),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_alert_levels() {
+        let usage = |used_secs| LimitUsage {
+            limit: limit(None, None, LimitPeriod::Daily),
+            used_secs,
+            allowed_secs: 3600,
+            period_start: String::new(),
+        };
+        assert_eq!(usage(1000).alert(), None);
+        assert_eq!(usage(1800).alert(), Some(Alert::Warning(50)));
+        assert_eq!(usage(3000).alert(), Some(Alert::Warning(80)));
+        assert_eq!(usage(3600).alert(), Some(Alert::Exceeded));
+        assert!(Alert::Warning(80) > Alert::Warning(50));
+        assert!(Alert::Exceeded > Alert::Warning(99));
+    }
+
+    #[test]
+    fn test_usage_by_target_and_period() {
+        let db = Database::open_memory().unwrap();
+        db.add_tag("firefox", "web").unwrap();
+        // a wednesday
+        let now = Local.with_ymd_and_hms(2026, 4, 1, 15, 0, 0).unwrap();
+        record(&db, "firefox", now - Duration::hours(2), 30);
+        record(&db, "code", now - Duration::hours(1), 20);
+        record(&db, "firefox", now - Duration::days(2), 40);
+
+        let used = |l: Limit| usage(&db, &l, now).unwrap().unwrap().used_secs / 60;
+        assert_eq!(used(limit(Some("firefox"), None, LimitPeriod::Daily)), 30);
+        assert_eq!(used(limit(None, Some("web"), LimitPeriod::Daily)), 30);
+        assert_eq!(used(limit(None, None, LimitPeriod::Daily)), 50);
+        assert_eq!(used(limit(Some("firefox"), None, LimitPeriod::Weekly)), 70);
+        assert_eq!(
+            usage(&db, &limit(None, None, LimitPeriod::Weekly), now)
+                .unwrap()
+                .unwrap()
+                .period_start,
+            "2026-03-30"
+        );
+    }
+
+    #[test]
+    fn test_tag_limits_count_rule_tags() {
+        use crate::config::{MatchKind, Rule, RuleAction, RuleField};
+
+        let db = Database::open_memory().unwrap();
+        db.set_rules(&[Rule {
+            field: RuleField::Process,
+            kind: MatchKind::Glob,
+            pattern: "fire*".to_string(),
+            action: RuleAction::Tag,
+            tag: Some("web".to_string()),
+            category: None,
+            process: None,
+        }])
+        .unwrap();
+        let now = Local.with_ymd_and_hms(2026, 4, 1, 15, 0, 0).unwrap();
+        let limits = vec![limit(None, Some("web"), LimitPeriod::Daily)];
+        record(&db, "firefox", now - Duration::hours(2), 70);
+        record(&db, "code", now - Duration::minutes(30), 20);
+
+        let fired = LimitAlerts::default().check(&db, &limits, now).unwrap();
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].0.used_secs, 4200);
+        assert_eq!(fired[0].1, Alert::Exceeded);
+    }
+
+    #[test]
+    fn test_alerts_fire_once_per_level() {
+        let db = Database::open_memory().unwrap();
+        let now = Local.with_ymd_and_hms(2026, 4, 1, 15, 0, 0).unwrap();
+        let limits = vec![limit(Some("firefox"), None, LimitPeriod::Daily)];
+        let mut alerts = LimitAlerts::default();
+
+        record(&db, "firefox", now - Duration::hours(3), 35);
+        let fired = alerts.check(&db, &limits, now).unwrap();
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].1, Alert::Warning(50));
+        assert!(alerts.check(&db, &limits, now).unwrap().is_empty());
+
+        record(&db, "firefox", now - Duration::hours(2), 30);
+        let fired = alerts.check(&db, &limits, now).unwrap();
+        assert_eq!(fired[0].1, Alert::Exceeded);
+        let (title, body) = fired[0].0.message(Alert::Exceeded);
+        assert_eq!(title, "firefox limit reached");
+        assert_eq!(body, "1h 5m of 1h 0m today");
+
+        // a new day starts over
+        let tomorrow = now + Duration::days(1);
+        record(&db, "firefox", tomorrow - Duration::hours(1), 40);
+        assert_eq!(alerts.check(&db, &limits, tomorrow).unwrap().len(), 1);
+    }
+}
@@ -2,14 +2,31 @@ use clap::Parser;
 use laches::{
     cli::{Cli, Commands, ConfigAction, DataAction},
     commands::{
+        api::handle_api_config,
         autostart::handle_autostart,
+        category::handle_category,
+        daemon::{handle_daemon_command, handle_pause},
         data,
-        list::{print_process_summaries, print_sessions, resolve_time_range},
+        edit::{handle_add, handle_edit, handle_merge, handle_split, SessionEdit},
+        focus::handle_focus,
+        goals::handle_goals,
+        import,
+        limits::handle_limit_command,
+        list::{
+            print_category_tree, print_heatmap, print_process_summaries, print_sessions,
+            resolve_time_range,
+        },
+        report::handle_report,
+        rules::handle_rule_command,
+        storage::set_store_path,
         summary::print_summary,
+        titles::print_titles,
     },
     config::{get_machine_id, load_or_create_config, save_config, FilterPattern},
     db::Database,
+    ipc,
     process::{start_monitoring, stop_monitoring},
+    titles::TitleNormalizer,
 };
 use std::error::Error;
 
@@ -23,16 +40,49 @@ fn main() -> Result<(), Box<dyn Error>> {
     let mut config = load_or_create_config(&config_dir)?;
     let cli = Cli::parse();
 
+    // relocating the data directory moves the database files, so it has to
+    // run before we hold any of them open
+    if let Commands::Config {
+        action: Some(ConfigAction::StorePath { path }),
+    } = &cli.command
+    {
+        return set_store_path(&mut config, &config_dir, path);
+    }
+
+    if let Some(tz) = &cli.tz {
+        laches::utils::set_report_timezone(tz)?;
+    }
+
     let machine_id = get_machine_id(&config_dir);
-    let data_dir = laches::config::data_dir(&config_dir);
+    let data_dir = laches::config::data_dir(&config_dir, &config);
     std::fs::create_dir_all(&data_dir)?;
 
-    let db_path = laches::config::machine_db_path(&config_dir, &machine_id);
+    let db_path = laches::config::machine_db_path(&config_dir, &config, &machine_id);
     let db = Database::open(&db_path)?;
+    db.set_rules(&config.rules)?;
 
     match &cli.command {
         Commands::Start => Ok(start_monitoring(&config_dir)?),
         Commands::Stop => Ok(stop_monitoring(&config_dir)?),
+        Commands::Status => handle_daemon_command(&config_dir, ipc::Command::Status),
+        Commands::Pause { duration, until } => {
+            handle_pause(&config_dir, duration.as_deref(), until.as_deref())
+        }
+        Commands::Resume => handle_daemon_command(&config_dir, ipc::Command::Resume),
+        Commands::Reload => handle_daemon_command(&config_dir, ipc::Command::Reload),
+        Commands::Focus {
+            duration,
+            allow,
+            break_length,
+            stop,
+        } => handle_focus(
+            &db,
+            &config_dir,
+            duration.as_deref(),
+            allow,
+            break_length.as_deref(),
+            *stop,
+        ),
 
         Commands::List {
             tag,
@@ -44,19 +94,36 @@ fn main() -> Result<(), Box<dyn Error>> {
             sessions,
             verbose,
             all_machines,
+            by_machine,
+            by_category,
+            heatmap,
         } => {
-            if *all_machines {
-                eprintln!(
-                    "warning: --all-machines is not yet implemented, showing local machine only"
-                );
-            }
+            let merged = if *all_machines {
+                Some(open_all_machines(&data_dir, &config)?)
+            } else {
+                None
+            };
+            let db = merged.as_ref().unwrap_or(&db);
+
             let (start, end, label) =
                 resolve_time_range(*today, *week, *month, date.as_deref(), range.as_deref())?;
 
             if *sessions {
-                print_sessions(&db, &start, &end, &label)?;
+                print_sessions(db, &start, &end, &label)?;
+            } else if *heatmap {
+                print_heatmap(db, &start, &end, &label)?;
+            } else if *by_category {
+                print_category_tree(db, &start, &end, &label, *verbose)?;
             } else {
-                print_process_summaries(&db, &start, &end, &label, tag.as_deref(), *verbose)?;
+                print_process_summaries(
+                    db,
+                    &start,
+                    &end,
+                    &label,
+                    tag.as_deref(),
+                    *verbose,
+                    *by_machine,
+                )?;
             }
 
             Ok(())
@@ -64,6 +131,91 @@ fn main() -> Result<(), Box<dyn Error>> {
 
         Commands::Summary => print_summary(&db),
 
+        Commands::Titles {
+            process,
+            today,
+            week,
+            month,
+            date,
+            range,
+            raw,
+            verbose,
+            limit,
+            all_machines,
+        } => {
+            let merged = if *all_machines {
+                Some(open_all_machines(&data_dir, &config)?)
+            } else {
+                None
+            };
+            let (start, end, label) =
+                resolve_time_range(*today, *week, *month, date.as_deref(), range.as_deref())?;
+            let normalizer = TitleNormalizer::new(&config.title_extractors)?;
+            print_titles(
+                merged.as_ref().unwrap_or(&db),
+                process,
+                (&start, &end, &label),
+                (!*raw).then_some(&normalizer),
+                *verbose,
+                *limit,
+            )
+        }
+
+        Commands::Report {
+            week: _,
+            month,
+            range,
+            format,
+            output,
+            all_machines,
+        } => {
+            let merged = if *all_machines {
+                Some(open_all_machines(&data_dir, &config)?)
+            } else {
+                None
+            };
+            handle_report(
+                merged.as_ref().unwrap_or(&db),
+                &config,
+                *month,
+                range.as_deref(),
+                *format,
+                output.as_deref(),
+            )
+        }
+
+        Commands::Add {
+            process,
+            from,
+            to,
+            title,
+            tag,
+        } => handle_add(&db, process, from, to, title.as_deref(), tag.as_deref()),
+
+        Commands::Edit {
+            id,
+            process,
+            title,
+            from,
+            to,
+            idle,
+            active,
+        } => handle_edit(
+            &db,
+            *id,
+            &SessionEdit {
+                process: process.as_deref(),
+                title: title.as_deref(),
+                from: from.as_deref(),
+                to: to.as_deref(),
+                idle: *idle,
+                active: *active,
+            },
+        ),
+
+        Commands::Split { id, at } => handle_split(&db, *id, at),
+        Commands::Merge { first, second } => handle_merge(&db, *first, *second),
+
         Commands::Tag {
             process,
             add,
@@ -108,6 +260,12 @@ fn main() -> Result<(), Box<dyn Error>> {
             Ok(())
         }
 
+        Commands::Category {
+            process,
+            set,
+            clear,
+        } => handle_category(&db, &config, process.as_deref(), set.as_deref(), *clear),
+
         Commands::Whitelist { action } => {
             handle_filter_list_action(&mut config, &config_dir, action, true)
         }
@@ -115,6 +273,23 @@ fn main() -> Result<(), Box<dyn Error>> {
             handle_filter_list_action(&mut config, &config_dir, action, false)
         }
 
+        Commands::Rule { action } => handle_rule_command(&mut config, &config_dir, action),
+
+        Commands::Limit { action } => handle_limit_command(&db, &mut config, &config_dir, action),
+
+        Commands::Goals {
+            days,
+            recompute,
+            action,
+        } => handle_goals(
+            &db,
+            &mut config,
+            &config_dir,
+            *days,
+            *recompute,
+            action.as_ref(),
+        ),
+
         Commands::Mode { mode } => {
             config.filtering.mode = mode.clone().into();
             save_config(&config, &config_dir)?;
@@ -125,16 +300,20 @@ fn main() -> Result<(), Box<dyn Error>> {
         Commands::Autostart { toggle } => handle_autostart(toggle, &config_dir),
 
         Commands::Config { action } => match action {
-            Some(ConfigAction::StorePath { path: _ }) => {
-                eprintln!("warning: store-path is not yet implemented");
-                Ok(())
+            Some(ConfigAction::StorePath { .. }) => {
+                unreachable!("handled before opening the database")
+            }
+            Some(ConfigAction::Api { state }) => {
+                handle_api_config(&mut config, &config_dir, *state)
             }
             None => {
                 println!("configuration:");
                 println!("  config dir: {}", config_dir.display());
+                println!("  data dir: {}", data_dir.display());
                 println!("  machine id: {}", machine_id);
                 println!("  check interval: {}s", config.daemon.check_interval);
                 println!("  idle timeout: {}s", config.daemon.idle_timeout);
+                println!("  tracker backend: {}", config.daemon.backend);
                 println!("  filter mode: {}", config.filtering.mode);
 
                 if !config.filtering.whitelist.is_empty() {
@@ -155,22 +334,19 @@ fn main() -> Result<(), Box<dyn Error>> {
                         .collect();
                     println!("  blacklist: {}", patterns.join(", "));
                 }
+                if !config.rules.is_empty() {
+                    println!("  rules: {}", config.rules.len());
+                }
+                if config.api.enabled {
+                    println!("  api: http://127.0.0.1:{}", config.api.port);
+                }
 
-                let data_dir = laches::config::data_dir(&config_dir);
-                if data_dir.exists() {
-                    let dbs: Vec<_> = std::fs::read_dir(&data_dir)?
-                        .filter_map(|e| e.ok())
-                        .filter(|e| e.path().extension().is_some_and(|ext| ext == "db"))
-                        .collect();
-
-                    if !dbs.is_empty() {
-                        println!("\n  synced machines:");
-                        for entry in dbs {
-                            let name = entry.file_name();
-                            let name = name.to_string_lossy().replace(".db", "");
-                            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
-                            println!("    - {} ({:.1} KB)", name, size as f64 / 1024.0);
-                        }
+                let dbs = laches::config::list_machine_dbs(&data_dir)?;
+                if !dbs.is_empty() {
+                    println!("\n  synced machines:");
+                    for (name, path) in dbs {
+                        let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                        println!("    - {} ({:.1} KB)", name, size as f64 / 1024.0);
                     }
                 }
 
@@ -182,14 +358,42 @@ fn main() -> Result<(), Box<dyn Error>> {
             DataAction::Export {
                 output,
                 duration,
+                format,
+                group_by,
                 all_machines,
             } => {
-                if *all_machines {
-                    eprintln!("warning: --all-machines is not yet implemented, exporting local machine only");
-                }
-                data::export_sessions(&db, output, duration.as_deref())
+                let merged;
+                let source = if *all_machines {
+                    merged = open_all_machines(&data_dir, &config)?;
+                    &merged
+                } else {
+                    &db
+                };
+                data::export_sessions(
+                    source,
+                    &config.rules,
+                    output,
+                    duration.as_deref(),
+                    *format,
+                    *group_by,
+                )
             }
 
+            DataAction::Import {
+                input,
+                from,
+                format,
+                machine,
+            } => import::import_sessions(
+                &db,
+                &data_dir,
+                &config.rules,
+                input,
+                *from,
+                *format,
+                machine.as_deref(),
+            ),
+
             DataAction::Delete { all, duration } => {
                 data::delete_sessions(&db, *all, duration.as_deref())
             }
@@ -199,6 +403,23 @@ fn main() -> Result<(), Box<dyn Error>> {
     }
 }
 
+/// Open a read-only merge of every machine database in the data directory.
+fn open_all_machines(
+    data_dir: &std::path::Path,
+    config: &laches::config::Config,
+) -> Result<Database, Box<dyn Error>> {
+    let sources = laches::config::list_machine_dbs(data_dir)?;
+    let (db, skipped) = Database::open_merged(&sources)?;
+    db.set_rules(&config.rules)?;
+    for machine in skipped {
+        eprintln!(
+            "warning: skipping '{}': database schema version differs, run laches on that machine to upgrade it",
+            machine
+        );
+    }
+    Ok(db)
+}
+
 fn handle_filter_list_action(
     config: &mut laches::config::Config,
     config_dir: &std::path::Path,
@@ -0,0 +1,147 @@
+use super::{process_from_app_id, process_from_pid};
+use crate::platform::{FocusInfo, FocusTracker};
+use serde_json::Value;
+use std::sync::Mutex;
+use std::time::Duration;
+use zbus::blocking::Connection;
+
+const SHELL_BUS: &str = "org.gnome.Shell";
+
+/// "Focused Window D-Bus" extension: `Get` returns the focused window as JSON.
+const FOCUSED_WINDOW_PATH: &str = "/org/gnome/shell/extensions/FocusedWindow";
+const FOCUSED_WINDOW_IFACE: &str = "org.gnome.shell.extensions.FocusedWindow";
+
+/// "Window Calls" extension: `List` returns every window as a JSON array.
+const WINDOW_CALLS_PATH: &str = "/org/gnome/Shell/Extensions/Windows";
+const WINDOW_CALLS_IFACE: &str = "org.gnome.Shell.Extensions.Windows";
+
+/// Focus tracker for GNOME Shell on Wayland.
+///
+/// Mutter does not expose the focused window to clients, so this relies on
+/// a shell extension publishing it on the session bus. Either the
+/// "Focused Window D-Bus" or the "Window Calls" extension must be enabled;
+/// without one, every poll returns `None`.
+pub struct GnomeFocusTracker {
+    conn: Mutex<Option<Connection>>,
+}
+
+impl GnomeFocusTracker {
+    pub fn new() -> Self {
+        GnomeFocusTracker {
+            conn: Mutex::new(None),
+        }
+    }
+
+    /// Call a shell extension method that returns a single string.
+    fn call<B>(&self, path: &str, iface: &str, method: &str, body: &B) -> Option<String>
+    where
+        B: serde::Serialize + zbus::zvariant::DynamicType,
+    {
+        let mut guard = self.conn.lock().ok()?;
+        if guard.is_none() {
+            *guard = Some(Connection::session().ok()?);
+        }
+        let conn = guard.as_ref()?;
+
+        let reply = conn
+            .call_method(Some(SHELL_BUS), path, Some(iface), method, body)
+            .ok()?;
+        reply.body().deserialize::<String>().ok()
+    }
+
+    fn focused_window_extension(&self) -> Option<Value> {
+        let json = self.call(FOCUSED_WINDOW_PATH, FOCUSED_WINDOW_IFACE, "Get", &())?;
+        serde_json::from_str(&json).ok()
+    }
+
+    fn window_calls_extension(&self) -> Option<Value> {
+        let json = self.call(WINDOW_CALLS_PATH, WINDOW_CALLS_IFACE, "List", &())?;
+        let windows: Value = serde_json::from_str(&json).ok()?;
+        let mut focused = windows
+            .as_array()?
+            .iter()
+            .find(|w| w.get("focus").and_then(Value::as_bool) == Some(true))?
+            .clone();
+
+        // older releases of the extension only expose titles via GetTitle
+        if focused.get("title").is_none() {
+            if let Some(id) = focused.get("id").and_then(Value::as_u64) {
+                if let Some(title) = self.call(
+                    WINDOW_CALLS_PATH,
+                    WINDOW_CALLS_IFACE,
+                    "GetTitle",
+                    &(id as u32),
+                ) {
+                    focused["title"] = Value::String(title);
+                }
+            }
+        }
+        Some(focused)
+    }
+}
+
+/// Build FocusInfo from either extension's window JSON. Both use the same
+/// `title` / `wm_class` / `pid` field names.
+fn focus_from_window(window: &Value) -> Option<FocusInfo> {
+    let window_title = window
+        .get("title")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+
+    let pid = window.get("pid").and_then(Value::as_u64).unwrap_or(0) as u32;
+    if let Some((process_name, exe_path)) = process_from_pid(pid) {
+        return Some(FocusInfo {
+            process_name,
+            exe_path,
+            window_title,
+        });
+    }
+
+    let class = window.get("wm_class").and_then(Value::as_str)?;
+    Some(FocusInfo {
+        process_name: process_from_app_id(class)?,
+        exe_path: None,
+        window_title,
+    })
+}
+
+impl FocusTracker for GnomeFocusTracker {
+    fn name(&self) -> &'static str {
+        "gnome"
+    }
+
+    fn get_focused_window(&self) -> Option<FocusInfo> {
+        let window = self
+            .focused_window_extension()
+            .or_else(|| self.window_calls_extension())?;
+        focus_from_window(&window)
+    }
+
+    fn get_idle_duration(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_focus_from_window_json() {
+        let window = json!({
+            "title": "Inbox - Mail",
+            "wm_class": "org.gnome.Evolution",
+            "pid": 0,
+            "focus": true,
+        });
+        let info = focus_from_window(&window).unwrap();
+        assert_eq!(info.process_name, "evolution");
+        assert_eq!(info.window_title.as_deref(), Some("Inbox - Mail"));
+    }
+
+    #[test]
+    fn test_focus_from_window_without_class_or_pid() {
+        assert!(focus_from_window(&json!({"title": "x"})).is_none());
+    }
+}
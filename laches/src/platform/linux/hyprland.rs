@@ -0,0 +1,162 @@
+use super::{process_from_app_id, process_from_pid};
+use crate::platform::{FocusInfo, FocusTracker, FocusWaker};
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Focus tracker for Hyprland via its request socket (`.socket.sock`).
+///
+/// Hyprland closes the connection after every reply, so each poll opens a
+/// fresh one and sends `j/activewindow`. Focus changes are pushed on the
+/// separate event socket (`.socket2.sock`).
+pub struct HyprlandFocusTracker {
+    socket_path: Option<PathBuf>,
+    event_socket_path: Option<PathBuf>,
+}
+
+impl HyprlandFocusTracker {
+    pub fn new() -> Self {
+        HyprlandFocusTracker {
+            socket_path: socket_path(".socket.sock"),
+            event_socket_path: socket_path(".socket2.sock"),
+        }
+    }
+}
+
+/// Locate one of Hyprland's sockets. Hyprland >= 0.40 uses
+/// `$XDG_RUNTIME_DIR/hypr`, older releases used `/tmp/hypr`.
+fn socket_path(file_name: &str) -> Option<PathBuf> {
+    let signature = std::env::var("HYPRLAND_INSTANCE_SIGNATURE").ok()?;
+
+    let mut candidates = Vec::new();
+    if let Some(runtime) = std::env::var_os("XDG_RUNTIME_DIR") {
+        candidates.push(PathBuf::from(runtime).join("hypr"));
+    }
+    candidates.push(PathBuf::from("/tmp/hypr"));
+
+    candidates
+        .into_iter()
+        .map(|dir| dir.join(&signature).join(file_name))
+        .find(|p| p.exists())
+}
+
+/// Whether an event socket line (`name>>data`) changes the focused window
+/// or its title.
+fn is_focus_event(line: &str) -> bool {
+    let name = line.split_once(">>").map_or(line, |(name, _)| name);
+    matches!(name, "activewindow" | "windowtitle")
+}
+
+/// Build FocusInfo from an `activewindow` reply. An empty object means no
+/// window is focused.
+fn focus_from_reply(reply: &Value) -> Option<FocusInfo> {
+    let window_title = reply
+        .get("title")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+
+    let pid = reply.get("pid").and_then(Value::as_i64).unwrap_or(0);
+    if let Some((process_name, exe_path)) = process_from_pid(pid.max(0) as u32) {
+        return Some(FocusInfo {
+            process_name,
+            exe_path,
+            window_title,
+        });
+    }
+
+    let class = reply.get("class").and_then(Value::as_str)?;
+    Some(FocusInfo {
+        process_name: process_from_app_id(class)?,
+        exe_path: None,
+        window_title,
+    })
+}
+
+impl FocusTracker for HyprlandFocusTracker {
+    fn name(&self) -> &'static str {
+        "hyprland"
+    }
+
+    fn get_focused_window(&self) -> Option<FocusInfo> {
+        let mut stream = UnixStream::connect(self.socket_path.as_ref()?).ok()?;
+        stream.set_read_timeout(Some(Duration::from_secs(1))).ok()?;
+        stream.write_all(b"j/activewindow").ok()?;
+
+        let mut reply = Vec::new();
+        stream.read_to_end(&mut reply).ok()?;
+        let reply: Value = serde_json::from_slice(&reply).ok()?;
+        focus_from_reply(&reply)
+    }
+
+    fn subscribe(&self, wake: FocusWaker) -> bool {
+        let Some(path) = self.event_socket_path.as_ref() else {
+            return false;
+        };
+        let Ok(stream) = UnixStream::connect(path) else {
+            return false;
+        };
+
+        // runs until the compositor goes away; polling covers the rest
+        std::thread::Builder::new()
+            .name("laches-hypr-events".into())
+            .spawn(move || {
+                for line in BufReader::new(stream).lines() {
+                    let Ok(line) = line else {
+                        return;
+                    };
+                    if is_focus_event(&line) {
+                        wake();
+                    }
+                }
+            })
+            .is_ok()
+    }
+
+    fn get_idle_duration(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_focus_from_reply_uses_class_without_pid() {
+        let reply = json!({
+            "address": "0x55d1",
+            "class": "kitty",
+            "title": "~/src",
+            "pid": -1,
+        });
+        let info = focus_from_reply(&reply).unwrap();
+        assert_eq!(info.process_name, "kitty");
+        assert_eq!(info.window_title.as_deref(), Some("~/src"));
+    }
+
+    #[test]
+    fn test_empty_reply_has_no_window() {
+        assert!(focus_from_reply(&json!({})).is_none());
+    }
+
+    #[test]
+    fn test_is_focus_event() {
+        assert!(is_focus_event("activewindow>>kitty,~/src"));
+        assert!(is_focus_event("windowtitle>>55d1"));
+        assert!(!is_focus_event("activewindowv2>>55d1"));
+        assert!(!is_focus_event("workspace>>2"));
+    }
+
+    #[test]
+    fn test_missing_socket_returns_none() {
+        let tracker = HyprlandFocusTracker {
+            socket_path: None,
+            event_socket_path: None,
+        };
+        assert!(tracker.get_focused_window().is_none());
+        assert!(!tracker.subscribe(Box::new(|| {})));
+    }
+}
@@ -0,0 +1,488 @@
+use crate::platform::{FocusTracker, PowerEvent};
+use chrono::Local;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::{wl_registry::WlRegistry, wl_seat::WlSeat};
+use wayland_client::{Connection as WaylandConnection, Dispatch, EventQueue, QueueHandle};
+use wayland_protocols::ext::idle_notify::v1::client::{
+    ext_idle_notification_v1::{self, ExtIdleNotificationV1},
+    ext_idle_notifier_v1::ExtIdleNotifierV1,
+};
+use zbus::blocking::{Connection, Proxy};
+use zbus::message::Message;
+use zbus::proxy::CacheProperties;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath};
+
+/// Timeout of the ext-idle-notify notification. The compositor only says
+/// when the user has been idle this long, so shorter idle stretches read
+/// as zero.
+const IDLE_NOTIFY_TIMEOUT: Duration = Duration::from_secs(5);
+
+const LOGIND_BUS: &str = "org.freedesktop.login1";
+const LOGIND_SESSION_IFACE: &str = "org.freedesktop.login1.Session";
+const SCREENSAVER_BUS: &str = "org.freedesktop.ScreenSaver";
+const MUTTER_IDLE_BUS: &str = "org.gnome.Mutter.IdleMonitor";
+
+/// Where the idle time comes from. Probed once at startup in order of
+/// preference; the first source that answers is used from then on.
+enum IdleSource {
+    /// Wayland `ext-idle-notify-v1` (sway, Hyprland, KWin, most wlroots).
+    IdleNotify(Mutex<Option<IdleNotify>>),
+    /// GNOME's `org.gnome.Mutter.IdleMonitor.GetIdletime`, in milliseconds.
+    Mutter(Proxy<'static>),
+    /// `org.freedesktop.ScreenSaver.GetSessionIdleTime`, in seconds (KDE).
+    ScreenSaver(Proxy<'static>),
+    /// The focus tracker's own idle time (XScreenSaver on X11).
+    Tracker,
+    /// logind `IdleHint` / `IdleSinceHint`, set by some desktops.
+    Logind(Proxy<'static>),
+    None,
+}
+
+/// Idle, lock and suspend detection shared by every Linux focus tracker.
+///
+/// Under Wayland the X11 screensaver extension only sees XWayland input,
+/// so idle time comes from the compositor or the desktop over D-Bus
+/// instead. Lock state and logind `PrepareForSleep` are followed through
+/// D-Bus signals on background threads.
+pub struct IdleMonitor {
+    source: IdleSource,
+    locked: Arc<AtomicBool>,
+    power_events: Arc<Mutex<Vec<PowerEvent>>>,
+}
+
+impl IdleMonitor {
+    pub fn new(wayland: bool) -> Self {
+        let session = Connection::session().ok();
+        let system = Connection::system().ok();
+
+        let mut source = IdleSource::None;
+        if wayland {
+            if let Ok(notify) = IdleNotify::connect() {
+                source = IdleSource::IdleNotify(Mutex::new(Some(notify)));
+            }
+        }
+        if matches!(source, IdleSource::None) {
+            source = session
+                .as_ref()
+                .and_then(probe_desktop)
+                .unwrap_or(IdleSource::None);
+        }
+        if matches!(source, IdleSource::None) {
+            source = if wayland {
+                system
+                    .as_ref()
+                    .and_then(logind_session)
+                    .map(IdleSource::Logind)
+                    .unwrap_or(IdleSource::None)
+            } else {
+                IdleSource::Tracker
+            };
+        }
+
+        let locked = Arc::new(AtomicBool::new(false));
+        watch_lock(session.as_ref(), system.as_ref(), &locked);
+
+        let power_events = Arc::new(Mutex::new(Vec::new()));
+        if let Some(system) = system.as_ref() {
+            watch_sleep(system, &power_events);
+        }
+
+        IdleMonitor {
+            source,
+            locked,
+            power_events,
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::SeqCst)
+    }
+
+    pub fn take_power_events(&self) -> Vec<PowerEvent> {
+        self.power_events
+            .lock()
+            .map(|mut events| std::mem::take(&mut *events))
+            .unwrap_or_default()
+    }
+
+    /// Name of the idle source in use, for logs.
+    pub fn source_name(&self) -> &'static str {
+        match self.source {
+            IdleSource::IdleNotify(_) => "ext-idle-notify",
+            IdleSource::Mutter(_) => "mutter",
+            IdleSource::ScreenSaver(_) => "screensaver",
+            IdleSource::Tracker => "tracker",
+            IdleSource::Logind(_) => "logind",
+            IdleSource::None => "none",
+        }
+    }
+
+    pub fn idle_duration(&self, tracker: &dyn FocusTracker) -> Duration {
+        match &self.source {
+            IdleSource::IdleNotify(notify) => {
+                let Ok(mut guard) = notify.lock() else {
+                    return Duration::ZERO;
+                };
+                if guard.is_none() {
+                    *guard = IdleNotify::connect().ok();
+                }
+                let Some(notify) = guard.as_mut() else {
+                    return Duration::ZERO;
+                };
+                if notify.queue.roundtrip(&mut notify.state).is_err() {
+                    // compositor went away; reconnect on the next poll
+                    *guard = None;
+                    return Duration::ZERO;
+                }
+                idle_since(notify.state.idled_at, Instant::now())
+            }
+            IdleSource::Mutter(proxy) => proxy
+                .call::<_, _, u64>("GetIdletime", &())
+                .map(Duration::from_millis)
+                .unwrap_or(Duration::ZERO),
+            IdleSource::ScreenSaver(proxy) => proxy
+                .call::<_, _, u32>("GetSessionIdleTime", &())
+                .map(|secs| Duration::from_secs(secs as u64))
+                .unwrap_or(Duration::ZERO),
+            IdleSource::Tracker => tracker.get_idle_duration(),
+            IdleSource::Logind(proxy) => {
+                let hint = proxy.get_property::<bool>("IdleHint").unwrap_or(false);
+                let since = proxy.get_property::<u64>("IdleSinceHint").unwrap_or(0);
+                logind_idle(hint, since, unix_micros())
+            }
+            IdleSource::None => Duration::ZERO,
+        }
+    }
+}
+
+/// Try the desktop idle monitors on the session bus. GNOME ships an
+/// `org.freedesktop.ScreenSaver` too, but without `GetSessionIdleTime`,
+/// so each source is only used if a test call succeeds.
+fn probe_desktop(session: &Connection) -> Option<IdleSource> {
+    if let Some(mutter) = proxy(
+        session,
+        MUTTER_IDLE_BUS,
+        ObjectPath::from_static_str_unchecked("/org/gnome/Mutter/IdleMonitor/Core"),
+        "org.gnome.Mutter.IdleMonitor",
+    ) {
+        if mutter.call::<_, _, u64>("GetIdletime", &()).is_ok() {
+            return Some(IdleSource::Mutter(mutter));
+        }
+    }
+
+    let screensaver = screensaver(session)?;
+    screensaver
+        .call::<_, _, u32>("GetSessionIdleTime", &())
+        .ok()?;
+    Some(IdleSource::ScreenSaver(screensaver))
+}
+
+/// Build an uncached proxy; the idle properties change constantly and are
+/// read once per poll anyway.
+fn proxy(
+    conn: &Connection,
+    destination: &'static str,
+    path: ObjectPath<'static>,
+    interface: &'static str,
+) -> Option<Proxy<'static>> {
+    zbus::blocking::proxy::Builder::new(conn)
+        .destination(destination)
+        .ok()?
+        .path(path)
+        .ok()?
+        .interface(interface)
+        .ok()?
+        .cache_properties(CacheProperties::No)
+        .build()
+        .ok()
+}
+
+fn screensaver(session: &Connection) -> Option<Proxy<'static>> {
+    proxy(
+        session,
+        SCREENSAVER_BUS,
+        ObjectPath::from_static_str_unchecked("/org/freedesktop/ScreenSaver"),
+        "org.freedesktop.ScreenSaver",
+    )
+}
+
+/// Resolve the caller's logind session. Signals are emitted on the real
+/// session path, not on the `session/auto` alias, so look up its id first.
+fn logind_session(system: &Connection) -> Option<Proxy<'static>> {
+    let auto = proxy(
+        system,
+        LOGIND_BUS,
+        ObjectPath::from_static_str_unchecked("/org/freedesktop/login1/session/auto"),
+        LOGIND_SESSION_IFACE,
+    )?;
+    let id: String = auto.get_property("Id").ok()?;
+
+    let manager = proxy(
+        system,
+        LOGIND_BUS,
+        ObjectPath::from_static_str_unchecked("/org/freedesktop/login1"),
+        "org.freedesktop.login1.Manager",
+    )?;
+    let path: OwnedObjectPath = manager.call("GetSession", &id).ok()?;
+
+    proxy(system, LOGIND_BUS, path.into_inner(), LOGIND_SESSION_IFACE)
+}
+
+/// Seed the lock flag from the current state, then keep it updated from
+/// logind `Lock`/`Unlock` and screensaver `ActiveChanged` signals on
+/// background threads.
+fn watch_lock(session: Option<&Connection>, system: Option<&Connection>, locked: &Arc<AtomicBool>) {
+    if let Some(logind) = system.and_then(logind_session) {
+        if logind.get_property::<bool>("LockedHint").unwrap_or(false) {
+            locked.store(true, Ordering::SeqCst);
+        }
+        let locked = locked.clone();
+        spawn_signal_watcher(logind, move |msg| {
+            if let Some(state) = lock_state(msg) {
+                locked.store(state, Ordering::SeqCst);
+            }
+        });
+    }
+
+    if let Some(screensaver) = session.and_then(screensaver) {
+        if screensaver
+            .call::<_, _, bool>("GetActive", &())
+            .unwrap_or(false)
+        {
+            locked.store(true, Ordering::SeqCst);
+        }
+        let locked = locked.clone();
+        spawn_signal_watcher(screensaver, move |msg| {
+            if let Some(state) = lock_state(msg) {
+                locked.store(state, Ordering::SeqCst);
+            }
+        });
+    }
+}
+
+/// Queue a power event for every logind `PrepareForSleep`, stamped with
+/// the time the signal arrived: just before the suspend, just after resume.
+fn watch_sleep(system: &Connection, events: &Arc<Mutex<Vec<PowerEvent>>>) {
+    let Some(manager) = proxy(
+        system,
+        LOGIND_BUS,
+        ObjectPath::from_static_str_unchecked("/org/freedesktop/login1"),
+        "org.freedesktop.login1.Manager",
+    ) else {
+        return;
+    };
+
+    let events = events.clone();
+    spawn_signal_watcher(manager, move |msg| {
+        let is_sleep = msg
+            .header()
+            .member()
+            .is_some_and(|m| m.as_str() == "PrepareForSleep");
+        if !is_sleep {
+            return;
+        }
+        let Ok(start) = msg.body().deserialize::<bool>() else {
+            return;
+        };
+        let now = Local::now();
+        let event = if start {
+            PowerEvent::Suspend(now)
+        } else {
+            PowerEvent::Resume(now)
+        };
+        if let Ok(mut events) = events.lock() {
+            events.push(event);
+        }
+    });
+}
+
+fn spawn_signal_watcher<F>(proxy: Proxy<'static>, mut on_signal: F)
+where
+    F: FnMut(&Message) + Send + 'static,
+{
+    let _ = thread::Builder::new()
+        .name("laches-dbus-watch".into())
+        .spawn(move || {
+            let Ok(signals) = proxy.receive_all_signals() else {
+                return;
+            };
+            for msg in signals {
+                on_signal(&msg);
+            }
+        });
+}
+
+/// Map a lock-related signal to the new lock state.
+fn lock_state(msg: &Message) -> Option<bool> {
+    let header = msg.header();
+    let member = header.member()?.as_str();
+    let active = msg.body().deserialize::<bool>().ok();
+    lock_signal(member, active)
+}
+
+fn lock_signal(member: &str, active: Option<bool>) -> Option<bool> {
+    match member {
+        "Lock" => Some(true),
+        "Unlock" => Some(false),
+        "ActiveChanged" => active,
+        _ => None,
+    }
+}
+
+/// Idle time from logind's hint and its CLOCK_REALTIME timestamp (µs).
+fn logind_idle(idle_hint: bool, since_usec: u64, now_usec: u64) -> Duration {
+    if !idle_hint || since_usec == 0 {
+        return Duration::ZERO;
+    }
+    Duration::from_micros(now_usec.saturating_sub(since_usec))
+}
+
+fn unix_micros() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_micros() as u64)
+        .unwrap_or(0)
+}
+
+/// Idle time given when the `idled` event arrived. The compositor sends it
+/// once the user has already been idle for `IDLE_NOTIFY_TIMEOUT`.
+fn idle_since(idled_at: Option<Instant>, now: Instant) -> Duration {
+    match idled_at {
+        Some(at) => now.saturating_duration_since(at) + IDLE_NOTIFY_TIMEOUT,
+        None => Duration::ZERO,
+    }
+}
+
+#[derive(Default)]
+struct IdleNotifyState {
+    idled_at: Option<Instant>,
+}
+
+struct IdleNotify {
+    queue: EventQueue<IdleNotifyState>,
+    state: IdleNotifyState,
+    // kept alive so the compositor keeps sending idled/resumed events
+    _notification: ExtIdleNotificationV1,
+}
+
+impl IdleNotify {
+    fn connect() -> Result<IdleNotify, Box<dyn std::error::Error>> {
+        let conn = WaylandConnection::connect_to_env()?;
+        let (globals, mut queue) = registry_queue_init::<IdleNotifyState>(&conn)?;
+        let qh = queue.handle();
+        let seat: WlSeat = globals.bind(&qh, 1..=1, ())?;
+        let notifier: ExtIdleNotifierV1 = globals.bind(&qh, 1..=1, ())?;
+        let notification =
+            notifier.get_idle_notification(IDLE_NOTIFY_TIMEOUT.as_millis() as u32, &seat, &qh, ());
+
+        let mut state = IdleNotifyState::default();
+        queue.roundtrip(&mut state)?;
+
+        Ok(IdleNotify {
+            queue,
+            state,
+            _notification: notification,
+        })
+    }
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for IdleNotifyState {
+    fn event(
+        _: &mut Self,
+        _: &WlRegistry,
+        _: <WlRegistry as wayland_client::Proxy>::Event,
+        _: &GlobalListContents,
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WlSeat, ()> for IdleNotifyState {
+    fn event(
+        _: &mut Self,
+        _: &WlSeat,
+        _: <WlSeat as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ExtIdleNotifierV1, ()> for IdleNotifyState {
+    fn event(
+        _: &mut Self,
+        _: &ExtIdleNotifierV1,
+        _: <ExtIdleNotifierV1 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ExtIdleNotificationV1, ()> for IdleNotifyState {
+    fn event(
+        state: &mut Self,
+        _: &ExtIdleNotificationV1,
+        event: ext_idle_notification_v1::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            ext_idle_notification_v1::Event::Idled => state.idled_at = Some(Instant::now()),
+            ext_idle_notification_v1::Event::Resumed => state.idled_at = None,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idle_since_adds_notify_timeout() {
+        let now = Instant::now();
+        assert_eq!(idle_since(None, now), Duration::ZERO);
+        assert_eq!(
+            idle_since(Some(now - Duration::from_secs(60)), now),
+            Duration::from_secs(60) + IDLE_NOTIFY_TIMEOUT
+        );
+    }
+
+    #[test]
+    fn test_logind_idle() {
+        assert_eq!(logind_idle(false, 1_000_000, 5_000_000), Duration::ZERO);
+        assert_eq!(logind_idle(true, 0, 5_000_000), Duration::ZERO);
+        assert_eq!(
+            logind_idle(true, 1_000_000, 5_000_000),
+            Duration::from_secs(4)
+        );
+        // clock went backwards
+        assert_eq!(logind_idle(true, 5_000_000, 1_000_000), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_lock_signal() {
+        assert_eq!(lock_signal("Lock", None), Some(true));
+        assert_eq!(lock_signal("Unlock", None), Some(false));
+        assert_eq!(lock_signal("ActiveChanged", Some(true)), Some(true));
+        assert_eq!(lock_signal("ActiveChanged", None), None);
+        assert_eq!(lock_signal("PauseDevice", None), None);
+    }
+
+    #[test]
+    fn test_new_does_not_panic_without_session() {
+        let monitor = IdleMonitor::new(false);
+        let _ = monitor.source_name();
+        let _ = monitor.is_locked();
+        assert!(monitor.take_power_events().is_empty());
+    }
+}
@@ -0,0 +1,343 @@
+use super::{process_from_app_id, process_from_pid};
+use crate::platform::{FocusInfo, FocusTracker, FocusWaker};
+use std::error::Error;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use zbus::blocking::Connection;
+
+const KWIN_BUS: &str = "org.kde.KWin";
+const RECEIVER_PATH: &str = "/org/lachesis/FocusTracker";
+const RECEIVER_IFACE: &str = "org.lachesis.FocusTracker";
+/// How long to wait before trying to load the script again after a failure.
+const RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Focus tracker for KDE Plasma (KWin) on Wayland.
+///
+/// KWin only exposes window state to its own scripting engine, so on first
+/// use this serves a small D-Bus object, loads a KWin script that calls it
+/// whenever the active window or its caption changes, and answers polls from
+/// the last reported value. The script is unloaded when the tracker drops.
+pub struct KdeFocusTracker {
+    focus: Arc<Mutex<Option<FocusInfo>>>,
+    wake: Arc<Mutex<Option<FocusWaker>>>,
+    script: Mutex<Option<KwinScript>>,
+    /// When loading the script last failed, so polls don't retry each time.
+    failed_at: Mutex<Option<Instant>>,
+}
+
+/// A loaded KWin script and the connection its callbacks arrive on.
+struct KwinScript {
+    conn: Connection,
+    plugin_name: String,
+    script_path: PathBuf,
+}
+
+impl Drop for KwinScript {
+    fn drop(&mut self) {
+        let _ = self.conn.call_method(
+            Some(KWIN_BUS),
+            "/Scripting",
+            Some("org.kde.kwin.Scripting"),
+            "unloadScript",
+            &(self.plugin_name.as_str(),),
+        );
+        let _ = std::fs::remove_file(&self.script_path);
+    }
+}
+
+/// D-Bus object the KWin script reports to.
+struct FocusReceiver {
+    focus: Arc<Mutex<Option<FocusInfo>>>,
+    wake: Arc<Mutex<Option<FocusWaker>>>,
+}
+
+impl FocusReceiver {
+    fn set_focus(&self, info: Option<FocusInfo>) {
+        if let Ok(mut focus) = self.focus.lock() {
+            *focus = info;
+        }
+        if let Ok(wake) = self.wake.lock() {
+            if let Some(wake) = wake.as_ref() {
+                wake();
+            }
+        }
+    }
+}
+
+#[zbus::interface(name = "org.lachesis.FocusTracker")]
+impl FocusReceiver {
+    /// Called by the script with the active window's caption, resource
+    /// class and pid (as a string, since KWin passes JS numbers as doubles).
+    fn update_focus(&self, caption: String, resource_class: String, pid: String) {
+        self.set_focus(focus_from_report(&caption, &resource_class, &pid));
+    }
+
+    /// Called by the script when no window is active.
+    fn clear(&self) {
+        self.set_focus(None);
+    }
+}
+
+impl KdeFocusTracker {
+    pub fn new() -> Self {
+        KdeFocusTracker {
+            focus: Arc::new(Mutex::new(None)),
+            wake: Arc::new(Mutex::new(None)),
+            script: Mutex::new(None),
+            failed_at: Mutex::new(None),
+        }
+    }
+
+    /// Load the script on first use; it keeps reporting from then on. After
+    /// a failure, loading is retried at most once per `RETRY_INTERVAL`.
+    fn ensure_script(&self) -> Option<()> {
+        let mut script = self.script.lock().ok()?;
+        if script.is_some() {
+            return Some(());
+        }
+
+        let mut failed_at = self.failed_at.lock().ok()?;
+        if failed_at.is_some_and(|at| at.elapsed() < RETRY_INTERVAL) {
+            return None;
+        }
+        match self.load_script() {
+            Ok(loaded) => {
+                *script = Some(loaded);
+                *failed_at = None;
+                Some(())
+            }
+            Err(_) => {
+                *failed_at = Some(Instant::now());
+                None
+            }
+        }
+    }
+
+    /// Serve the receiver object and load + run the KWin script.
+    fn load_script(&self) -> Result<KwinScript, Box<dyn Error>> {
+        let conn = Connection::session()?;
+        conn.object_server().at(
+            RECEIVER_PATH,
+            FocusReceiver {
+                focus: Arc::clone(&self.focus),
+                wake: Arc::clone(&self.wake),
+            },
+        )?;
+        let unique_name = conn
+            .unique_name()
+            .ok_or("session bus connection has no unique name")?
+            .to_string();
+
+        let plugin_name = format!("lachesis-{}", std::process::id());
+        let script_path = script_dir().join(format!("{}.js", plugin_name));
+        write_private(&script_path, &kwin_script(&unique_name))?;
+
+        let script = KwinScript {
+            conn,
+            plugin_name,
+            script_path,
+        };
+
+        // a stale script from a previous run with the same pid would make
+        // loadScript fail, so unload first and ignore the result
+        let scripting = Some("org.kde.kwin.Scripting");
+        let _ = script.conn.call_method(
+            Some(KWIN_BUS),
+            "/Scripting",
+            scripting,
+            "unloadScript",
+            &(script.plugin_name.as_str(),),
+        );
+
+        let path_str = script.script_path.to_string_lossy().into_owned();
+        let id: i32 = script
+            .conn
+            .call_method(
+                Some(KWIN_BUS),
+                "/Scripting",
+                scripting,
+                "loadScript",
+                &(path_str.as_str(), script.plugin_name.as_str()),
+            )?
+            .body()
+            .deserialize()?;
+
+        // plasma 6 moved script objects under /Scripting
+        let run = |path: String| {
+            script
+                .conn
+                .call_method(
+                    Some(KWIN_BUS),
+                    path.as_str(),
+                    Some("org.kde.kwin.Script"),
+                    "run",
+                    &(),
+                )
+                .map(|_| ())
+        };
+        run(format!("/Scripting/Script{}", id)).or_else(|_| run(format!("/{}", id)))?;
+
+        Ok(script)
+    }
+}
+
+/// Where the script file goes: the per-user runtime directory, falling back
+/// to the system temp directory.
+fn script_dir() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => std::env::temp_dir(),
+    }
+}
+
+/// Write `contents` to a new file at `path` readable only by this user.
+/// A leftover file (or symlink) there is removed first rather than followed.
+fn write_private(path: &std::path::Path, contents: &str) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(contents.as_bytes())
+}
+
+/// Generate the KWin script that reports focus changes to `service`.
+/// Handles both the Plasma 6 (`windowActivated`) and Plasma 5
+/// (`clientActivated`) workspace APIs.
+fn kwin_script(service: &str) -> String {
+    format!(
+        r#"var current = null;
+
+function report() {{
+    if (!current) {{
+        callDBus("{service}", "{path}", "{iface}", "Clear");
+        return;
+    }}
+    callDBus("{service}", "{path}", "{iface}", "UpdateFocus",
+        String(current.caption || ""), String(current.resourceClass || ""),
+        String(current.pid || 0));
+}}
+
+function activated(window) {{
+    if (current && current.captionChanged) {{
+        current.captionChanged.disconnect(report);
+    }}
+    current = window;
+    if (current && current.captionChanged) {{
+        current.captionChanged.connect(report);
+    }}
+    report();
+}}
+
+if (workspace.windowActivated) {{
+    workspace.windowActivated.connect(activated);
+    activated(workspace.activeWindow);
+}} else {{
+    workspace.clientActivated.connect(activated);
+    activated(workspace.activeClient);
+}}
+"#,
+        service = service,
+        path = RECEIVER_PATH,
+        iface = RECEIVER_IFACE,
+    )
+}
+
+/// Build FocusInfo from the values the KWin script reports.
+fn focus_from_report(caption: &str, resource_class: &str, pid: &str) -> Option<FocusInfo> {
+    let window_title = if caption.is_empty() {
+        None
+    } else {
+        Some(caption.to_string())
+    };
+
+    let pid: u32 = pid.parse::<f64>().map(|p| p as u32).unwrap_or(0);
+    if let Some((process_name, exe_path)) = process_from_pid(pid) {
+        return Some(FocusInfo {
+            process_name,
+            exe_path,
+            window_title,
+        });
+    }
+
+    Some(FocusInfo {
+        process_name: process_from_app_id(resource_class)?,
+        exe_path: None,
+        window_title,
+    })
+}
+
+impl FocusTracker for KdeFocusTracker {
+    fn name(&self) -> &'static str {
+        "kde"
+    }
+
+    fn get_focused_window(&self) -> Option<FocusInfo> {
+        self.ensure_script()?;
+        self.focus.lock().ok()?.clone()
+    }
+
+    fn subscribe(&self, wake: FocusWaker) -> bool {
+        // the script already pushes every change; just forward them
+        if let Ok(mut slot) = self.wake.lock() {
+            *slot = Some(wake);
+        }
+        self.ensure_script().is_some()
+    }
+
+    fn get_idle_duration(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kwin_script_targets_service() {
+        let script = kwin_script(":1.42");
+        assert!(script.contains(r#"callDBus(":1.42", "/org/lachesis/FocusTracker""#));
+        assert!(script.contains("workspace.windowActivated"));
+        assert!(script.contains("workspace.clientActivated"));
+    }
+
+    #[test]
+    fn test_write_private_replaces_symlink() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("laches-kde-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("target");
+        let path = dir.join("script.js");
+        std::fs::write(&target, "keep").unwrap();
+        std::os::unix::fs::symlink(&target, &path).unwrap();
+
+        write_private(&path, "script").unwrap();
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "keep");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "script");
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_focus_from_report_falls_back_to_class() {
+        let info = focus_from_report("Konsole", "org.kde.konsole", "0").unwrap();
+        assert_eq!(info.process_name, "konsole");
+        assert_eq!(info.window_title.as_deref(), Some("Konsole"));
+
+        let info = focus_from_report("", "dolphin", "not a pid").unwrap();
+        assert!(info.window_title.is_none());
+        assert!(focus_from_report("", "", "0").is_none());
+    }
+}
@@ -0,0 +1,229 @@
+//! Linux focus tracking backends.
+//!
+//! X11 exposes the active window through EWMH properties, but Wayland has no
+//! equivalent that every compositor implements. Each Wayland backend here
+//! talks to one family of compositors; `create_tracker` picks one from the
+//! session environment unless `daemon.backend` forces a specific one.
+
+mod gnome;
+mod hyprland;
+mod idle;
+mod kde;
+mod notify;
+mod sway;
+mod wlroots;
+mod x11;
+
+pub use notify::notify;
+
+use super::{normalize_process_name, FocusInfo, FocusTracker, FocusWaker, PowerEvent};
+use crate::config::TrackerBackend;
+use std::time::Duration;
+
+/// Create the focus tracker for `backend`, detecting it when set to `auto`.
+pub fn create_tracker(backend: TrackerBackend) -> Box<dyn FocusTracker> {
+    let backend = match backend {
+        TrackerBackend::Auto => detect_backend(|key| std::env::var(key).ok()),
+        forced => forced,
+    };
+
+    let focus: Box<dyn FocusTracker> = match backend {
+        TrackerBackend::Sway => Box::new(sway::SwayFocusTracker::new()),
+        TrackerBackend::Hyprland => Box::new(hyprland::HyprlandFocusTracker::new()),
+        TrackerBackend::Gnome => Box::new(gnome::GnomeFocusTracker::new()),
+        TrackerBackend::Kde => Box::new(kde::KdeFocusTracker::new()),
+        TrackerBackend::Wlroots => Box::new(wlroots::WlrootsFocusTracker::new()),
+        TrackerBackend::X11 | TrackerBackend::Auto => Box::new(x11::X11FocusTracker::new()),
+    };
+
+    let wayland = !matches!(backend, TrackerBackend::X11 | TrackerBackend::Auto);
+    Box::new(LinuxTracker {
+        focus,
+        idle: idle::IdleMonitor::new(wayland),
+    })
+}
+
+/// Pairs a focus backend with the shared idle monitor, so every backend
+/// gets idle, lock and suspend detection regardless of how it finds the
+/// focus.
+struct LinuxTracker {
+    focus: Box<dyn FocusTracker>,
+    idle: idle::IdleMonitor,
+}
+
+impl FocusTracker for LinuxTracker {
+    fn name(&self) -> &'static str {
+        self.focus.name()
+    }
+
+    fn idle_source(&self) -> &'static str {
+        self.idle.source_name()
+    }
+
+    fn get_focused_window(&self) -> Option<FocusInfo> {
+        self.focus.get_focused_window()
+    }
+
+    fn get_idle_duration(&self) -> Duration {
+        self.idle.idle_duration(self.focus.as_ref())
+    }
+
+    fn is_locked(&self) -> bool {
+        self.idle.is_locked()
+    }
+
+    fn subscribe(&self, wake: FocusWaker) -> bool {
+        self.focus.subscribe(wake)
+    }
+
+    fn take_power_events(&self) -> Vec<PowerEvent> {
+        self.idle.take_power_events()
+    }
+}
+
+/// Pick a backend from session environment variables.
+///
+/// Compositor-specific IPC is preferred over desktop detection, and the
+/// generic wlroots protocol is the last Wayland resort. Anything that is
+/// not a Wayland session uses X11. `env` is injectable for testing.
+fn detect_backend(env: impl Fn(&str) -> Option<String>) -> TrackerBackend {
+    let is_wayland = env("WAYLAND_DISPLAY").is_some_and(|v| !v.is_empty())
+        || env("XDG_SESSION_TYPE").is_some_and(|v| v.eq_ignore_ascii_case("wayland"));
+    if !is_wayland {
+        return TrackerBackend::X11;
+    }
+
+    if env("SWAYSOCK").is_some_and(|v| !v.is_empty()) {
+        return TrackerBackend::Sway;
+    }
+    if env("HYPRLAND_INSTANCE_SIGNATURE").is_some_and(|v| !v.is_empty()) {
+        return TrackerBackend::Hyprland;
+    }
+
+    let desktop = env("XDG_CURRENT_DESKTOP")
+        .unwrap_or_default()
+        .to_ascii_uppercase();
+    let is_desktop = |name: &str| desktop.split(':').any(|d| d == name);
+    if is_desktop("GNOME") {
+        return TrackerBackend::Gnome;
+    }
+    if is_desktop("KDE") {
+        return TrackerBackend::Kde;
+    }
+
+    TrackerBackend::Wlroots
+}
+
+/// Resolve a pid to its process name and executable path via /proc.
+/// Returns None if the process is gone or its exe link is unreadable.
+fn process_from_pid(pid: u32) -> Option<(String, Option<String>)> {
+    if pid == 0 {
+        return None;
+    }
+
+    let exe_path = std::fs::read_link(format!("/proc/{}/exe", pid))
+        .ok()
+        .map(|p| p.to_string_lossy().into_owned());
+
+    // extract process name from the full path (e.g. "/usr/bin/firefox" -> "firefox")
+    let process_name = exe_path
+        .as_ref()
+        .and_then(|p| p.rsplit('/').next())
+        .map(normalize_process_name)
+        .unwrap_or_default();
+
+    if process_name.is_empty() {
+        None
+    } else {
+        Some((process_name, exe_path))
+    }
+}
+
+/// Fallback process name when a compositor reports an app id or window
+/// class but the pid cannot be resolved (e.g. sandboxed apps).
+fn process_from_app_id(app_id: &str) -> Option<String> {
+    let name = app_id.rsplit('.').next().unwrap_or(app_id).to_lowercase();
+    if name.is_empty() {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env_of(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let map: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |key| map.get(key).cloned()
+    }
+
+    #[test]
+    fn test_detect_x11_without_wayland() {
+        let env = env_of(&[("DISPLAY", ":0"), ("XDG_CURRENT_DESKTOP", "GNOME")]);
+        assert_eq!(detect_backend(env), TrackerBackend::X11);
+    }
+
+    #[test]
+    fn test_detect_compositor_ipc_first() {
+        let env = env_of(&[
+            ("WAYLAND_DISPLAY", "wayland-1"),
+            ("SWAYSOCK", "/run/user/1000/sway-ipc.sock"),
+            ("XDG_CURRENT_DESKTOP", "sway"),
+        ]);
+        assert_eq!(detect_backend(env), TrackerBackend::Sway);
+
+        let env = env_of(&[
+            ("XDG_SESSION_TYPE", "wayland"),
+            ("HYPRLAND_INSTANCE_SIGNATURE", "abc"),
+        ]);
+        assert_eq!(detect_backend(env), TrackerBackend::Hyprland);
+    }
+
+    #[test]
+    fn test_detect_desktops() {
+        let env = env_of(&[
+            ("WAYLAND_DISPLAY", "wayland-0"),
+            ("XDG_CURRENT_DESKTOP", "ubuntu:GNOME"),
+        ]);
+        assert_eq!(detect_backend(env), TrackerBackend::Gnome);
+
+        let env = env_of(&[
+            ("WAYLAND_DISPLAY", "wayland-0"),
+            ("XDG_CURRENT_DESKTOP", "KDE"),
+        ]);
+        assert_eq!(detect_backend(env), TrackerBackend::Kde);
+    }
+
+    #[test]
+    fn test_detect_falls_back_to_wlroots() {
+        let env = env_of(&[
+            ("WAYLAND_DISPLAY", "wayland-0"),
+            ("XDG_CURRENT_DESKTOP", "river"),
+        ]);
+        assert_eq!(detect_backend(env), TrackerBackend::Wlroots);
+    }
+
+    #[test]
+    fn test_process_from_app_id() {
+        assert_eq!(process_from_app_id("firefox").as_deref(), Some("firefox"));
+        assert_eq!(
+            process_from_app_id("org.gnome.Nautilus").as_deref(),
+            Some("nautilus")
+        );
+        assert_eq!(process_from_app_id(""), None);
+    }
+
+    #[test]
+    fn test_process_from_own_pid() {
+        let (name, exe) = process_from_pid(std::process::id()).unwrap();
+        assert!(!name.is_empty());
+        assert!(exe.is_some());
+        assert!(process_from_pid(0).is_none());
+    }
+}
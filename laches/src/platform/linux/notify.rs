@@ -0,0 +1,45 @@
+//! Desktop notifications through the freedesktop notification service
+//! (`org.freedesktop.Notifications` on the session bus), which every
+//! major desktop and standalone daemons like dunst and mako implement.
+
+use std::collections::HashMap;
+use zbus::blocking::Connection;
+use zbus::zvariant::Value;
+
+const NOTIFICATIONS_BUS: &str = "org.freedesktop.Notifications";
+const NOTIFICATIONS_PATH: &str = "/org/freedesktop/Notifications";
+
+/// Urgency hint levels from the notification spec.
+const URGENCY_NORMAL: u8 = 1;
+const URGENCY_CRITICAL: u8 = 2;
+
+pub fn notify(summary: &str, body: &str, urgent: bool) -> bool {
+    let Ok(conn) = Connection::session() else {
+        return false;
+    };
+    let urgency = if urgent {
+        URGENCY_CRITICAL
+    } else {
+        URGENCY_NORMAL
+    };
+    let hints: HashMap<&str, Value> = HashMap::from([("urgency", Value::U8(urgency))]);
+    conn.call_method(
+        Some(NOTIFICATIONS_BUS),
+        NOTIFICATIONS_PATH,
+        Some(NOTIFICATIONS_BUS),
+        "Notify",
+        // app name, replaces id, icon, summary, body, actions, hints,
+        // timeout (-1: server default)
+        &(
+            "lachesis",
+            0u32,
+            "",
+            summary,
+            body,
+            Vec::<&str>::new(),
+            hints,
+            -1i32,
+        ),
+    )
+    .is_ok()
+}
@@ -0,0 +1,260 @@
+use super::{process_from_app_id, process_from_pid};
+use crate::platform::{FocusInfo, FocusTracker, FocusWaker};
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// i3-ipc magic string that prefixes every message in both directions.
+const IPC_MAGIC: &[u8] = b"i3-ipc";
+/// i3-ipc message type for SUBSCRIBE.
+const SUBSCRIBE: u32 = 2;
+/// i3-ipc message type for GET_TREE.
+const GET_TREE: u32 = 4;
+
+/// Focus tracker for sway (and i3) via the i3-ipc socket.
+///
+/// The socket path comes from `$SWAYSOCK` (or `$I3SOCK`). The connection
+/// is opened lazily and re-opened after errors, so a daemon started before
+/// the compositor picks it up once the socket appears.
+pub struct SwayFocusTracker {
+    socket_path: Option<PathBuf>,
+    stream: Mutex<Option<UnixStream>>,
+}
+
+impl SwayFocusTracker {
+    pub fn new() -> Self {
+        let socket_path = std::env::var_os("SWAYSOCK")
+            .or_else(|| std::env::var_os("I3SOCK"))
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from);
+
+        SwayFocusTracker {
+            socket_path,
+            stream: Mutex::new(None),
+        }
+    }
+
+    /// Send GET_TREE and return the parsed layout tree.
+    fn get_tree(&self) -> Option<Value> {
+        let mut guard = self.stream.lock().ok()?;
+        if guard.is_none() {
+            let stream = UnixStream::connect(self.socket_path.as_ref()?).ok()?;
+            stream.set_read_timeout(Some(Duration::from_secs(1))).ok()?;
+            *guard = Some(stream);
+        }
+
+        let result = guard.as_mut().and_then(|stream| request(stream, GET_TREE));
+        if result.is_none() {
+            // drop the broken connection so the next poll reconnects
+            *guard = None;
+        }
+        result
+    }
+}
+
+/// Write one i3-ipc request and read its reply payload as JSON.
+fn request(stream: &mut UnixStream, message_type: u32) -> Option<Value> {
+    write_message(stream, message_type, b"")?;
+    let (_, payload) = read_message(stream)?;
+    serde_json::from_slice(&payload).ok()
+}
+
+fn write_message(stream: &mut UnixStream, message_type: u32, payload: &[u8]) -> Option<()> {
+    let mut message = Vec::with_capacity(14 + payload.len());
+    message.extend_from_slice(IPC_MAGIC);
+    message.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    message.extend_from_slice(&message_type.to_le_bytes());
+    message.extend_from_slice(payload);
+    stream.write_all(&message).ok()
+}
+
+/// Read one reply or event. Events have the high bit of the type set.
+fn read_message(stream: &mut UnixStream) -> Option<(u32, Vec<u8>)> {
+    let mut header = [0u8; 14];
+    stream.read_exact(&mut header).ok()?;
+    if &header[..6] != IPC_MAGIC {
+        return None;
+    }
+    let len = u32::from_le_bytes(header[6..10].try_into().ok()?) as usize;
+    let message_type = u32::from_le_bytes(header[10..14].try_into().ok()?);
+
+    let mut payload = vec![0u8; len];
+    stream.read_exact(&mut payload).ok()?;
+    Some((message_type, payload))
+}
+
+/// Whether a `window` event changes what is focused or its title.
+fn is_focus_event(payload: &[u8]) -> bool {
+    let Ok(event) = serde_json::from_slice::<Value>(payload) else {
+        return false;
+    };
+    matches!(
+        event.get("change").and_then(Value::as_str),
+        Some("focus" | "title" | "close")
+    )
+}
+
+/// Depth-first search for the node with `"focused": true`.
+fn find_focused(node: &Value) -> Option<&Value> {
+    if node.get("focused").and_then(Value::as_bool) == Some(true) {
+        return Some(node);
+    }
+    ["nodes", "floating_nodes"]
+        .iter()
+        .filter_map(|key| node.get(*key).and_then(Value::as_array))
+        .flatten()
+        .find_map(find_focused)
+}
+
+/// Build FocusInfo from a focused tree node. Workspaces and outputs can be
+/// focused too (empty workspace), in which case there is no window.
+fn focus_from_node(node: &Value) -> Option<FocusInfo> {
+    let node_type = node.get("type").and_then(Value::as_str).unwrap_or("");
+    if node_type != "con" && node_type != "floating_con" {
+        return None;
+    }
+
+    let window_title = node.get("name").and_then(Value::as_str).map(str::to_string);
+
+    let pid = node.get("pid").and_then(Value::as_u64).unwrap_or(0) as u32;
+    if let Some((process_name, exe_path)) = process_from_pid(pid) {
+        return Some(FocusInfo {
+            process_name,
+            exe_path,
+            window_title,
+        });
+    }
+
+    // native wayland clients report app_id, xwayland clients a window class
+    let app_id = node.get("app_id").and_then(Value::as_str).or_else(|| {
+        node.pointer("/window_properties/class")
+            .and_then(Value::as_str)
+    })?;
+
+    Some(FocusInfo {
+        process_name: process_from_app_id(app_id)?,
+        exe_path: None,
+        window_title,
+    })
+}
+
+impl FocusTracker for SwayFocusTracker {
+    fn name(&self) -> &'static str {
+        "sway"
+    }
+
+    fn get_focused_window(&self) -> Option<FocusInfo> {
+        let tree = self.get_tree()?;
+        focus_from_node(find_focused(&tree)?)
+    }
+
+    fn subscribe(&self, wake: FocusWaker) -> bool {
+        let Some(path) = self.socket_path.as_ref() else {
+            return false;
+        };
+        let Ok(mut stream) = UnixStream::connect(path) else {
+            return false;
+        };
+        if write_message(&mut stream, SUBSCRIBE, br#"["window"]"#).is_none() {
+            return false;
+        }
+        let subscribed = read_message(&mut stream)
+            .and_then(|(_, reply)| serde_json::from_slice::<Value>(&reply).ok())
+            .and_then(|reply| reply.get("success").and_then(Value::as_bool));
+        if subscribed != Some(true) {
+            return false;
+        }
+
+        // runs until the compositor goes away; polling covers the rest
+        std::thread::Builder::new()
+            .name("laches-sway-events".into())
+            .spawn(move || {
+                while let Some((_, payload)) = read_message(&mut stream) {
+                    if is_focus_event(&payload) {
+                        wake();
+                    }
+                }
+            })
+            .is_ok()
+    }
+
+    fn get_idle_duration(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_find_focused_in_nested_tree() {
+        let tree = json!({
+            "type": "root", "focused": false,
+            "nodes": [{
+                "type": "output", "focused": false,
+                "nodes": [{
+                    "type": "workspace", "focused": false,
+                    "nodes": [
+                        {"type": "con", "focused": false, "name": "a", "app_id": "foot"},
+                    ],
+                    "floating_nodes": [
+                        {"type": "floating_con", "focused": true, "name": "Notes", "app_id": "org.gnome.TextEditor"},
+                    ],
+                }],
+            }],
+        });
+
+        let info = focus_from_node(find_focused(&tree).unwrap()).unwrap();
+        assert_eq!(info.process_name, "texteditor");
+        assert_eq!(info.window_title.as_deref(), Some("Notes"));
+        assert!(info.exe_path.is_none());
+    }
+
+    #[test]
+    fn test_xwayland_window_uses_class() {
+        let node = json!({
+            "type": "con", "focused": true, "name": "Steam",
+            "app_id": null, "window_properties": {"class": "Steam"},
+        });
+        let info = focus_from_node(&node).unwrap();
+        assert_eq!(info.process_name, "steam");
+    }
+
+    #[test]
+    fn test_focused_workspace_has_no_window() {
+        let node = json!({"type": "workspace", "focused": true, "name": "1"});
+        assert!(focus_from_node(&node).is_none());
+    }
+
+    #[test]
+    fn test_is_focus_event() {
+        assert!(is_focus_event(br#"{"change": "focus", "container": {}}"#));
+        assert!(is_focus_event(br#"{"change": "title", "container": {}}"#));
+        assert!(!is_focus_event(br#"{"change": "mark", "container": {}}"#));
+        assert!(!is_focus_event(b"not json"));
+    }
+
+    #[test]
+    fn test_message_roundtrip() {
+        let (mut a, mut b) = UnixStream::pair().unwrap();
+        write_message(&mut a, SUBSCRIBE, br#"["window"]"#).unwrap();
+        let (message_type, payload) = read_message(&mut b).unwrap();
+        assert_eq!(message_type, SUBSCRIBE);
+        assert_eq!(payload, br#"["window"]"#);
+    }
+
+    #[test]
+    fn test_missing_socket_returns_none() {
+        let tracker = SwayFocusTracker {
+            socket_path: Some(PathBuf::from("/nonexistent/sway-ipc.sock")),
+            stream: Mutex::new(None),
+        };
+        assert!(tracker.get_focused_window().is_none());
+        assert!(!tracker.subscribe(Box::new(|| {})));
+    }
+}
@@ -0,0 +1,273 @@
+use super::process_from_app_id;
+use crate::platform::{FocusInfo, FocusTracker, FocusWaker};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use wayland_client::backend::ObjectId;
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::wl_registry::WlRegistry;
+use wayland_client::{event_created_child, Connection, Dispatch, EventQueue, Proxy, QueueHandle};
+use wayland_protocols_wlr::foreign_toplevel::v1::client::{
+    zwlr_foreign_toplevel_handle_v1::{self, ZwlrForeignToplevelHandleV1},
+    zwlr_foreign_toplevel_manager_v1::{self, ZwlrForeignToplevelManagerV1},
+};
+
+/// `zwlr_foreign_toplevel_handle_v1.state` value for the focused toplevel.
+const STATE_ACTIVATED: u32 = 2;
+
+/// Title, app id and activation state of one toplevel. The protocol sends
+/// changes followed by `done`, so updates land in `pending` first.
+#[derive(Default, Clone)]
+struct Toplevel {
+    title: Option<String>,
+    app_id: Option<String>,
+    activated: bool,
+}
+
+#[derive(Default)]
+struct ToplevelState {
+    current: HashMap<ObjectId, Toplevel>,
+    pending: HashMap<ObjectId, Toplevel>,
+    /// Set on the subscription's own connection.
+    wake: Option<FocusWaker>,
+}
+
+impl ToplevelState {
+    fn pending_mut(&mut self, id: ObjectId) -> &mut Toplevel {
+        let current = self.current.get(&id).cloned().unwrap_or_default();
+        self.pending.entry(id).or_insert(current)
+    }
+
+    fn activated(&self) -> Option<&Toplevel> {
+        self.current.values().find(|t| t.activated)
+    }
+
+    fn notify(&self) {
+        if let Some(wake) = self.wake.as_ref() {
+            wake();
+        }
+    }
+}
+
+/// Whether committing `new` over `old` changes the focused window or the
+/// focused window's title.
+fn focus_changed(old: Option<&Toplevel>, new: &Toplevel) -> bool {
+    let was_activated = old.is_some_and(|t| t.activated);
+    if was_activated != new.activated {
+        return true;
+    }
+    new.activated && old.and_then(|t| t.title.as_ref()) != new.title.as_ref()
+}
+
+struct WaylandConnection {
+    queue: EventQueue<ToplevelState>,
+    state: ToplevelState,
+    // kept alive so the compositor keeps sending toplevel events
+    _manager: ZwlrForeignToplevelManagerV1,
+}
+
+/// Focus tracker using the wlroots `foreign-toplevel-management` protocol,
+/// supported by sway, river, labwc, wayfire, niri and most other wlroots
+/// based compositors.
+///
+/// The protocol does not expose pids, so the process name is derived from
+/// the toplevel's app id and `exe_path` is always `None`.
+pub struct WlrootsFocusTracker {
+    wayland: Mutex<Option<WaylandConnection>>,
+}
+
+impl WlrootsFocusTracker {
+    pub fn new() -> Self {
+        WlrootsFocusTracker {
+            wayland: Mutex::new(Self::connect().ok()),
+        }
+    }
+
+    fn connect() -> Result<WaylandConnection, Box<dyn std::error::Error>> {
+        let conn = Connection::connect_to_env()?;
+        let (globals, mut queue) = registry_queue_init::<ToplevelState>(&conn)?;
+        let qh = queue.handle();
+        let manager: ZwlrForeignToplevelManagerV1 = globals.bind(&qh, 1..=3, ())?;
+
+        // the compositor announces every existing toplevel right after bind
+        let mut state = ToplevelState::default();
+        queue.roundtrip(&mut state)?;
+
+        Ok(WaylandConnection {
+            queue,
+            state,
+            _manager: manager,
+        })
+    }
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for ToplevelState {
+    fn event(
+        _: &mut Self,
+        _: &WlRegistry,
+        _: <WlRegistry as Proxy>::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrForeignToplevelManagerV1, ()> for ToplevelState {
+    fn event(
+        _: &mut Self,
+        _: &ZwlrForeignToplevelManagerV1,
+        _: zwlr_foreign_toplevel_manager_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        // new toplevels are tracked through their handle's first `done`
+    }
+
+    event_created_child!(ToplevelState, ZwlrForeignToplevelManagerV1, [
+        zwlr_foreign_toplevel_manager_v1::EVT_TOPLEVEL_OPCODE => (ZwlrForeignToplevelHandleV1, ()),
+    ]);
+}
+
+impl Dispatch<ZwlrForeignToplevelHandleV1, ()> for ToplevelState {
+    fn event(
+        state: &mut Self,
+        handle: &ZwlrForeignToplevelHandleV1,
+        event: zwlr_foreign_toplevel_handle_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        use zwlr_foreign_toplevel_handle_v1::Event;
+
+        let id = handle.id();
+        match event {
+            Event::Title { title } => state.pending_mut(id).title = Some(title),
+            Event::AppId { app_id } => state.pending_mut(id).app_id = Some(app_id),
+            Event::State { state: raw } => {
+                state.pending_mut(id).activated = parse_states(&raw).contains(&STATE_ACTIVATED);
+            }
+            Event::Done => {
+                if let Some(toplevel) = state.pending.remove(&id) {
+                    let changed = focus_changed(state.current.get(&id), &toplevel);
+                    state.current.insert(id, toplevel);
+                    if changed {
+                        state.notify();
+                    }
+                }
+            }
+            Event::Closed => {
+                state.pending.remove(&id);
+                let closed = state.current.remove(&id);
+                handle.destroy();
+                if closed.is_some_and(|t| t.activated) {
+                    state.notify();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Decode the `state` event's array of native-endian u32 values.
+fn parse_states(raw: &[u8]) -> Vec<u32> {
+    raw.chunks_exact(4)
+        .map(|c| u32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+        .collect()
+}
+
+impl FocusTracker for WlrootsFocusTracker {
+    fn name(&self) -> &'static str {
+        "wlroots"
+    }
+
+    fn get_focused_window(&self) -> Option<FocusInfo> {
+        let mut guard = self.wayland.lock().ok()?;
+        if guard.is_none() {
+            *guard = Self::connect().ok();
+        }
+        let wayland = guard.as_mut()?;
+
+        if wayland.queue.roundtrip(&mut wayland.state).is_err() {
+            // compositor went away; reconnect on the next poll
+            *guard = None;
+            return None;
+        }
+
+        let toplevel = wayland.state.activated()?;
+        Some(FocusInfo {
+            process_name: process_from_app_id(toplevel.app_id.as_deref()?)?,
+            exe_path: None,
+            window_title: toplevel.title.clone(),
+        })
+    }
+
+    fn subscribe(&self, wake: FocusWaker) -> bool {
+        // a second connection, blocked in dispatch on its own thread
+        let Ok(mut wayland) = Self::connect() else {
+            return false;
+        };
+        wayland.state.wake = Some(wake);
+
+        std::thread::Builder::new()
+            .name("laches-wlr-events".into())
+            .spawn(move || {
+                let WaylandConnection {
+                    mut queue,
+                    mut state,
+                    _manager,
+                } = wayland;
+                while queue.blocking_dispatch(&mut state).is_ok() {}
+            })
+            .is_ok()
+    }
+
+    fn get_idle_duration(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_states() {
+        let mut raw = Vec::new();
+        raw.extend_from_slice(&1u32.to_ne_bytes());
+        raw.extend_from_slice(&STATE_ACTIVATED.to_ne_bytes());
+        assert_eq!(parse_states(&raw), vec![1, STATE_ACTIVATED]);
+        assert!(parse_states(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_focus_changed() {
+        let focused = Toplevel {
+            title: Some("a".to_string()),
+            app_id: Some("foot".to_string()),
+            activated: true,
+        };
+        let retitled = Toplevel {
+            title: Some("b".to_string()),
+            ..focused.clone()
+        };
+        let background = Toplevel {
+            activated: false,
+            ..focused.clone()
+        };
+
+        assert!(focus_changed(None, &focused));
+        assert!(!focus_changed(None, &background));
+        assert!(!focus_changed(Some(&focused), &focused));
+        assert!(focus_changed(Some(&focused), &retitled));
+        assert!(focus_changed(Some(&focused), &background));
+        assert!(!focus_changed(Some(&background), &background));
+    }
+
+    #[test]
+    fn test_new_does_not_panic_without_compositor() {
+        let tracker = WlrootsFocusTracker::new();
+        let _ = tracker.get_focused_window();
+    }
+}
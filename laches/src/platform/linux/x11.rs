@@ -1,8 +1,10 @@
-use super::{normalize_process_name, FocusInfo, FocusTracker};
+use super::process_from_pid;
+use crate::platform::{FocusInfo, FocusTracker, FocusWaker};
 use std::time::Duration;
 use x11rb::connection::Connection;
 use x11rb::protocol::screensaver;
 use x11rb::protocol::xproto::*;
+use x11rb::protocol::Event;
 use x11rb::rust_connection::RustConnection;
 
 /// Pre-interned X11 atoms for window property queries.
@@ -65,6 +67,55 @@ impl X11Connection {
         self.get_property_string(window, self.atoms.net_wm_name, self.atoms.utf8_string)
             .or_else(|| self.get_property_string(window, self.atoms.wm_name, AtomEnum::STRING))
     }
+
+    /// Select (or with `EventMask::NO_EVENT`, deselect) PropertyNotify
+    /// events on a window. Only affects this client's own event mask.
+    fn select_property_events(&self, window: Window, mask: EventMask) -> Option<()> {
+        self.conn
+            .change_window_attributes(window, &ChangeWindowAttributesAux::new().event_mask(mask))
+            .ok()?;
+        self.conn.flush().ok()
+    }
+
+    /// Follow PropertyNotify events until the connection breaks: changes to
+    /// `_NET_ACTIVE_WINDOW` on the root window, and title changes on
+    /// whichever window is active.
+    fn watch_focus(self, wake: FocusWaker) {
+        let active_window = |x11: &Self| {
+            x11.get_property_u32(x11.root, x11.atoms.net_active_window, AtomEnum::WINDOW)
+                .unwrap_or(0)
+        };
+
+        let mut watched = active_window(&self);
+        if watched != 0 {
+            self.select_property_events(watched, EventMask::PROPERTY_CHANGE);
+        }
+
+        while let Ok(event) = self.conn.wait_for_event() {
+            // errors for windows destroyed under us arrive as events too
+            let Event::PropertyNotify(event) = event else {
+                continue;
+            };
+
+            if event.window == self.root && event.atom == self.atoms.net_active_window {
+                let active = active_window(&self);
+                if active != watched {
+                    if watched != 0 {
+                        self.select_property_events(watched, EventMask::NO_EVENT);
+                    }
+                    if active != 0 {
+                        self.select_property_events(active, EventMask::PROPERTY_CHANGE);
+                    }
+                    watched = active;
+                }
+                wake();
+            } else if event.window == watched
+                && (event.atom == self.atoms.net_wm_name || event.atom == self.atoms.wm_name)
+            {
+                wake();
+            }
+        }
+    }
 }
 
 /// X11-based focus tracker for Linux.
@@ -73,13 +124,13 @@ impl X11Connection {
 /// (pure Rust, no native library dependencies). If the connection fails
 /// (e.g. `$DISPLAY` is unset on a Wayland-only session), both trait methods
 /// gracefully degrade to `None` / `Duration::ZERO`.
-pub struct LinuxFocusTracker {
+pub struct X11FocusTracker {
     x11: Option<X11Connection>,
 }
 
-impl LinuxFocusTracker {
+impl X11FocusTracker {
     pub fn new() -> Self {
-        LinuxFocusTracker {
+        X11FocusTracker {
             x11: Self::connect().ok(),
         }
     }
@@ -108,7 +159,11 @@ impl LinuxFocusTracker {
     }
 }
 
-impl FocusTracker for LinuxFocusTracker {
+impl FocusTracker for X11FocusTracker {
+    fn name(&self) -> &'static str {
+        "x11"
+    }
+
     fn get_focused_window(&self) -> Option<FocusInfo> {
         let x11 = self.x11.as_ref()?;
 
@@ -123,20 +178,7 @@ impl FocusTracker for LinuxFocusTracker {
         let pid = x11.get_property_u32(window, x11.atoms.net_wm_pid, AtomEnum::CARDINAL)?;
 
         // resolve the executable path via /proc
-        let exe_path = std::fs::read_link(format!("/proc/{}/exe", pid))
-            .ok()
-            .map(|p| p.to_string_lossy().into_owned());
-
-        // extract process name from the full path (e.g. "/usr/bin/firefox" -> "firefox")
-        let process_name = exe_path
-            .as_ref()
-            .and_then(|p| p.rsplit('/').next())
-            .map(normalize_process_name)
-            .unwrap_or_default();
-
-        if process_name.is_empty() {
-            return None;
-        }
+        let (process_name, exe_path) = process_from_pid(pid)?;
 
         let window_title = x11.get_window_title(window);
 
@@ -164,6 +206,24 @@ impl FocusTracker for LinuxFocusTracker {
 
         Duration::from_millis(reply.ms_since_user_input as u64)
     }
+
+    fn subscribe(&self, wake: FocusWaker) -> bool {
+        // events go to a dedicated connection so polls never consume them
+        let Ok(x11) = Self::connect() else {
+            return false;
+        };
+        if x11
+            .select_property_events(x11.root, EventMask::PROPERTY_CHANGE)
+            .is_none()
+        {
+            return false;
+        }
+
+        std::thread::Builder::new()
+            .name("laches-x11-events".into())
+            .spawn(move || x11.watch_focus(wake))
+            .is_ok()
+    }
 }
 
 #[cfg(test)]
@@ -173,21 +233,27 @@ mod tests {
     #[test]
     fn test_new_does_not_panic() {
         // on a machine without X11, inner will be None
-        let _tracker = LinuxFocusTracker::new();
+        let _tracker = X11FocusTracker::new();
     }
 
     #[test]
     fn test_get_focused_window_returns_something_or_none() {
-        let tracker = LinuxFocusTracker::new();
+        let tracker = X11FocusTracker::new();
         let result = tracker.get_focused_window();
         if let Some(info) = result {
             assert!(!info.process_name.is_empty());
         }
     }
 
+    #[test]
+    fn test_subscribe_does_not_panic() {
+        let tracker = X11FocusTracker::new();
+        let _ = tracker.subscribe(Box::new(|| {}));
+    }
+
     #[test]
     fn test_get_idle_duration_does_not_panic() {
-        let tracker = LinuxFocusTracker::new();
+        let tracker = X11FocusTracker::new();
         let _duration = tracker.get_idle_duration();
     }
 }
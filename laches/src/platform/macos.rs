@@ -12,6 +12,10 @@ impl MacOsFocusTracker {
 }
 
 impl FocusTracker for MacOsFocusTracker {
+    fn name(&self) -> &'static str {
+        "macos"
+    }
+
     fn get_focused_window(&self) -> Option<FocusInfo> {
         // TODO: implement via NSWorkspace.shared.frontmostApplication (issue #18)
         None
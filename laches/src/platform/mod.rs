@@ -1,3 +1,5 @@
+use crate::config::TrackerBackend;
+use chrono::{DateTime, Local};
 use std::time::Duration;
 
 /// Normalize a process name for consistent cross-platform matching.
@@ -23,14 +25,53 @@ pub struct FocusInfo {
     pub window_title: Option<String>,
 }
 
+/// A suspend or resume reported by the OS, with the wall-clock time it
+/// happened. The daemon is frozen while suspended, so it only sees these
+/// after resuming.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PowerEvent {
+    Suspend(DateTime<Local>),
+    Resume(DateTime<Local>),
+}
+
+/// Callback a subscribed tracker invokes whenever the focused window or its
+/// title changes. It only signals that something changed; the daemon reads
+/// the new state through `get_focused_window`.
+pub type FocusWaker = Box<dyn Fn() + Send + Sync>;
+
 /// Platform-specific interface for getting the focused window and idle state.
 pub trait FocusTracker {
+    /// Short backend name for logs (e.g. "x11", "sway").
+    fn name(&self) -> &'static str;
+
+    /// Short name of the idle detection source for logs.
+    fn idle_source(&self) -> &'static str {
+        self.name()
+    }
+
     /// Get information about the currently focused/foreground window.
     /// Returns None if no window is focused or if the query fails.
     fn get_focused_window(&self) -> Option<FocusInfo>;
 
     /// Get how long the user has been idle (no keyboard/mouse input).
     fn get_idle_duration(&self) -> Duration;
+
+    /// Whether the session's screen is locked.
+    fn is_locked(&self) -> bool {
+        false
+    }
+
+    /// Start calling `wake` on every focus or title change, from a
+    /// background thread. Returns false if the backend can only be polled.
+    fn subscribe(&self, wake: FocusWaker) -> bool {
+        let _ = wake;
+        false
+    }
+
+    /// Drain the suspend/resume events seen since the last call.
+    fn take_power_events(&self) -> Vec<PowerEvent> {
+        Vec::new()
+    }
 }
 
 #[cfg(target_os = "windows")]
@@ -43,19 +84,22 @@ mod linux;
 mod macos;
 
 /// Create a FocusTracker for the current platform.
-pub fn create_tracker() -> Box<dyn FocusTracker> {
+/// `backend` selects between the Linux backends and is ignored elsewhere.
+pub fn create_tracker(backend: TrackerBackend) -> Box<dyn FocusTracker> {
     #[cfg(target_os = "windows")]
     {
+        let _ = backend;
         Box::new(windows::WindowsFocusTracker::new())
     }
 
     #[cfg(target_os = "linux")]
     {
-        Box::new(linux::LinuxFocusTracker::new())
+        linux::create_tracker(backend)
     }
 
     #[cfg(target_os = "macos")]
     {
+        let _ = backend;
         Box::new(macos::MacOsFocusTracker::new())
     }
 
@@ -65,6 +109,22 @@ pub fn create_tracker() -> Box<dyn FocusTracker> {
     }
 }
 
+/// Show a desktop notification, marked urgent if `urgent`. Returns false
+/// if it could not be shown, e.g. with no notification service running.
+/// Only Linux has an implementation so far.
+pub fn notify(summary: &str, body: &str, urgent: bool) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        linux::notify(summary, body, urgent)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (summary, body, urgent);
+        false
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
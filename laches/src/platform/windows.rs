@@ -20,6 +20,10 @@ impl WindowsFocusTracker {
 }
 
 impl FocusTracker for WindowsFocusTracker {
+    fn name(&self) -> &'static str {
+        "windows"
+    }
+
     fn get_focused_window(&self) -> Option<FocusInfo> {
         unsafe {
             let hwnd: HWND = GetForegroundWindow();
@@ -1,7 +1,9 @@
 use crate::config::{clear_daemon_pid, read_daemon_pid, write_daemon_pid};
 use crate::error::LachesError;
+use crate::ipc;
 use std::env;
 use std::process::Stdio;
+use std::time::Instant;
 use std::{path::Path, process::Command, thread, time::Duration};
 use sysinfo::{Pid, ProcessRefreshKind, System, UpdateKind};
 
@@ -87,8 +89,29 @@ pub fn start_monitoring(config_dir: &Path) -> Result<(), LachesError> {
     Ok(())
 }
 
-/// Stop the monitoring daemon. Uses a single process lookup to avoid
-/// TOCTOU races between checking and killing.
+/// How long `laches stop` waits for the daemon to exit on its own before
+/// killing it.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Ask the daemon over its control socket to close the open session and
+/// exit, then wait for `pid` to go away. False if the daemon could not be
+/// reached or is still running after `SHUTDOWN_TIMEOUT`.
+fn request_shutdown(config_dir: &Path, pid: u32) -> bool {
+    if ipc::request(&ipc::socket_path(config_dir), ipc::Command::Shutdown).is_err() {
+        return false;
+    }
+    let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+    while Instant::now() < deadline {
+        if !find_daemon_process(&mut System::new(), pid) {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    false
+}
+
+/// Stop the monitoring daemon, gracefully over the control socket when
+/// possible so the open session is closed, else by killing it.
 pub fn stop_monitoring(config_dir: &Path) -> Result<(), LachesError> {
     let pid = match read_daemon_pid(config_dir) {
         Some(pid) => pid,
@@ -107,7 +130,9 @@ pub fn stop_monitoring(config_dir: &Path) -> Result<(), LachesError> {
 
     if let Some(process) = sys.process(sysinfo_pid) {
         if process.name().contains("laches_mon") {
-            process.kill();
+            if !request_shutdown(config_dir, pid) {
+                process.kill();
+            }
             clear_daemon_pid(config_dir);
             println!("info: stopped laches_mon (pid: {})", pid);
         } else {
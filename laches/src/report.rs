@@ -0,0 +1,803 @@
+//! Weekly, monthly and custom range reports, rendered as Markdown or as a
+//! single HTML file with inline SVG charts.
+
+use crate::db::{date_range_for_day, Database};
+use crate::error::LachesError;
+use crate::titles::{group, TitleGroup, TitleNormalizer};
+use crate::utils::{format_duration_hm, heat_char, truncate_str};
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+
+/// Processes listed in a report; the rest are only part of the total.
+pub const TOP_PROCESSES: usize = 15;
+
+/// Window titles listed in a report.
+pub const TOP_TITLES: usize = 10;
+
+/// Active seconds per local hour of one day.
+pub type DayHours = (NaiveDate, [i64; 24]);
+
+/// Local days `first..=last` covered by a report.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Period {
+    pub first: NaiveDate,
+    pub last: NaiveDate,
+}
+
+impl Period {
+    /// The last `n` days, ending with `today`.
+    pub fn last_days(today: NaiveDate, n: i64) -> Self {
+        Period {
+            first: today - Duration::days(n - 1),
+            last: today,
+        }
+    }
+
+    pub fn days(&self) -> i64 {
+        (self.last - self.first).num_days() + 1
+    }
+
+    /// The period of the same length just before this one.
+    pub fn previous(&self) -> Self {
+        let last = self.first - Duration::days(1);
+        Period {
+            first: last - Duration::days(self.days() - 1),
+            last,
+        }
+    }
+
+    /// Every day of the period, in order.
+    pub fn dates(&self) -> impl Iterator<Item = NaiveDate> {
+        self.first.iter_days().take(self.days() as usize)
+    }
+
+    /// UTC bounds of the period, for database queries.
+    fn bounds(&self) -> Result<(String, String), LachesError> {
+        let day = |d: NaiveDate| date_range_for_day(&d.format("%Y-%m-%d").to_string());
+        match (day(self.first), day(self.last)) {
+            (Some((start, _)), Some((_, end))) => Ok((start, end)),
+            _ => Err(format!("no such date range: {}", self).into()),
+        }
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.first == self.last {
+            write!(f, "{}", self.first.format("%Y-%m-%d"))
+        } else {
+            write!(
+                f,
+                "{} to {}",
+                self.first.format("%Y-%m-%d"),
+                self.last.format("%Y-%m-%d")
+            )
+        }
+    }
+}
+
+/// Active time for a tag or process, with the same figure for the
+/// previous period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Total {
+    pub name: String,
+    pub secs: i64,
+    pub previous_secs: i64,
+}
+
+/// Everything shown in a report.
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub period: Period,
+    pub previous: Period,
+    pub generated: DateTime<Local>,
+    pub total_secs: i64,
+    pub previous_secs: i64,
+    /// Tags with time in either period, busiest first.
+    pub tags: Vec<Total>,
+    /// Processes with time in this period, busiest first.
+    pub processes: Vec<Total>,
+    /// Active seconds for every day of the period.
+    pub daily: Vec<(NaiveDate, i64)>,
+    /// Active seconds per local hour for every day of the period.
+    pub hours: Vec<DayHours>,
+    /// Busiest window titles, grouped by normalized title, at most
+    /// [`TOP_TITLES`].
+    pub titles: Vec<TitleGroup>,
+}
+
+/// Gather a report for `period`. `tags` are the tag names to break time
+/// down by and `normalizer` groups the window titles.
+pub fn build(
+    db: &Database,
+    period: Period,
+    tags: &[String],
+    normalizer: &TitleNormalizer,
+    now: DateTime<Utc>,
+) -> Result<Report, LachesError> {
+    let previous = period.previous();
+    let (start, end) = period.bounds()?;
+    let (prev_start, prev_end) = previous.bounds()?;
+
+    let mut tag_totals = Vec::new();
+    for tag in tags {
+        let total = Total {
+            name: tag.clone(),
+            secs: db.query_target_seconds(&start, &end, None, Some(tag))?,
+            previous_secs: db.query_target_seconds(&prev_start, &prev_end, None, Some(tag))?,
+        };
+        if total.secs > 0 || total.previous_secs > 0 {
+            tag_totals.push(total);
+        }
+    }
+    tag_totals.sort_by(|a, b| b.secs.cmp(&a.secs).then(a.name.cmp(&b.name)));
+
+    let previous_by_process: HashMap<String, i64> = db
+        .query_process_summaries(&prev_start, &prev_end, None)?
+        .into_iter()
+        .map(|s| (s.process_name, s.total_seconds))
+        .collect();
+    let processes = db
+        .query_process_summaries(&start, &end, None)?
+        .into_iter()
+        .map(|s| Total {
+            previous_secs: previous_by_process
+                .get(&s.process_name)
+                .copied()
+                .unwrap_or(0),
+            name: s.process_name,
+            secs: s.total_seconds,
+        })
+        .collect();
+
+    let by_day: HashMap<String, i64> = db.query_daily_totals(&start, &end)?.into_iter().collect();
+    let daily = period
+        .dates()
+        .map(|d| {
+            let key = d.format("%Y-%m-%d").to_string();
+            (d, by_day.get(&key).copied().unwrap_or(0))
+        })
+        .collect();
+
+    let by_hour: HashMap<String, [i64; 24]> =
+        db.query_hourly_totals(&start, &end)?.into_iter().collect();
+    let hours = period
+        .dates()
+        .map(|d| {
+            let key = d.format("%Y-%m-%d").to_string();
+            (d, by_hour.get(&key).copied().unwrap_or([0; 24]))
+        })
+        .collect();
+
+    Ok(Report {
+        period,
+        previous,
+        generated: now.with_timezone(&Local),
+        total_secs: db.query_total_active_seconds(&start, &end)?,
+        previous_secs: db.query_total_active_seconds(&prev_start, &prev_end)?,
+        tags: tag_totals,
+        processes,
+        daily,
+        hours,
+        titles: {
+            let mut titles = group(
+                &db.query_title_summaries(&start, &end, None)?,
+                Some(normalizer),
+            );
+            titles.truncate(TOP_TITLES);
+            titles
+        },
+    })
+}
+
+impl Report {
+    /// Average active time per day of the period.
+    pub fn daily_average(&self) -> i64 {
+        self.total_secs / self.period.days().max(1)
+    }
+
+    /// The day with the most active time, if anything was tracked.
+    pub fn busiest_day(&self) -> Option<(NaiveDate, i64)> {
+        self.daily
+            .iter()
+            .copied()
+            .filter(|(_, secs)| *secs > 0)
+            .max_by_key(|(d, secs)| (*secs, std::cmp::Reverse(*d)))
+    }
+
+    fn busiest_hour_secs(&self) -> i64 {
+        self.hours
+            .iter()
+            .flat_map(|(_, h)| h.iter().copied())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Render the report as Markdown.
+    pub fn write_markdown<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        writeln!(out, "# laches report: {}", self.period)?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "compared with {}. generated {}.",
+            self.previous,
+            self.generated.format("%Y-%m-%d %H:%M")
+        )?;
+        writeln!(out)?;
+
+        let previous_average = self.previous_secs / self.previous.days().max(1);
+        writeln!(out, "| | this period | previous period | change |")?;
+        writeln!(out, "|---|---:|---:|---:|")?;
+        writeln!(
+            out,
+            "| active time | {} | {} | {} |",
+            format_duration_hm(self.total_secs),
+            format_duration_hm(self.previous_secs),
+            change(self.total_secs, self.previous_secs)
+        )?;
+        writeln!(
+            out,
+            "| daily average | {} | {} | {} |",
+            format_duration_hm(self.daily_average()),
+            format_duration_hm(previous_average),
+            change(self.daily_average(), previous_average)
+        )?;
+        if let Some((day, secs)) = self.busiest_day() {
+            writeln!(
+                out,
+                "| busiest day | {} ({}) | | |",
+                day.format("%a %Y-%m-%d"),
+                format_duration_hm(secs)
+            )?;
+        }
+
+        writeln!(out)?;
+        writeln!(out, "## daily")?;
+        writeln!(out)?;
+        let max_day = self.daily.iter().map(|(_, s)| *s).max().unwrap_or(0).max(1);
+        writeln!(out, "```")?;
+        for (day, secs) in &self.daily {
+            let filled = (*secs as f64 / max_day as f64 * 30.0).round() as usize;
+            writeln!(
+                out,
+                "{}  {:>8}  {}",
+                day.format("%a %m-%d"),
+                format_duration_hm(*secs),
+                "\u{2588}".repeat(filled)
+            )?;
+        }
+        writeln!(out, "```")?;
+
+        writeln!(out)?;
+        writeln!(out, "## hour of day")?;
+        writeln!(out)?;
+        let max_hour = self.busiest_hour_secs().max(1);
+        writeln!(out, "```")?;
+        writeln!(out, "           0     6     12    18")?;
+        for (day, hours) in &self.hours {
+            let cells: String = hours.iter().map(|s| heat_char(*s, max_hour)).collect();
+            writeln!(out, "{}  {}", day.format("%a %m-%d"), cells)?;
+        }
+        writeln!(out, "```")?;
+        writeln!(
+            out,
+            "\neach column is one hour; darker means more active time, up to {} in an hour.",
+            format_duration_hm(self.busiest_hour_secs())
+        )?;
+
+        if !self.tags.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "## tags")?;
+            writeln!(out)?;
+            self.write_totals_table(out, "tag", &self.tags)?;
+        }
+
+        writeln!(out)?;
+        writeln!(out, "## processes")?;
+        writeln!(out)?;
+        if self.processes.is_empty() {
+            writeln!(out, "nothing tracked in this period.")?;
+        } else {
+            let top = &self.processes[..self.processes.len().min(TOP_PROCESSES)];
+            self.write_totals_table(out, "process", top)?;
+            if self.processes.len() > top.len() {
+                writeln!(
+                    out,
+                    "\nand {} more processes.",
+                    self.processes.len() - top.len()
+                )?;
+            }
+        }
+
+        if !self.titles.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "## top window titles")?;
+            writeln!(out)?;
+            writeln!(out, "| title | process | active |")?;
+            writeln!(out, "|---|---|---:|")?;
+            for t in &self.titles {
+                writeln!(
+                    out,
+                    "| {} | {} | {} |",
+                    markdown_cell(&truncate_str(&t.name, 80)),
+                    markdown_cell(&t.process_name),
+                    format_duration_hm(t.total_seconds)
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_totals_table<W: Write>(
+        &self,
+        out: &mut W,
+        column: &str,
+        totals: &[Total],
+    ) -> io::Result<()> {
+        writeln!(out, "| {} | active | share | previous | change |", column)?;
+        writeln!(out, "|---|---:|---:|---:|---:|")?;
+        for t in totals {
+            writeln!(
+                out,
+                "| {} | {} | {} | {} | {} |",
+                markdown_cell(&t.name),
+                format_duration_hm(t.secs),
+                share(t.secs, self.total_secs),
+                format_duration_hm(t.previous_secs),
+                change(t.secs, t.previous_secs)
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Render the report as a single self-contained HTML page.
+    pub fn write_html<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let title = format!("laches report: {}", self.period);
+        writeln!(out, "<!DOCTYPE html>")?;
+        writeln!(out, "<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">")?;
+        writeln!(out, "<title>{}</title>", escape_html(&title))?;
+        writeln!(out, "<style>{}</style>\n</head>\n<body>", STYLE)?;
+        writeln!(out, "<h1>{}</h1>", escape_html(&title))?;
+        writeln!(
+            out,
+            "<p class=\"muted\">compared with {}. generated {}.</p>",
+            self.previous,
+            self.generated.format("%Y-%m-%d %H:%M")
+        )?;
+
+        let previous_average = self.previous_secs / self.previous.days().max(1);
+        writeln!(out, "<div class=\"cards\">")?;
+        card(
+            out,
+            "active time",
+            &format_duration_hm(self.total_secs),
+            &change(self.total_secs, self.previous_secs),
+        )?;
+        card(
+            out,
+            "daily average",
+            &format_duration_hm(self.daily_average()),
+            &change(self.daily_average(), previous_average),
+        )?;
+        if let Some((day, secs)) = self.busiest_day() {
+            card(
+                out,
+                "busiest day",
+                &day.format("%a %b %-d").to_string(),
+                &format_duration_hm(secs),
+            )?;
+        }
+        writeln!(out, "</div>")?;
+
+        writeln!(out, "<h2>daily</h2>")?;
+        self.write_daily_svg(out)?;
+        writeln!(out, "<h2>hour of day</h2>")?;
+        self.write_heatmap_svg(out)?;
+
+        if !self.tags.is_empty() {
+            writeln!(out, "<h2>tags</h2>")?;
+            self.write_totals_html(out, "tag", &self.tags)?;
+        }
+
+        writeln!(out, "<h2>processes</h2>")?;
+        if self.processes.is_empty() {
+            writeln!(
+                out,
+                "<p class=\"muted\">nothing tracked in this period.</p>"
+            )?;
+        } else {
+            let top = &self.processes[..self.processes.len().min(TOP_PROCESSES)];
+            self.write_totals_html(out, "process", top)?;
+            if self.processes.len() > top.len() {
+                writeln!(
+                    out,
+                    "<p class=\"muted\">and {} more processes.</p>",
+                    self.processes.len() - top.len()
+                )?;
+            }
+        }
+
+        if !self.titles.is_empty() {
+            writeln!(out, "<h2>top window titles</h2>")?;
+            writeln!(
+                out,
+                "<table>\n<tr><th>title</th><th>process</th><th class=\"num\">active</th></tr>"
+            )?;
+            for t in &self.titles {
+                writeln!(
+                    out,
+                    "<tr><td>{}</td><td>{}</td><td class=\"num\">{}</td></tr>",
+                    escape_html(&truncate_str(&t.name, 100)),
+                    escape_html(&t.process_name),
+                    format_duration_hm(t.total_seconds)
+                )?;
+            }
+            writeln!(out, "</table>")?;
+        }
+
+        writeln!(out, "</body>\n</html>")
+    }
+
+    fn write_totals_html<W: Write>(
+        &self,
+        out: &mut W,
+        column: &str,
+        totals: &[Total],
+    ) -> io::Result<()> {
+        let max = totals.iter().map(|t| t.secs).max().unwrap_or(0).max(1);
+        writeln!(
+            out,
+            "<table>\n<tr><th>{}</th><th class=\"num\">active</th><th></th>\
+             <th class=\"num\">share</th><th class=\"num\">previous</th>\
+             <th class=\"num\">change</th></tr>",
+            column
+        )?;
+        for t in totals {
+            writeln!(
+                out,
+                "<tr><td>{}</td><td class=\"num\">{}</td>\
+                 <td><svg width=\"120\" height=\"10\"><rect width=\"{:.1}\" height=\"10\" rx=\"2\" fill=\"{}\"/></svg></td>\
+                 <td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+                escape_html(&t.name),
+                format_duration_hm(t.secs),
+                t.secs as f64 / max as f64 * 120.0,
+                ACCENT,
+                share(t.secs, self.total_secs),
+                format_duration_hm(t.previous_secs),
+                change(t.secs, t.previous_secs)
+            )?;
+        }
+        writeln!(out, "</table>")
+    }
+
+    fn write_daily_svg<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        const HEIGHT: f64 = 160.0;
+        const LABELS: f64 = 20.0;
+        let step = (720.0 / self.daily.len().max(1) as f64).clamp(6.0, 48.0);
+        let width = step * self.daily.len() as f64;
+        let max = self.daily.iter().map(|(_, s)| *s).max().unwrap_or(0).max(1);
+        // label every day while they fit, otherwise every monday
+        let every_day = step >= 40.0;
+
+        writeln!(
+            out,
+            "<svg class=\"chart\" width=\"{:.0}\" height=\"{:.0}\" role=\"img\">",
+            width,
+            HEIGHT + LABELS
+        )?;
+        for (i, (day, secs)) in self.daily.iter().enumerate() {
+            let x = i as f64 * step;
+            let h = *secs as f64 / max as f64 * (HEIGHT - 4.0);
+            writeln!(
+                out,
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" rx=\"2\" fill=\"{}\">\
+                 <title>{}: {}</title></rect>",
+                x + step * 0.15,
+                HEIGHT - h,
+                step * 0.7,
+                h,
+                ACCENT,
+                day.format("%a %Y-%m-%d"),
+                format_duration_hm(*secs)
+            )?;
+            if every_day || day.format("%u").to_string() == "1" {
+                let label = if every_day {
+                    day.format("%a %-d")
+                } else {
+                    day.format("%b %-d")
+                };
+                writeln!(
+                    out,
+                    "<text x=\"{:.1}\" y=\"{:.0}\" class=\"label\">{}</text>",
+                    x + step * 0.15,
+                    HEIGHT + 14.0,
+                    label
+                )?;
+            }
+        }
+        writeln!(out, "</svg>")
+    }
+
+    fn write_heatmap_svg<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        const CELL: f64 = 22.0;
+        const ROW: f64 = 16.0;
+        const LEFT: f64 = 80.0;
+        const TOP: f64 = 16.0;
+        let max = self.busiest_hour_secs().max(1);
+
+        writeln!(
+            out,
+            "<svg class=\"chart\" width=\"{:.0}\" height=\"{:.0}\" role=\"img\">",
+            LEFT + CELL * 24.0,
+            TOP + ROW * self.hours.len() as f64
+        )?;
+        for hour in (0..24).step_by(3) {
+            writeln!(
+                out,
+                "<text x=\"{:.0}\" y=\"11\" class=\"label\">{:02}</text>",
+                LEFT + hour as f64 * CELL,
+                hour
+            )?;
+        }
+        for (row, (day, hours)) in self.hours.iter().enumerate() {
+            let y = TOP + row as f64 * ROW;
+            writeln!(
+                out,
+                "<text x=\"0\" y=\"{:.0}\" class=\"label\">{}</text>",
+                y + 11.0,
+                day.format("%a %b %-d")
+            )?;
+            for (hour, secs) in hours.iter().enumerate() {
+                let fill = if *secs > 0 {
+                    format!(
+                        "fill=\"{}\" fill-opacity=\"{:.2}\"",
+                        ACCENT,
+                        0.15 + 0.85 * (*secs as f64 / max as f64)
+                    )
+                } else {
+                    "fill=\"#eceef1\"".to_string()
+                };
+                writeln!(
+                    out,
+                    "<rect x=\"{:.0}\" y=\"{:.0}\" width=\"{:.0}\" height=\"{:.0}\" rx=\"2\" {}>\
+                     <title>{} {:02}:00: {}</title></rect>",
+                    LEFT + hour as f64 * CELL,
+                    y,
+                    CELL - 2.0,
+                    ROW - 2.0,
+                    fill,
+                    day.format("%a %Y-%m-%d"),
+                    hour,
+                    format_duration_hm(*secs)
+                )?;
+            }
+        }
+        writeln!(out, "</svg>")
+    }
+}
+
+const ACCENT: &str = "#3b6fd8";
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:880px;margin:2em auto;\
+padding:0 1em;color:#222}h1{font-size:1.5em}h2{font-size:1.15em;margin-top:2em}\
+.muted{color:#777}.cards{display:flex;gap:1em;flex-wrap:wrap}\
+.card{border:1px solid #ddd;border-radius:6px;padding:.6em 1em;min-width:10em}\
+.card .value{font-size:1.4em;font-weight:600}.card .note{color:#777}\
+table{border-collapse:collapse;width:100%}th,td{text-align:left;padding:.25em .5em;\
+border-bottom:1px solid #eee}.num{text-align:right;white-space:nowrap}\
+.chart{display:block;overflow:visible}.label{font-size:10px;fill:#777}";
+
+fn card<W: Write>(out: &mut W, label: &str, value: &str, note: &str) -> io::Result<()> {
+    writeln!(
+        out,
+        "<div class=\"card\"><div class=\"muted\">{}</div><div class=\"value\">{}</div>\
+         <div class=\"note\">{}</div></div>",
+        label,
+        escape_html(value),
+        escape_html(note)
+    )
+}
+
+/// Difference from the previous period, e.g. `+1h 5m (+12%)`.
+fn change(now: i64, before: i64) -> String {
+    let diff = now - before;
+    if diff == 0 {
+        return "same".to_string();
+    }
+    if before == 0 {
+        return "new".to_string();
+    }
+    let sign = if diff > 0 { '+' } else { '-' };
+    format!(
+        "{}{} ({}{}%)",
+        sign,
+        format_duration_hm(diff.abs()),
+        sign,
+        (diff.abs() as f64 / before as f64 * 100.0).round()
+    )
+}
+
+fn share(secs: i64, total: i64) -> String {
+    if total <= 0 {
+        return "-".to_string();
+    }
+    format!("{:.0}%", secs as f64 / total as f64 * 100.0)
+}
+
+/// Make `s` safe inside a markdown table cell. Markup characters are
+/// escaped too, since most renderers pass inline html through.
+fn markdown_cell(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '|' => escaped.push_str("\\|"),
+            '\n' | '\r' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{format_timestamp, Session, SessionKind};
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 4, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 4, day).unwrap()
+    }
+
+    fn record(db: &Database, process: &str, title: &str, start: DateTime<Local>, minutes: i64) {
+        db.add_session(&Session {
+            id: 0,
+            process_name: process.to_string(),
+            exe_path: None,
+            window_title: Some(title.to_string()),
+            start_time: format_timestamp(&start),
+            end_time: Some(format_timestamp(&(start + Duration::minutes(minutes)))),
+            idle: false,
+            kind: SessionKind::Active,
+            utc_offset: None,
+            machine: None,
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_period() {
+        let week = Period::last_days(date(14), 7);
+        assert_eq!(week.first, date(8));
+        assert_eq!(week.days(), 7);
+        assert_eq!(
+            week.previous(),
+            Period {
+                first: date(1),
+                last: date(7)
+            }
+        );
+        assert_eq!(week.dates().count(), 7);
+        assert_eq!(week.to_string(), "2026-04-08 to 2026-04-14");
+    }
+
+    #[test]
+    fn test_build_report() {
+        let db = Database::open_memory().unwrap();
+        db.add_tag("code", "dev").unwrap();
+        // previous period
+        record(&db, "code", "main.rs", at(3, 9, 0), 60);
+        // this period: 09:30-11:15 spans three hours
+        record(&db, "code", "main.rs", at(9, 9, 30), 105);
+        record(&db, "firefox", "docs | rust", at(10, 14, 0), 30);
+        db.insert_session(
+            "code",
+            SessionKind::Idle,
+            &format_timestamp(&at(10, 15, 0)),
+            &format_timestamp(&at(10, 16, 0)),
+        )
+        .unwrap();
+
+        let period = Period::last_days(date(14), 7);
+        let report = build(
+            &db,
+            period,
+            &["dev".to_string(), "unused".to_string()],
+            &TitleNormalizer::new(&[]).unwrap(),
+            Utc::now(),
+        )
+        .unwrap();
+
+        assert_eq!(report.total_secs, 135 * 60);
+        assert_eq!(report.previous_secs, 60 * 60);
+        assert_eq!(
+            report.tags,
+            vec![Total {
+                name: "dev".to_string(),
+                secs: 105 * 60,
+                previous_secs: 60 * 60
+            }]
+        );
+        assert_eq!(report.processes[0].name, "code");
+        assert_eq!(report.processes[0].previous_secs, 3600);
+        assert_eq!(report.processes[1].previous_secs, 0);
+
+        assert_eq!(report.daily.len(), 7);
+        assert_eq!(report.daily[1], (date(9), 105 * 60));
+        assert_eq!(report.busiest_day(), Some((date(9), 105 * 60)));
+
+        let (day, hours) = report.hours[1];
+        assert_eq!(day, date(9));
+        assert_eq!(&hours[9..12], &[30 * 60, 60 * 60, 15 * 60]);
+        assert_eq!(report.hours[2].1[15], 0);
+
+        assert_eq!(report.titles.len(), 2);
+        assert_eq!(report.titles[0].name, "main.rs");
+        assert_eq!(report.titles[0].total_seconds, 105 * 60);
+        assert_eq!(report.titles[1].name, "rust");
+    }
+
+    #[test]
+    fn test_render() {
+        let db = Database::open_memory().unwrap();
+        record(&db, "code", "a <b> | c", at(9, 9, 0), 60);
+        let normalizer = TitleNormalizer::new(&[]).unwrap();
+        let report = build(
+            &db,
+            Period::last_days(date(14), 7),
+            &[],
+            &normalizer,
+            Utc::now(),
+        )
+        .unwrap();
+
+        let mut md = Vec::new();
+        report.write_markdown(&mut md).unwrap();
+        let md = String::from_utf8(md).unwrap();
+        assert!(md.starts_with("# laches report: 2026-04-08 to 2026-04-14"));
+        assert!(md.contains("| active time | 1h 0m | 0m | new |"));
+        assert!(md.contains("| a &lt;b&gt; \\| c | code | 1h 0m |"));
+        assert!(!md.contains("## tags"));
+
+        let mut html = Vec::new();
+        report.write_html(&mut html).unwrap();
+        let html = String::from_utf8(html).unwrap();
+        assert!(html.contains("a &lt;b&gt; | c"));
+        assert_eq!(html.matches("<svg class=\"chart\"").count(), 2);
+        assert!(html.trim_end().ends_with("</html>"));
+    }
+
+    #[test]
+    fn test_change() {
+        assert_eq!(change(90 * 60, 60 * 60), "+30m (+50%)");
+        assert_eq!(change(30 * 60, 60 * 60), "-30m (-50%)");
+        assert_eq!(change(60, 60), "same");
+        assert_eq!(change(60, 0), "new");
+        assert_eq!(change(0, 0), "same");
+    }
+}
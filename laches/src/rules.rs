@@ -0,0 +1,266 @@
+//! Rules from `config.toml` compiled for matching windows: whether to
+//! record them, and which tags and category they get.
+
+use crate::config::{MatchKind, Rule, RuleAction, RuleField};
+use regex::Regex;
+
+pub(crate) enum CompiledPattern {
+    Exact(String),
+    Regex(Regex),
+}
+
+impl CompiledPattern {
+    /// Compile a rule pattern. Globs become anchored, case-insensitive
+    /// regexes. Returns None for an invalid regex.
+    pub(crate) fn compile(kind: MatchKind, pattern: &str) -> Option<Self> {
+        match kind {
+            MatchKind::Exact => Some(CompiledPattern::Exact(pattern.to_string())),
+            MatchKind::Glob => Regex::new(&glob_to_regex(pattern))
+                .ok()
+                .map(CompiledPattern::Regex),
+            MatchKind::Regex => Regex::new(pattern).ok().map(CompiledPattern::Regex),
+        }
+    }
+
+    pub(crate) fn matches(&self, name: &str) -> bool {
+        match self {
+            CompiledPattern::Exact(s) => {
+                if cfg!(windows) {
+                    s.eq_ignore_ascii_case(name)
+                } else {
+                    s == name
+                }
+            }
+            CompiledPattern::Regex(r) => r.is_match(name),
+        }
+    }
+}
+
+/// Translate a shell glob into an anchored, case-insensitive regex.
+fn glob_to_regex(glob: &str) -> String {
+    let mut re = String::from("(?i)^");
+    for c in glob.chars() {
+        match c {
+            '*' => re.push_str(".*"),
+            '?' => re.push('.'),
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    re
+}
+
+struct CompiledRule {
+    field: RuleField,
+    pattern: CompiledPattern,
+    process: Option<String>,
+    action: RuleAction,
+    tag: Option<String>,
+    category: Option<String>,
+}
+
+impl CompiledRule {
+    fn matches(
+        &self,
+        process_name: &str,
+        exe_path: Option<&str>,
+        window_title: Option<&str>,
+    ) -> bool {
+        if self.process.as_deref().is_some_and(|p| p != process_name) {
+            return false;
+        }
+        let value = match self.field {
+            RuleField::Process => Some(process_name),
+            RuleField::Title => window_title,
+            RuleField::Exe => exe_path,
+        };
+        value.is_some_and(|v| self.pattern.matches(v))
+    }
+}
+
+/// Rules from `config.toml`, compiled once. Rules with an invalid pattern
+/// are skipped.
+#[derive(Default)]
+pub struct CompiledRules {
+    rules: Vec<CompiledRule>,
+}
+
+impl CompiledRules {
+    pub fn new(rules: &[Rule]) -> Self {
+        let rules = rules
+            .iter()
+            .filter_map(|r| {
+                Some(CompiledRule {
+                    field: r.field,
+                    pattern: CompiledPattern::compile(r.kind, &r.pattern)?,
+                    process: r.process.clone(),
+                    action: r.action,
+                    tag: r.tag.clone(),
+                    category: r.category.as_deref().and_then(crate::category::normalize),
+                })
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// The first matching track/ignore rule decides whether a window is
+    /// recorded. None if no such rule matches.
+    pub fn decide(
+        &self,
+        process_name: &str,
+        exe_path: Option<&str>,
+        window_title: Option<&str>,
+    ) -> Option<bool> {
+        self.rules
+            .iter()
+            .filter(|r| matches!(r.action, RuleAction::Track | RuleAction::Ignore))
+            .find(|r| r.matches(process_name, exe_path, window_title))
+            .map(|r| r.action == RuleAction::Track)
+    }
+
+    /// Tags derived from every matching tag rule, in rule order.
+    pub fn tags_for(
+        &self,
+        process_name: &str,
+        exe_path: Option<&str>,
+        window_title: Option<&str>,
+    ) -> Vec<&str> {
+        let mut tags: Vec<&str> = Vec::new();
+        for rule in &self.rules {
+            if rule.action != RuleAction::Tag || !rule.matches(process_name, exe_path, window_title)
+            {
+                continue;
+            }
+            if let Some(tag) = rule.tag.as_deref() {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+        }
+        tags
+    }
+
+    /// Category from the first matching category rule.
+    pub fn category_for(
+        &self,
+        process_name: &str,
+        exe_path: Option<&str>,
+        window_title: Option<&str>,
+    ) -> Option<&str> {
+        self.rules
+            .iter()
+            .filter(|r| r.action == RuleAction::Category)
+            .find(|r| r.matches(process_name, exe_path, window_title))
+            .and_then(|r| r.category.as_deref())
+    }
+
+    /// Whether any tag rule attaches `tag` to this window.
+    pub fn has_tag(
+        &self,
+        tag: &str,
+        process_name: &str,
+        exe_path: Option<&str>,
+        window_title: Option<&str>,
+    ) -> bool {
+        self.rules.iter().any(|r| {
+            r.action == RuleAction::Tag
+                && r.tag.as_deref() == Some(tag)
+                && r.matches(process_name, exe_path, window_title)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(field: RuleField, kind: MatchKind, pattern: &str, action: RuleAction) -> Rule {
+        Rule {
+            field,
+            kind,
+            pattern: pattern.to_string(),
+            action,
+            tag: None,
+            category: None,
+            process: None,
+        }
+    }
+
+    #[test]
+    fn test_glob_to_regex() {
+        let p = CompiledPattern::compile(MatchKind::Glob, "*YouTube*").unwrap();
+        assert!(p.matches("Cats - youtube - Mozilla Firefox"));
+        assert!(!p.matches("GitHub"));
+
+        let p = CompiledPattern::compile(MatchKind::Glob, "PROJ-???.txt").unwrap();
+        assert!(p.matches("proj-123.txt"));
+        assert!(!p.matches("proj-1234.txt"));
+        assert!(!p.matches("proj-123xtxt"));
+    }
+
+    #[test]
+    fn test_tag_rules() {
+        let rules = CompiledRules::new(&[
+            Rule {
+                tag: Some("work".to_string()),
+                ..rule(
+                    RuleField::Title,
+                    MatchKind::Regex,
+                    "Jira|GitHub",
+                    RuleAction::Tag,
+                )
+            },
+            Rule {
+                tag: Some("browsing".to_string()),
+                ..rule(
+                    RuleField::Process,
+                    MatchKind::Exact,
+                    "firefox",
+                    RuleAction::Tag,
+                )
+            },
+            rule(
+                RuleField::Title,
+                MatchKind::Regex,
+                "[invalid",
+                RuleAction::Ignore,
+            ),
+        ]);
+        assert_eq!(
+            rules.tags_for("firefox", None, Some("PROJ-1 - Jira")),
+            vec!["work", "browsing"]
+        );
+        assert!(rules.has_tag("work", "code", None, Some("laches - GitHub")));
+        assert!(!rules.has_tag("work", "firefox", None, None));
+        assert_eq!(rules.decide("firefox", None, Some("[invalid")), None);
+    }
+
+    #[test]
+    fn test_category_rules_first_match_wins() {
+        let rules = CompiledRules::new(&[
+            Rule {
+                category: Some("work/ clientA/".to_string()),
+                ..rule(
+                    RuleField::Title,
+                    MatchKind::Glob,
+                    "*jira*",
+                    RuleAction::Category,
+                )
+            },
+            Rule {
+                category: Some("work".to_string()),
+                ..rule(RuleField::Title, MatchKind::Glob, "*", RuleAction::Category)
+            },
+        ]);
+        assert_eq!(
+            rules.category_for("firefox", None, Some("PROJ-1 - Jira")),
+            Some("work/clientA")
+        );
+        assert_eq!(
+            rules.category_for("code", None, Some("main.rs")),
+            Some("work")
+        );
+        assert_eq!(rules.category_for("code", None, None), None);
+        assert_eq!(rules.decide("code", None, Some("main.rs")), None);
+    }
+}
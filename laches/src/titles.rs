@@ -0,0 +1,292 @@
+//! Grouping window titles for analysis: application suffixes are stripped,
+//! then regex extractors pull out what is worth grouping by, such as a
+//! browser tab's domain or an editor's project and file.
+
+use crate::config::TitleExtractor;
+use crate::db::TitleSummary;
+use crate::error::LachesError;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// Application names appended to window titles, e.g. " — Mozilla Firefox".
+const APP_SUFFIXES: &[&str] = &[
+    "Mozilla Firefox",
+    "Mozilla Firefox Private Browsing",
+    "Firefox",
+    "LibreWolf",
+    "Google Chrome",
+    "Chromium",
+    "Brave",
+    "Microsoft Edge",
+    "Microsoft\u{200b} Edge",
+    "Opera",
+    "Vivaldi",
+    "Safari",
+    "Visual Studio Code",
+    "VSCodium",
+    "Cursor",
+    "Sublime Text",
+    "Zed",
+];
+
+const BROWSERS: &[&str] = &[
+    "firefox",
+    "firefox-esr",
+    "librewolf",
+    "chrome",
+    "google-chrome",
+    "chromium",
+    "chromium-browser",
+    "brave",
+    "brave-browser",
+    "msedge",
+    "microsoft-edge",
+    "opera",
+    "vivaldi",
+    "safari",
+];
+
+const VSCODE_EDITORS: &[&str] = &["code", "code-oss", "codium", "vscodium", "cursor"];
+
+const JETBRAINS_EDITORS: &[&str] = &[
+    "idea",
+    "clion",
+    "goland",
+    "pycharm",
+    "rustrover",
+    "webstorm",
+    "phpstorm",
+    "rider",
+];
+
+/// Built-in extractors as (processes, pattern, replacement), tried after
+/// the configured ones. An empty process list matches every process.
+const BUILTIN_EXTRACTORS: &[(&[&str], &str, &str)] = &[
+    // a url in the title, as some browsers and extensions add
+    (&[], r"\bhttps?://(?:www\.)?([^/\s:?#]+)", "$1"),
+    // the site name browsers show last: "Pull requests · GitHub"
+    (BROWSERS, r"^.+\s[-—–|·]\s([^-—–|·]{1,40})$", "$1"),
+    // "● main.rs - lachesis" becomes "lachesis/main.rs"
+    (VSCODE_EDITORS, r"^(?:● )?(.+?) [-—] (.+)$", "$2/$1"),
+    // "lachesis – main.rs"
+    (JETBRAINS_EDITORS, r"^(.+?) – (.+?)(?: \[.*\])?$", "$1/$2"),
+];
+
+struct Extractor {
+    /// Process names as compared by [`process_key`]; empty for any.
+    processes: Vec<String>,
+    regex: Regex,
+    replace: Option<String>,
+}
+
+/// Turns raw window titles into the names they are grouped under.
+pub struct TitleNormalizer {
+    suffix: Regex,
+    extractors: Vec<Extractor>,
+}
+
+impl TitleNormalizer {
+    /// Build a normalizer trying `custom` extractors ahead of the built-in
+    /// ones.
+    pub fn new(custom: &[TitleExtractor]) -> Result<Self, LachesError> {
+        let names: Vec<String> = APP_SUFFIXES.iter().map(|s| regex::escape(s)).collect();
+        let suffix = Regex::new(&format!(r"\s+[-—–|]\s+(?:{})$", names.join("|")))
+            .expect("suffix pattern is valid");
+
+        let mut extractors = Vec::new();
+        for e in custom {
+            let regex = Regex::new(&e.pattern).map_err(|err| {
+                LachesError::Config(format!("invalid regex '{}': {}", e.pattern, err))
+            })?;
+            extractors.push(Extractor {
+                processes: e.process.iter().map(|p| process_key(p)).collect(),
+                regex,
+                replace: e.replace.clone(),
+            });
+        }
+        for (processes, pattern, replace) in BUILTIN_EXTRACTORS {
+            extractors.push(Extractor {
+                processes: processes.iter().map(|p| p.to_string()).collect(),
+                regex: Regex::new(pattern).expect("built-in pattern is valid"),
+                replace: Some(replace.to_string()),
+            });
+        }
+        Ok(TitleNormalizer { suffix, extractors })
+    }
+
+    /// The name `title` of `process` is grouped under.
+    pub fn normalize(&self, process: &str, title: &str) -> String {
+        let title = self.suffix.replace(title.trim(), "");
+        let key = process_key(process);
+        for e in &self.extractors {
+            if !e.processes.is_empty() && !e.processes.contains(&key) {
+                continue;
+            }
+            let Some(caps) = e.regex.captures(&title) else {
+                continue;
+            };
+            let name = match &e.replace {
+                Some(replace) => {
+                    let mut name = String::new();
+                    caps.expand(replace, &mut name);
+                    name
+                }
+                None => caps
+                    .get(1)
+                    .or_else(|| caps.get(0))
+                    .map_or(String::new(), |m| m.as_str().to_string()),
+            };
+            let name = name.trim();
+            if !name.is_empty() {
+                return name.to_string();
+            }
+        }
+        title.into_owned()
+    }
+}
+
+/// Process names compare case-insensitively and without `.exe`.
+fn process_key(process: &str) -> String {
+    let lower = process.to_lowercase();
+    lower.strip_suffix(".exe").unwrap_or(&lower).to_string()
+}
+
+/// Active time under one normalized title of a process, with the raw
+/// titles it was made up of, longest first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TitleGroup {
+    pub process_name: String,
+    pub name: String,
+    pub total_seconds: i64,
+    pub titles: Vec<(String, i64)>,
+}
+
+/// Group per-title totals by normalized title, busiest first. With
+/// `normalizer` unset, every raw title is its own group.
+pub fn group(summaries: &[TitleSummary], normalizer: Option<&TitleNormalizer>) -> Vec<TitleGroup> {
+    let mut groups: Vec<TitleGroup> = Vec::new();
+    let mut index: HashMap<(String, String), usize> = HashMap::new();
+    for s in summaries {
+        let name = match normalizer {
+            Some(n) => n.normalize(&s.process_name, &s.window_title),
+            None => s.window_title.clone(),
+        };
+        let i = *index
+            .entry((s.process_name.clone(), name.clone()))
+            .or_insert_with(|| {
+                groups.push(TitleGroup {
+                    process_name: s.process_name.clone(),
+                    name,
+                    total_seconds: 0,
+                    titles: Vec::new(),
+                });
+                groups.len() - 1
+            });
+        groups[i].total_seconds += s.total_seconds;
+        groups[i]
+            .titles
+            .push((s.window_title.clone(), s.total_seconds));
+    }
+
+    for g in &mut groups {
+        g.titles.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    }
+    groups.retain(|g| g.total_seconds > 0);
+    groups.sort_by(|a, b| {
+        b.total_seconds
+            .cmp(&a.total_seconds)
+            .then(a.name.cmp(&b.name))
+    });
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_builtin() {
+        let n = TitleNormalizer::new(&[]).unwrap();
+        assert_eq!(
+            n.normalize(
+                "firefox",
+                "Pull requests · ibra/lachesis · GitHub — Mozilla Firefox"
+            ),
+            "GitHub"
+        );
+        assert_eq!(
+            n.normalize(
+                "chrome.exe",
+                "https://www.example.com/docs?x=1 - Google Chrome"
+            ),
+            "example.com"
+        );
+        assert_eq!(
+            n.normalize("firefox", "New Tab — Mozilla Firefox"),
+            "New Tab"
+        );
+        assert_eq!(
+            n.normalize("code", "● main.rs - lachesis - Visual Studio Code"),
+            "lachesis/main.rs"
+        );
+        assert_eq!(
+            n.normalize("rustrover", "lachesis – db.rs [laches]"),
+            "lachesis/db.rs"
+        );
+        // other processes only lose a known suffix
+        assert_eq!(n.normalize("kitty", "vim - notes.md"), "vim - notes.md");
+    }
+
+    #[test]
+    fn test_custom_extractors_go_first() {
+        let custom = [
+            TitleExtractor {
+                pattern: r"^(?P<branch>\S+) \((?P<repo>\w+)\)$".to_string(),
+                replace: Some("${repo}@${branch}".to_string()),
+                process: Some("Terminal".to_string()),
+            },
+            TitleExtractor {
+                pattern: r"JIRA-\d+".to_string(),
+                replace: None,
+                process: None,
+            },
+        ];
+        let n = TitleNormalizer::new(&custom).unwrap();
+        assert_eq!(n.normalize("terminal", "main (laches)"), "laches@main");
+        assert_eq!(n.normalize("other", "main (laches)"), "main (laches)");
+        assert_eq!(
+            n.normalize("firefox", "JIRA-12 fix it - Jira — Mozilla Firefox"),
+            "JIRA-12"
+        );
+
+        let bad = TitleExtractor {
+            pattern: "(".to_string(),
+            replace: None,
+            process: None,
+        };
+        assert!(TitleNormalizer::new(&[bad]).is_err());
+    }
+
+    #[test]
+    fn test_group() {
+        let summary = |title: &str, secs| TitleSummary {
+            process_name: "code".to_string(),
+            window_title: title.to_string(),
+            total_seconds: secs,
+        };
+        let summaries = [
+            summary("main.rs - lachesis - Visual Studio Code", 600),
+            summary("● main.rs - lachesis - Visual Studio Code", 300),
+            summary("db.rs - lachesis - Visual Studio Code", 1200),
+        ];
+        let n = TitleNormalizer::new(&[]).unwrap();
+        let groups = group(&summaries, Some(&n));
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].name, "lachesis/db.rs");
+        assert_eq!(groups[1].name, "lachesis/main.rs");
+        assert_eq!(groups[1].total_seconds, 900);
+        assert_eq!(groups[1].titles[0].1, 600);
+
+        assert_eq!(group(&summaries, None).len(), 3);
+    }
+}
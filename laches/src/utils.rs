@@ -1,4 +1,6 @@
+use crate::error::LachesError;
 use std::io::{self, Write};
+use std::path::Path;
 
 /// Truncate a string to at most `max_chars` characters, appending "..." if truncated.
 /// Safe for multi-byte UTF-8 strings (never panics on char boundaries).
@@ -58,12 +60,122 @@ pub fn format_uptime(seconds: u64) -> String {
     }
 }
 
+/// One heatmap cell for `value` out of `max`: a dot for nothing, then
+/// four shades.
+pub fn heat_char(value: i64, max: i64) -> char {
+    if value <= 0 {
+        return '\u{00b7}';
+    }
+    match value * 4 / max.max(1) {
+        0 => '\u{2591}',
+        1 => '\u{2592}',
+        2 => '\u{2593}',
+        _ => '\u{2588}',
+    }
+}
+
+/// Format a stored session timestamp as local `HH:MM` for display.
+pub fn local_hm(timestamp: &str) -> Option<String> {
+    crate::db::local_time(timestamp).map(|t| t.format("%H:%M").to_string())
+}
+
+/// Parse a length of time such as `45s`, `30m`, `2h` or `1h30m`. Zero,
+/// negative and overflowing lengths are rejected.
+pub fn parse_duration_hms(s: &str) -> Option<chrono::Duration> {
+    let mut total = chrono::Duration::zero();
+    let mut digits = String::new();
+    for c in s.trim().chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let n: i64 = digits.parse().ok()?;
+        let part = match c {
+            'h' => chrono::Duration::try_hours(n),
+            'm' => chrono::Duration::try_minutes(n),
+            's' => chrono::Duration::try_seconds(n),
+            _ => None,
+        }?;
+        total = total.checked_add(&part)?;
+        digits.clear();
+    }
+    (digits.is_empty() && total > chrono::Duration::zero()).then_some(total)
+}
+
 pub fn session_duration_secs(start_time: &str, end_time: &str) -> Option<i64> {
-    let st = chrono::NaiveDateTime::parse_from_str(start_time, crate::db::TIMESTAMP_FORMAT).ok()?;
-    let en = chrono::NaiveDateTime::parse_from_str(end_time, crate::db::TIMESTAMP_FORMAT).ok()?;
+    let st = crate::db::parse_timestamp(start_time)?;
+    let en = crate::db::parse_timestamp(end_time)?;
     Some((en - st).num_seconds().max(0))
 }
 
+/// Use `spec` as the local time zone for the rest of this process, so day
+/// boundaries and displayed times follow it. Accepts `utc`, a fixed offset
+/// such as `+05:30`, or an IANA zone name such as `Europe/Berlin`.
+///
+/// Works by setting `TZ`, which both chrono's `Local` and SQLite's
+/// `localtime` modifier honor on unix. Call before any database query.
+pub fn set_report_timezone(spec: &str) -> Result<(), LachesError> {
+    if !cfg!(unix) {
+        return Err("--tz is only supported on linux and macos".into());
+    }
+    let tz = posix_tz(spec)?;
+    std::env::set_var("TZ", tz);
+    Ok(())
+}
+
+/// Translate a `--tz` value into a `TZ` environment value.
+fn posix_tz(spec: &str) -> Result<String, LachesError> {
+    if spec.eq_ignore_ascii_case("utc") || spec == "Z" {
+        return Ok("UTC0".to_string());
+    }
+
+    if spec.starts_with('+') || spec.starts_with('-') {
+        let east = parse_utc_offset(spec)
+            .ok_or_else(|| format!("invalid utc offset '{}', use +HH:MM or -HH:MM", spec))?;
+        let (hours, minutes) = (east.abs() / 3600, east.abs() % 3600 / 60);
+        // POSIX offsets count hours west of UTC, so the sign flips
+        let (label_sign, posix_sign) = if east < 0 { ('-', '+') } else { ('+', '-') };
+        return Ok(format!(
+            "<{}{:02}{:02}>{}{:02}:{:02}",
+            label_sign, hours, minutes, posix_sign, hours, minutes
+        ));
+    }
+
+    let tzdir = std::env::var("TZDIR").unwrap_or_else(|_| "/usr/share/zoneinfo".to_string());
+    let known = !spec.split('/').any(|part| part.is_empty() || part == "..")
+        && Path::new(&tzdir).join(spec).is_file();
+    if known {
+        Ok(spec.to_string())
+    } else {
+        Err(format!("unknown time zone '{}'", spec).into())
+    }
+}
+
+/// Parse `+HH:MM`, `+HHMM` or `+HH` into seconds east of UTC.
+fn parse_utc_offset(s: &str) -> Option<i32> {
+    let (sign, rest) = match s.as_bytes().first()? {
+        b'+' => (1, &s[1..]),
+        b'-' => (-1, &s[1..]),
+        _ => return None,
+    };
+    let digits: String = rest.chars().filter(|c| *c != ':').collect();
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        2 => (digits.parse::<i32>().ok()?, 0),
+        4 => (
+            digits[..2].parse::<i32>().ok()?,
+            digits[2..].parse::<i32>().ok()?,
+        ),
+        _ => return None,
+    };
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
 pub fn confirm(prompt: &str) -> bool {
     print!("{}", prompt);
     io::stdout().flush().unwrap();
@@ -78,6 +190,37 @@ pub fn confirm(prompt: &str) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_duration_hms() {
+        let minutes = chrono::Duration::minutes;
+        assert_eq!(parse_duration_hms("30m"), Some(minutes(30)));
+        assert_eq!(parse_duration_hms("1h30m"), Some(minutes(90)));
+        assert_eq!(
+            parse_duration_hms("45s"),
+            Some(chrono::Duration::seconds(45))
+        );
+        for bad in ["", "30", "m", "0m", "1x", "1h30", "99999999999999999h"] {
+            assert_eq!(parse_duration_hms(bad), None, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_posix_tz_offsets() {
+        assert_eq!(posix_tz("UTC").unwrap(), "UTC0");
+        assert_eq!(posix_tz("+05:30").unwrap(), "<+0530>-05:30");
+        assert_eq!(posix_tz("-08").unwrap(), "<-0800>+08:00");
+        assert_eq!(posix_tz("+0100").unwrap(), "<+0100>-01:00");
+        assert!(posix_tz("+25:00").is_err());
+        assert!(posix_tz("+5:3").is_err());
+    }
+
+    #[test]
+    fn test_posix_tz_rejects_unknown_names() {
+        assert!(posix_tz("Not/AZone").is_err());
+        assert!(posix_tz("../etc/passwd").is_err());
+        assert!(posix_tz("").is_err());
+    }
+
     #[test]
     fn test_format_uptime_seconds_only() {
         assert_eq!(format_uptime(0), "0s");
@@ -1,9 +1,15 @@
+use chrono::{DateTime, Local, NaiveDate};
 use laches::{
+    api,
     commands::filtering::CompiledFilter,
-    config::{get_machine_id, load_or_create_config},
-    db::Database,
-    platform::{create_tracker, FocusInfo},
+    config::{config_path, get_machine_id, load_config, load_or_create_config, Config, Rule},
+    db::{format_timestamp, Database, SessionKind},
+    focus::FocusWatch,
+    goals, ipc,
+    limits::{Alert, LimitAlerts},
+    platform::{self, create_tracker, FocusInfo, PowerEvent},
 };
+use serde_json::{json, Value};
 use std::{
     env,
     fs::OpenOptions,
@@ -11,10 +17,11 @@ use std::{
     path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc,
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        Arc, RwLock,
     },
     thread,
-    time::Duration,
+    time::{Duration, Instant, SystemTime},
 };
 
 /// Simple file-based logger for the daemon.
@@ -41,7 +48,81 @@ impl DaemonLogger {
     }
 }
 
-fn init_daemon(config_dir: &Path) -> (Database, laches::config::Config, DaemonLogger, PathBuf) {
+/// The parts of the config the monitoring loop reads, swapped out as a
+/// whole on `reload`.
+struct Settings {
+    config: Config,
+    filter: CompiledFilter,
+    /// The rules as seen by the api thread, updated in place on reload.
+    shared_rules: Arc<RwLock<Vec<Rule>>>,
+}
+
+impl Settings {
+    fn new(config: Config) -> Self {
+        let filter = CompiledFilter::new(
+            config.filtering.mode.clone(),
+            &config.filtering.whitelist,
+            &config.filtering.blacklist,
+        )
+        .with_rules(&config.rules);
+        let shared_rules = Arc::new(RwLock::new(config.rules.clone()));
+        Settings {
+            config,
+            filter,
+            shared_rules,
+        }
+    }
+
+    /// Whether a window would be recorded under these settings.
+    fn tracks(&self, info: &FocusInfo) -> bool {
+        self.filter.should_track_window(
+            &info.process_name,
+            info.exe_path.as_deref(),
+            info.window_title.as_deref(),
+        )
+    }
+}
+
+/// Notices edits to config.toml by polling its modification time, which
+/// works the same on every platform and costs one `stat` per poll.
+struct ConfigWatcher {
+    path: PathBuf,
+    seen: Option<SystemTime>,
+}
+
+impl ConfigWatcher {
+    fn new(path: PathBuf) -> Self {
+        let seen = modified(&path);
+        ConfigWatcher { path, seen }
+    }
+
+    /// Whether the file changed since the last call. A file that is
+    /// missing (e.g. mid-replace by an editor) doesn't count as a change.
+    fn changed(&mut self) -> bool {
+        match modified(&self.path) {
+            Some(time) if Some(time) != self.seen => {
+                self.seen = Some(time);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// What wakes the monitoring loop before `check_interval` is up.
+enum Event {
+    /// The tracker saw the focus change.
+    Focus,
+    /// A client sent a command over the control socket and waits for the
+    /// reply on the sender.
+    Command(ipc::Command, Sender<Value>),
+}
+
+fn init_daemon(config_dir: &Path) -> (Database, Config, DaemonLogger, PathBuf) {
     let mut logger = DaemonLogger::open(config_dir).expect("error: failed to open daemon.log");
 
     let config = match load_or_create_config(config_dir) {
@@ -53,13 +134,13 @@ fn init_daemon(config_dir: &Path) -> (Database, laches::config::Config, DaemonLo
     };
 
     let machine_id = get_machine_id(config_dir);
-    let data_dir = laches::config::data_dir(config_dir);
+    let data_dir = laches::config::data_dir(config_dir, &config);
     if let Err(e) = std::fs::create_dir_all(&data_dir) {
         logger.log(&format!("error: failed to create data directory: {}", e));
         std::process::exit(1);
     }
 
-    let db_path = laches::config::machine_db_path(config_dir, &machine_id);
+    let db_path = laches::config::machine_db_path(config_dir, &config, &machine_id);
     let db = match Database::open(&db_path) {
         Ok(d) => d,
         Err(e) => {
@@ -67,65 +148,500 @@ fn init_daemon(config_dir: &Path) -> (Database, laches::config::Config, DaemonLo
             std::process::exit(1);
         }
     };
+    // goals and limits count rule-derived tags from the start
+    if let Err(e) = db.set_rules(&config.rules) {
+        logger.log(&format!("warning: failed to apply rules: {}", e));
+    }
 
     (db, config, logger, db_path)
 }
 
+/// Extra wall-clock time beyond `check_interval` between two polls that
+/// means the daemon was not running, i.e. the machine was suspended.
+const SUSPEND_GRACE: Duration = Duration::from_secs(30);
+
+/// Whether the wall clock moved much further than one poll interval since
+/// the last poll. Catches suspends the OS did not announce.
+fn is_clock_jump(last_tick: DateTime<Local>, now: DateTime<Local>, interval: Duration) -> bool {
+    (now - last_tick)
+        .to_std()
+        .is_ok_and(|gap| gap > interval + SUSPEND_GRACE)
+}
+
+/// Sleep until the tracker reports a focus change, a command arrives or
+/// `interval` passes, and return the commands to answer. Without a
+/// subscription or control socket nothing is ever sent, so this is a plain
+/// sleep.
+fn wait_for_change(
+    events: &Receiver<Event>,
+    interval: Duration,
+) -> Vec<(ipc::Command, Sender<Value>)> {
+    let first = match events.recv_timeout(interval) {
+        Ok(event) => event,
+        Err(RecvTimeoutError::Timeout) => return Vec::new(),
+        Err(RecvTimeoutError::Disconnected) => {
+            thread::sleep(interval);
+            return Vec::new();
+        }
+    };
+    // coalesce a burst of events (e.g. a title that keeps updating)
+    std::iter::once(first)
+        .chain(events.try_iter())
+        .filter_map(|event| match event {
+            Event::Focus => None,
+            Event::Command(command, reply) => Some((command, reply)),
+        })
+        .collect()
+}
+
+/// End the open session (if any) at `at`, or now when `at` is None.
+fn close_session(
+    db: &Database,
+    session_id: &mut Option<i64>,
+    at: Option<DateTime<Local>>,
+    logger: &mut DaemonLogger,
+) {
+    let Some(sid) = session_id.take() else {
+        return;
+    };
+    let result = match at {
+        Some(at) => db.end_session_at(sid, &format_timestamp(&at)),
+        None => db.end_session(sid),
+    };
+    if let Err(e) = result {
+        logger.log(&format!("warning: failed to end session: {}", e));
+    }
+}
+
+/// Serve the local HTTP API on its own thread with its own connection to
+/// the database. Failing to start is logged and tracking carries on.
+fn start_api(
+    config: &Config,
+    db_path: &Path,
+    rules: Arc<RwLock<Vec<Rule>>>,
+    running: Arc<AtomicBool>,
+    logger: &mut DaemonLogger,
+) {
+    let Some(token) = config.api.token.clone().filter(|t| !t.is_empty()) else {
+        logger.log("warning: api is enabled but has no token, run `laches config api on`");
+        return;
+    };
+    let listener = match api::bind(config.api.port) {
+        Ok(listener) => listener,
+        Err(e) => {
+            logger.log(&format!(
+                "warning: failed to start api on port {}: {}",
+                config.api.port, e
+            ));
+            return;
+        }
+    };
+    let db = match Database::open(db_path) {
+        Ok(db) => db,
+        Err(e) => {
+            logger.log(&format!("warning: failed to open database for api: {}", e));
+            return;
+        }
+    };
+
+    logger.log(&format!("api listening on 127.0.0.1:{}", config.api.port));
+    thread::spawn(move || api::serve(listener, &db, &token, &rules, &running));
+}
+
+/// Listen on the control socket and forward each command to the monitoring
+/// loop, relaying its reply. Returns the socket path so it can be removed on
+/// exit, or None if the socket could not be bound.
+#[cfg(unix)]
+fn start_ipc(
+    config_dir: &Path,
+    events: Sender<Event>,
+    logger: &mut DaemonLogger,
+) -> Option<PathBuf> {
+    let path = ipc::socket_path(config_dir);
+    let listener = match ipc::Listener::bind(&path) {
+        Ok(listener) => listener,
+        Err(e) => {
+            logger.log(&format!(
+                "warning: failed to open control socket {}: {}",
+                path.display(),
+                e
+            ));
+            return None;
+        }
+    };
+
+    logger.log(&format!("control socket at {}", path.display()));
+    thread::spawn(move || {
+        while let Ok(connection) = listener.accept() {
+            let (reply_tx, reply_rx) = mpsc::channel();
+            if events
+                .send(Event::Command(connection.command, reply_tx))
+                .is_err()
+            {
+                break;
+            }
+            let reply = reply_rx
+                .recv_timeout(ipc::REPLY_TIMEOUT)
+                .unwrap_or_else(|_| ipc::reply_error("daemon did not answer in time"));
+            connection.reply(&reply);
+        }
+    });
+    Some(path)
+}
+
+#[cfg(not(unix))]
+fn start_ipc(
+    _config_dir: &Path,
+    _events: Sender<Event>,
+    _logger: &mut DaemonLogger,
+) -> Option<PathBuf> {
+    None
+}
+
+/// Re-read config.toml and swap in the new settings. An invalid config is
+/// logged and rejected, and tracking carries on with the current one.
+fn reload_settings(
+    db: &Database,
+    config_dir: &Path,
+    settings: &mut Settings,
+    last_focus: &mut Option<FocusInfo>,
+    logger: &mut DaemonLogger,
+) -> Result<(), String> {
+    let config = load_config(config_dir).map_err(|e| {
+        logger.log(&format!("warning: ignoring invalid config: {}", e));
+        format!("invalid config: {}", e)
+    })?;
+    let mut reloaded = Settings::new(config);
+
+    // re-check the open window only if the new filter changes whether it
+    // is recorded, so a reload doesn't split the session
+    if let Some(info) = last_focus.as_ref() {
+        if settings.tracks(info) != reloaded.tracks(info) {
+            *last_focus = None;
+        }
+    }
+    if reloaded.config.daemon.backend != settings.config.daemon.backend
+        || reloaded.config.api != settings.config.api
+    {
+        logger.log("warning: tracker backend and api changes take effect after a restart");
+    }
+    if let Err(e) = db.set_rules(&reloaded.config.rules) {
+        logger.log(&format!("warning: failed to apply rules: {}", e));
+    }
+
+    if let Ok(mut rules) = settings.shared_rules.write() {
+        rules.clone_from(&reloaded.config.rules);
+    }
+    reloaded.shared_rules = Arc::clone(&settings.shared_rules);
+
+    *settings = reloaded;
+    logger.log(&format!(
+        "reloaded config (interval={}s, idle_timeout={}s, filter={}, rules={})",
+        settings.config.daemon.check_interval,
+        settings.config.daemon.idle_timeout,
+        settings.config.filtering.mode,
+        settings.config.rules.len(),
+    ));
+    Ok(())
+}
+
+/// How often usage is checked against `[[limits]]`.
+const LIMIT_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Notify about every limit that reached a new warning threshold or ran out.
+fn check_limits(
+    db: &Database,
+    settings: &Settings,
+    alerts: &mut LimitAlerts,
+    logger: &mut DaemonLogger,
+) {
+    let fired = match alerts.check(db, &settings.config.limits, Local::now()) {
+        Ok(fired) => fired,
+        Err(e) => {
+            logger.log(&format!("warning: failed to check limits: {}", e));
+            return;
+        }
+    };
+    for (usage, alert) in fired {
+        let (title, body) = usage.message(alert);
+        logger.log(&format!("limit: {} ({})", title, body));
+        notify(&title, &body, alert == Alert::Exceeded, logger);
+    }
+}
+
+/// Notify about distractions during a focus block, its end, and the end
+/// of the break after it. `focused` is the process being recorded as
+/// active, if any.
+fn check_focus(
+    db: &Database,
+    watch: &mut FocusWatch,
+    focused: Option<&str>,
+    logger: &mut DaemonLogger,
+) {
+    let events = match watch.update(db, focused, Local::now()) {
+        Ok(events) => events,
+        Err(e) => {
+            logger.log(&format!("warning: failed to check focus block: {}", e));
+            return;
+        }
+    };
+    for event in events {
+        let (title, body) = event.message();
+        logger.log(&format!("focus: {} ({})", title, body));
+        notify(&title, &body, false, logger);
+    }
+}
+
+fn notify(title: &str, body: &str, urgent: bool, logger: &mut DaemonLogger) {
+    if !platform::notify(title, body, urgent) {
+        logger.log("warning: failed to show notification");
+    }
+}
+
 /// Core monitoring loop. Extracted from main for testability.
 fn run_monitor(
     db: &Database,
-    config: &laches::config::Config,
-    filter: &CompiledFilter,
+    config_dir: &Path,
+    settings: &mut Settings,
     logger: &mut DaemonLogger,
     tracker: &dyn laches::platform::FocusTracker,
+    events: &Receiver<Event>,
     running: &AtomicBool,
 ) {
-    let check_interval = Duration::from_secs(config.daemon.check_interval);
-    let idle_timeout = Duration::from_secs(config.daemon.idle_timeout);
-
     let mut last_focus: Option<FocusInfo> = None;
-    let mut was_idle = false;
+    let mut last_kind: Option<SessionKind> = None;
     let mut current_session_id: Option<i64> = None;
+    let mut session_start = String::new();
+    let mut last_tick = Local::now();
+    let mut suspended_since: Option<DateTime<Local>> = None;
+    let mut paused = false;
+    let mut pause_until: Option<DateTime<Local>> = None;
+    let mut watcher = ConfigWatcher::new(config_path(config_dir));
+    let mut limit_alerts = LimitAlerts::default();
+    let mut last_limit_check: Option<Instant> = None;
+    let mut focus_watch = FocusWatch::default();
+    let mut goals_recorded: Option<NaiveDate> = None;
 
     while running.load(Ordering::SeqCst) {
-        let focused = tracker.get_focused_window();
-        let idle_duration = tracker.get_idle_duration();
-        let is_idle = idle_duration >= idle_timeout;
+        if watcher.changed() {
+            // errors are logged; the old settings stay in effect
+            let _ = reload_settings(db, config_dir, settings, &mut last_focus, logger);
+        }
 
-        let focus_changed = focused != last_focus;
-        let idle_changed = is_idle != was_idle;
+        let check_interval = Duration::from_secs(settings.config.daemon.check_interval);
+        let idle_timeout = Duration::from_secs(settings.config.daemon.idle_timeout);
+        let now = Local::now();
 
-        if focus_changed || idle_changed {
-            if let Some(sid) = current_session_id.take() {
-                if let Err(e) = db.end_session(sid) {
-                    logger.log(&format!("warning: failed to end session: {}", e));
+        let mut resumed_at = None;
+        for event in tracker.take_power_events() {
+            match event {
+                PowerEvent::Suspend(at) => {
+                    suspended_since.get_or_insert(at);
+                }
+                PowerEvent::Resume(at) => resumed_at = Some(at),
+            }
+        }
+        if resumed_at.is_some() || is_clock_jump(last_tick, now, check_interval) {
+            // without a suspend signal, the last poll is the best estimate
+            suspended_since.get_or_insert(last_tick);
+            resumed_at.get_or_insert(now);
+        }
+        last_tick = now;
+
+        // about to suspend; nothing to track until the resume
+        let mut asleep = false;
+        if let Some(since) = suspended_since {
+            close_session(db, &mut current_session_id, Some(since), logger);
+
+            if let Some(until) = resumed_at {
+                suspended_since = None;
+
+                let (start, end) = (
+                    format_timestamp(&since),
+                    format_timestamp(&until.max(since)),
+                );
+                match db.insert_session("suspended", SessionKind::Suspended, &start, &end) {
+                    Ok(_) => logger.log(&format!("resumed after suspend ({} -> {})", start, end)),
+                    Err(e) => logger.log(&format!("warning: failed to record suspend: {}", e)),
                 }
+
+                // start a fresh session for whatever is focused after resume
+                last_focus = None;
+                last_kind = None;
+            } else {
+                asleep = true;
             }
+        }
 
-            if is_idle {
-                match db.start_session("idle", None, None, true) {
-                    Ok(sid) => current_session_id = Some(sid),
-                    Err(e) => logger.log(&format!("warning: failed to start idle session: {}", e)),
+        if paused && !asleep {
+            if pause_until.is_some_and(|until| until <= now) {
+                close_session(db, &mut current_session_id, pause_until, logger);
+                paused = false;
+                pause_until = None;
+                logger.log("pause ended");
+            } else if current_session_id.is_none() {
+                // a new pause, or one that was cut by a suspend
+                match db.start_session(
+                    SessionKind::Paused.as_str(),
+                    None,
+                    None,
+                    SessionKind::Paused,
+                ) {
+                    Ok(sid) => {
+                        current_session_id = Some(sid);
+                        session_start = format_timestamp(&now);
+                    }
+                    Err(e) => {
+                        logger.log(&format!("warning: failed to start paused session: {}", e))
+                    }
                 }
-            } else if let Some(ref info) = focused {
-                if filter.should_track(&info.process_name) {
-                    match db.start_session(
+            }
+        }
+
+        if !asleep && !paused {
+            let focused = tracker.get_focused_window();
+            let kind = if tracker.is_locked() {
+                SessionKind::Locked
+            } else if tracker.get_idle_duration() >= idle_timeout {
+                SessionKind::Idle
+            } else {
+                SessionKind::Active
+            };
+
+            if focused != last_focus || Some(kind) != last_kind {
+                close_session(db, &mut current_session_id, None, logger);
+
+                let started = if kind != SessionKind::Active {
+                    db.start_session(kind.as_str(), None, None, kind)
+                        .map(Some)
+                        .map_err(|e| format!("failed to start {} session: {}", kind, e))
+                } else if let Some(info) = focused.as_ref().filter(|info| settings.tracks(info)) {
+                    db.start_session(
                         &info.process_name,
                         info.exe_path.as_deref(),
                         info.window_title.as_deref(),
-                        false,
-                    ) {
-                        Ok(sid) => current_session_id = Some(sid),
-                        Err(e) => logger.log(&format!("warning: failed to start session: {}", e)),
+                        SessionKind::Active,
+                    )
+                    .map(Some)
+                    .map_err(|e| format!("failed to start session: {}", e))
+                } else {
+                    Ok(None)
+                };
+                match started {
+                    Ok(sid) => {
+                        current_session_id = sid;
+                        session_start = format_timestamp(&Local::now());
                     }
+                    Err(e) => logger.log(&format!("warning: {}", e)),
                 }
+
+                last_focus = focused;
+                last_kind = Some(kind);
+            }
+        }
+
+        if !asleep {
+            let focused = last_focus
+                .as_ref()
+                .filter(|_| {
+                    !paused
+                        && current_session_id.is_some()
+                        && last_kind == Some(SessionKind::Active)
+                })
+                .map(|f| f.process_name.as_str());
+            check_focus(db, &mut focus_watch, focused, logger);
+        }
+
+        // yesterday's goal outcomes are final once the day changes
+        if !asleep && !settings.config.goals.is_empty() && goals_recorded != Some(now.date_naive())
+        {
+            if let Err(e) = goals::record(db, &settings.config.goals, now.date_naive(), false) {
+                logger.log(&format!("warning: failed to record goals: {}", e));
             }
+            goals_recorded = Some(now.date_naive());
+        }
 
-            last_focus = focused;
-            was_idle = is_idle;
+        if !asleep
+            && !settings.config.limits.is_empty()
+            && last_limit_check.is_none_or(|at| at.elapsed() >= LIMIT_CHECK_INTERVAL)
+        {
+            check_limits(db, settings, &mut limit_alerts, logger);
+            last_limit_check = Some(Instant::now());
         }
 
-        thread::sleep(check_interval);
+        for (command, reply) in wait_for_change(events, check_interval) {
+            let response = match command {
+                ipc::Command::Status => {
+                    let state = if paused {
+                        "paused"
+                    } else if current_session_id.is_none() {
+                        "untracked"
+                    } else {
+                        last_kind.map_or("untracked", SessionKind::as_str)
+                    };
+                    let focus = last_focus
+                        .as_ref()
+                        .filter(|_| state == SessionKind::Active.as_str());
+                    json!({
+                        "ok": true,
+                        "pid": std::process::id(),
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "backend": tracker.name(),
+                        "paused": paused,
+                        "until": pause_until.map(|t| format_timestamp(&t)),
+                        "state": state,
+                        "process": focus.map(|f| f.process_name.as_str()),
+                        "title": focus.and_then(|f| f.window_title.as_deref()),
+                        "since": current_session_id.map(|_| session_start.as_str()),
+                    })
+                }
+                ipc::Command::Pause(until) => {
+                    let until = until.map(|t| t.with_timezone(&Local));
+                    let changed = !paused || until != pause_until;
+                    if !paused {
+                        // the loop opens a paused session in its place
+                        close_session(db, &mut current_session_id, None, logger);
+                        last_focus = None;
+                        last_kind = None;
+                        paused = true;
+                    }
+                    pause_until = until;
+                    if changed {
+                        match until {
+                            Some(until) => {
+                                logger.log(&format!("paused until {}", format_timestamp(&until)))
+                            }
+                            None => logger.log("paused"),
+                        }
+                    }
+                    json!({ "ok": true, "changed": changed })
+                }
+                ipc::Command::Resume => {
+                    let changed = paused;
+                    if changed {
+                        close_session(db, &mut current_session_id, None, logger);
+                        paused = false;
+                        pause_until = None;
+                        logger.log("resumed");
+                    }
+                    json!({ "ok": true, "changed": changed })
+                }
+                ipc::Command::Reload => {
+                    // don't pick the same edit up again on the next poll
+                    watcher.changed();
+                    match reload_settings(db, config_dir, settings, &mut last_focus, logger) {
+                        Ok(()) => ipc::reply_ok(),
+                        Err(e) => ipc::reply_error(&e),
+                    }
+                }
+                ipc::Command::Shutdown => {
+                    logger.log("shutdown requested");
+                    running.store(false, Ordering::SeqCst);
+                    json!({ "ok": true, "pid": std::process::id() })
+                }
+            };
+            let _ = reply.send(response);
+        }
     }
 
     if let Some(sid) = current_session_id {
@@ -156,7 +672,7 @@ fn main() {
         std::process::exit(1);
     }
 
-    let (db, config, mut logger, _db_path) = init_daemon(config_dir);
+    let (db, config, mut logger, db_path) = init_daemon(config_dir);
 
     // close any sessions left open from a previous crash
     if let Ok(count) = db.close_all_open_sessions() {
@@ -176,34 +692,109 @@ fn main() {
     })
     .expect("error: failed to set signal handler");
 
-    let tracker = create_tracker();
-    let filter = CompiledFilter::new(
-        config.filtering.mode.clone(),
-        &config.filtering.whitelist,
-        &config.filtering.blacklist,
-    );
+    let tracker = create_tracker(config.daemon.backend);
+
+    // focus changes and control commands wake the loop early;
+    // `check_interval` polling still runs for idle detection and for
+    // backends without events
+    let (event_tx, event_rx) = mpsc::channel();
+    let focus_tx = event_tx.clone();
+    let subscribed = tracker.subscribe(Box::new(move || {
+        let _ = focus_tx.send(Event::Focus);
+    }));
+    let socket = start_ipc(config_dir, event_tx, &mut logger);
 
     logger.log(&format!(
-        "started (interval={}s, idle_timeout={}s, filter={})",
-        config.daemon.check_interval, config.daemon.idle_timeout, config.filtering.mode
+        "started (interval={}s, idle_timeout={}s, filter={}, rules={}, backend={}, idle={}, focus={})",
+        config.daemon.check_interval,
+        config.daemon.idle_timeout,
+        config.filtering.mode,
+        config.rules.len(),
+        tracker.name(),
+        tracker.idle_source(),
+        if subscribed { "events" } else { "polling" }
     ));
 
+    let mut settings = Settings::new(config);
+    if settings.config.api.enabled {
+        start_api(
+            &settings.config,
+            &db_path,
+            Arc::clone(&settings.shared_rules),
+            running.clone(),
+            &mut logger,
+        );
+    }
     run_monitor(
         &db,
-        &config,
-        &filter,
+        config_dir,
+        &mut settings,
         &mut logger,
         tracker.as_ref(),
+        &event_rx,
         &running,
     );
 
+    if let Some(socket) = socket {
+        let _ = std::fs::remove_file(socket);
+    }
     logger.log("stopped cleanly");
 }
 
 #[cfg(test)]
 mod tests {
+    use super::{is_clock_jump, ConfigWatcher};
+    use chrono::{Duration as ChronoDuration, Local};
     use laches::commands::filtering::CompiledFilter;
     use laches::config::{FilterMode, FilterPattern};
+    use std::time::Duration;
+
+    #[test]
+    fn test_clock_jump_detects_suspend() {
+        let interval = Duration::from_secs(2);
+        let last = Local::now();
+        assert!(!is_clock_jump(
+            last,
+            last + ChronoDuration::seconds(3),
+            interval
+        ));
+        assert!(!is_clock_jump(
+            last,
+            last + ChronoDuration::seconds(30),
+            interval
+        ));
+        assert!(is_clock_jump(
+            last,
+            last + ChronoDuration::hours(3),
+            interval
+        ));
+        // clock set backwards is not a suspend
+        assert!(!is_clock_jump(
+            last,
+            last - ChronoDuration::hours(1),
+            interval
+        ));
+    }
+
+    #[test]
+    fn test_config_watcher_sees_edits() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        let mut watcher = ConfigWatcher::new(path.clone());
+        assert!(!watcher.changed());
+
+        std::fs::write(&path, "a").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(std::time::SystemTime::UNIX_EPOCH)
+            .unwrap();
+        assert!(watcher.changed());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(!watcher.changed());
+    }
 
     #[test]
     fn test_should_track_default_mode() {
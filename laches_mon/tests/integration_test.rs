@@ -1,6 +1,6 @@
 use laches::{
-    config::{get_machine_id, load_or_create_config, machine_db_path},
-    db::Database,
+    config::{get_machine_id, load_or_create_config, machine_db_path, TrackerBackend},
+    db::{Database, SessionKind},
     platform::create_tracker,
 };
 use tempfile::TempDir;
@@ -16,11 +16,12 @@ fn test_config_loads_in_temp_dir() {
 #[test]
 fn test_database_opens_in_data_dir() {
     let temp_dir = TempDir::new().unwrap();
+    let config = load_or_create_config(temp_dir.path()).unwrap();
     let machine_id = get_machine_id(temp_dir.path());
-    let data_dir = laches::config::data_dir(temp_dir.path());
+    let data_dir = laches::config::data_dir(temp_dir.path(), &config);
     std::fs::create_dir_all(&data_dir).unwrap();
 
-    let db_path = machine_db_path(temp_dir.path(), &machine_id);
+    let db_path = machine_db_path(temp_dir.path(), &config, &machine_id);
     let db = Database::open(&db_path).unwrap();
 
     let sid = db
@@ -28,7 +29,7 @@ fn test_database_opens_in_data_dir() {
             "test_process",
             Some("/usr/bin/test"),
             Some("Test Window"),
-            false,
+            SessionKind::Active,
         )
         .unwrap();
     assert!(sid > 0);
@@ -40,7 +41,7 @@ fn test_database_opens_in_data_dir() {
 
 #[test]
 fn test_focus_tracker_does_not_panic() {
-    let tracker = create_tracker();
+    let tracker = create_tracker(TrackerBackend::Auto);
     // should not panic on any platform, even without a desktop session
     let _ = tracker.get_focused_window();
     let _ = tracker.get_idle_duration();
@@ -49,21 +50,22 @@ fn test_focus_tracker_does_not_panic() {
 #[test]
 fn test_session_lifecycle() {
     let temp_dir = TempDir::new().unwrap();
+    let config = load_or_create_config(temp_dir.path()).unwrap();
     let machine_id = get_machine_id(temp_dir.path());
-    let data_dir = laches::config::data_dir(temp_dir.path());
+    let data_dir = laches::config::data_dir(temp_dir.path(), &config);
     std::fs::create_dir_all(&data_dir).unwrap();
 
-    let db_path = machine_db_path(temp_dir.path(), &machine_id);
+    let db_path = machine_db_path(temp_dir.path(), &config, &machine_id);
     let db = Database::open(&db_path).unwrap();
 
     // simulate daemon: start session, end it, start another
     let s1 = db
-        .start_session("firefox", None, Some("GitHub"), false)
+        .start_session("firefox", None, Some("GitHub"), SessionKind::Active)
         .unwrap();
     db.end_session(s1).unwrap();
 
     let s2 = db
-        .start_session("code", None, Some("main.rs"), false)
+        .start_session("code", None, Some("main.rs"), SessionKind::Active)
         .unwrap();
     db.end_session(s2).unwrap();
 
@@ -76,16 +78,19 @@ fn test_session_lifecycle() {
 #[test]
 fn test_stale_session_cleanup() {
     let temp_dir = TempDir::new().unwrap();
+    let config = load_or_create_config(temp_dir.path()).unwrap();
     let machine_id = get_machine_id(temp_dir.path());
-    let data_dir = laches::config::data_dir(temp_dir.path());
+    let data_dir = laches::config::data_dir(temp_dir.path(), &config);
     std::fs::create_dir_all(&data_dir).unwrap();
 
-    let db_path = machine_db_path(temp_dir.path(), &machine_id);
+    let db_path = machine_db_path(temp_dir.path(), &config, &machine_id);
     let db = Database::open(&db_path).unwrap();
 
     // simulate a crash: session left open
-    db.start_session("firefox", None, None, false).unwrap();
-    db.start_session("code", None, None, false).unwrap();
+    db.start_session("firefox", None, None, SessionKind::Active)
+        .unwrap();
+    db.start_session("code", None, None, SessionKind::Active)
+        .unwrap();
 
     let open = db.get_open_session().unwrap();
     assert!(open.is_some());
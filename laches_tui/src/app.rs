@@ -1,7 +1,25 @@
-use laches::db::{date_range_for_day, last_n_days_range, Database, ProcessSummary, Session};
+use laches::category::{build_tree, flatten, CategoryNode};
+use laches::config::Goal;
+use laches::db::{
+    date_range_for_day, last_n_days_range, Database, ProcessSummary, Session, SessionKind,
+};
+use laches::goals::GoalProgress;
+use laches::platform::FocusTracker;
+use laches::titles::{TitleGroup, TitleNormalizer};
 use std::path::PathBuf;
+use std::time::Duration;
 
-const TAB_COUNT: usize = 4;
+const TAB_COUNT: usize = 6;
+
+/// Days of goal history kept for the goals tab.
+const GOAL_HISTORY_DAYS: u32 = 28;
+
+/// Days up to the viewed one covered by the heatmap tab.
+pub const HEATMAP_DAYS: i64 = 28;
+
+/// How long a refresh waits for the daemon's status before falling back
+/// to the pid file, so a stuck daemon can't freeze the ui.
+const STATUS_TIMEOUT: Duration = Duration::from_millis(250);
 
 pub struct Insights {
     pub yesterday_secs: i64,
@@ -9,7 +27,6 @@ pub struct Insights {
     pub last_week_secs: i64,
     pub avg_7d: i64,
     pub avg_30d: i64,
-    pub streak: u32,
     pub top_week_process: Option<String>,
     pub top_week_secs: i64,
 }
@@ -20,34 +37,79 @@ pub struct TagGroup {
     pub processes: Vec<String>,
 }
 
+/// One line of the category tree: a category or a process filed under it.
+pub struct CategoryRow {
+    pub depth: usize,
+    pub label: String,
+    pub total_seconds: i64,
+    pub is_process: bool,
+}
+
 pub struct App<'a> {
     pub db: &'a Database,
     pub config_dir: PathBuf,
+    tracker: Box<dyn FocusTracker>,
+    goal_defs: Vec<Goal>,
+    normalizer: TitleNormalizer,
+    /// Tags `[[rules]]` can attach, grouped alongside explicit ones.
+    rule_tags: Vec<String>,
     pub tab: usize,
     pub viewing_date: chrono::NaiveDate,
     pub earliest_date: Option<chrono::NaiveDate>,
 
+    /// Scroll position per tab. In the today view's process list it is
+    /// the selected row.
     pub scroll_offsets: [usize; TAB_COUNT],
 
     pub summaries: Vec<ProcessSummary>,
     pub sessions: Vec<Session>,
     pub active_secs: i64,
     pub idle_secs: i64,
+    pub paused_secs: i64,
     pub insights: Insights,
+    /// Progress of each configured goal on the viewed day.
+    pub goals: Vec<GoalProgress>,
+    /// Active seconds per weekday (monday first) and hour over the
+    /// [`HEATMAP_DAYS`] up to the viewed day.
+    pub heatmap: [[i64; 24]; 7],
     pub current_process: Option<String>,
     pub current_window_title: Option<String>,
     pub daemon_running: bool,
+    pub daemon_paused: bool,
+    /// Local `HH:MM` a timed pause ends at.
+    pub paused_until: Option<String>,
+    /// The running focus block's remaining seconds and score so far.
+    pub focus: Option<(i64, Option<i64>)>,
     pub show_help: bool,
     pub group_by_tag: bool,
     pub tag_groups: Vec<TagGroup>,
+    pub group_by_category: bool,
+    pub category_tree: Vec<CategoryNode>,
+    /// Process whose window titles the today view is drilled into.
+    pub drill: Option<String>,
+    /// The drilled-into process's time on the viewed day by title.
+    pub title_groups: Vec<TitleGroup>,
+    /// Selected row of the title list, kept in view like the process list's.
+    pub drill_scroll: usize,
     pub last_error: Option<String>,
 }
 
 impl<'a> App<'a> {
-    pub fn new(db: &'a Database, config_dir: PathBuf) -> Self {
+    pub fn new(
+        db: &'a Database,
+        config_dir: PathBuf,
+        tracker: Box<dyn FocusTracker>,
+        goal_defs: Vec<Goal>,
+        normalizer: TitleNormalizer,
+        rule_tags: Vec<String>,
+    ) -> Self {
         Self {
             db,
             config_dir,
+            tracker,
+            goal_defs,
+            normalizer,
+            rule_tags,
             tab: 0,
             viewing_date: chrono::Local::now().date_naive(),
             earliest_date: None,
@@ -56,22 +118,32 @@ impl<'a> App<'a> {
             sessions: Vec::new(),
             active_secs: 0,
             idle_secs: 0,
+            paused_secs: 0,
             insights: Insights {
                 yesterday_secs: 0,
                 week_secs: 0,
                 last_week_secs: 0,
                 avg_7d: 0,
                 avg_30d: 0,
-                streak: 0,
                 top_week_process: None,
                 top_week_secs: 0,
             },
+            goals: Vec::new(),
+            heatmap: [[0; 24]; 7],
             current_process: None,
             current_window_title: None,
             daemon_running: false,
+            daemon_paused: false,
+            paused_until: None,
+            focus: None,
             show_help: false,
             group_by_tag: false,
             tag_groups: Vec::new(),
+            group_by_category: false,
+            category_tree: Vec::new(),
+            drill: None,
+            title_groups: Vec::new(),
+            drill_scroll: 0,
             last_error: None,
         }
     }
@@ -128,17 +200,77 @@ impl<'a> App<'a> {
 
     pub fn toggle_group_by_tag(&mut self) {
         self.group_by_tag = !self.group_by_tag;
+        self.group_by_category = false;
         self.scroll_offsets[0] = 0;
-        self.rebuild_tag_groups();
+        self.close_drill();
+        self.refresh_data();
+    }
+
+    pub fn toggle_group_by_category(&mut self) {
+        self.group_by_category = !self.group_by_category;
+        self.group_by_tag = false;
+        self.scroll_offsets[0] = 0;
+        self.close_drill();
+    }
+
+    /// Show the window titles of the process selected in the today view.
+    pub fn open_drill(&mut self) {
+        if self.tab != 0 || self.group_by_tag || self.group_by_category || self.drill.is_some() {
+            return;
+        }
+        let Some(selected) = self.summaries.get(self.scroll_offsets[0]) else {
+            return;
+        };
+        self.drill = Some(selected.process_name.clone());
+        self.drill_scroll = 0;
+        self.refresh_data();
+    }
+
+    /// Go back from the window titles to the process list. Returns false
+    /// if there was nothing to close.
+    pub fn close_drill(&mut self) -> bool {
+        self.title_groups.clear();
+        self.drill.take().is_some()
+    }
+
+    /// The category tree flattened for display, each category followed by
+    /// its subcategories and then the processes filed directly under it.
+    pub fn category_rows(&self) -> Vec<CategoryRow> {
+        let mut rows = Vec::new();
+        for (depth, node) in flatten(&self.category_tree) {
+            rows.push(CategoryRow {
+                depth,
+                label: node.name.clone(),
+                total_seconds: node.total_seconds,
+                is_process: false,
+            });
+            rows.extend(node.processes.iter().map(|(process, secs)| CategoryRow {
+                depth: depth + 1,
+                label: process.clone(),
+                total_seconds: *secs,
+                is_process: true,
+            }));
+        }
+        rows
     }
 
     pub fn scroll_up(&mut self) {
+        if self.tab == 0 && self.drill.is_some() {
+            self.drill_scroll = self.drill_scroll.saturating_sub(1);
+            return;
+        }
         self.scroll_offsets[self.tab] = self.scroll_offsets[self.tab].saturating_sub(1);
     }
 
     pub fn scroll_down(&mut self) {
+        if self.tab == 0 && self.drill.is_some() {
+            if self.drill_scroll + 1 < self.title_groups.len() {
+                self.drill_scroll += 1;
+            }
+            return;
+        }
         let max = self.scrollable_item_count(self.tab);
-        if self.scroll_offsets[self.tab] < max {
+        if self.scroll_offsets[self.tab] + 1 < max {
             self.scroll_offsets[self.tab] += 1;
         }
     }
@@ -148,11 +280,14 @@ impl<'a> App<'a> {
             0 => {
                 if self.group_by_tag {
                     self.tag_groups.len()
+                } else if self.group_by_category {
+                    self.category_rows().len()
                 } else {
                     self.summaries.len()
                 }
             }
             3 => self.sessions.iter().filter(|s| !s.idle).count(),
+            4 => self.goals.len(),
             _ => 0,
         }
     }
@@ -177,6 +312,14 @@ impl<'a> App<'a> {
             }
         }
 
+        self.rebuild_tag_groups(&day_start, &day_end);
+        self.category_tree = build_tree(
+            &self
+                .db
+                .query_category_totals(&day_start, &day_end)
+                .unwrap_or_default(),
+        );
+
         match self.db.query_sessions(&day_start, &day_end) {
             Ok(v) => self.sessions = v,
             Err(e) => {
@@ -185,6 +328,19 @@ impl<'a> App<'a> {
             }
         }
 
+        if let Some(process) = &self.drill {
+            match self
+                .db
+                .query_title_summaries(&day_start, &day_end, Some(process))
+            {
+                Ok(v) => self.title_groups = laches::titles::group(&v, Some(&self.normalizer)),
+                Err(e) => {
+                    self.last_error = Some(format!("query failed: {}", e));
+                    return;
+                }
+            }
+        }
+
         self.active_secs = self
             .db
             .query_total_active_seconds(&day_start, &day_end)
@@ -195,6 +351,11 @@ impl<'a> App<'a> {
             .query_total_idle_seconds(&day_start, &day_end)
             .unwrap_or(0);
 
+        self.paused_secs = self
+            .db
+            .query_total_kind_seconds(&day_start, &day_end, SessionKind::Paused)
+            .unwrap_or(0);
+
         let today = chrono::Local::now().date_naive();
         let start_day = today - chrono::Duration::days(29);
         let (range_start, _) =
@@ -213,6 +374,22 @@ impl<'a> App<'a> {
 
         self.compute_insights(&db_totals);
 
+        let heatmap_start = self.viewing_date - chrono::Duration::days(HEATMAP_DAYS - 1);
+        let (heatmap_start, _) =
+            date_range_for_day(&heatmap_start.format("%Y-%m-%d").to_string()).unwrap_or_default();
+        match self.db.query_weekday_hour_totals(&heatmap_start, &day_end) {
+            Ok(v) => self.heatmap = v,
+            Err(e) => {
+                self.last_error = Some(format!("query failed: {}", e));
+                return;
+            }
+        }
+
+        if let Err(e) = self.refresh_goals(today) {
+            self.last_error = Some(format!("query failed: {}", e));
+            return;
+        }
+
         if self.is_viewing_today() {
             let open = self
                 .db
@@ -222,8 +399,20 @@ impl<'a> App<'a> {
                 .filter(|s| !s.idle);
             self.current_process = open.map(|s| s.process_name);
 
-            let tracker = laches::platform::create_tracker();
-            if let Some(info) = tracker.get_focused_window() {
+            let now = chrono::Local::now();
+            self.focus = self
+                .db
+                .current_focus_block(&laches::db::format_timestamp(&now))
+                .ok()
+                .flatten()
+                .map(|block| {
+                    let score = laches::focus::score(self.db, &block, now)
+                        .ok()
+                        .and_then(|s| s.percent());
+                    (laches::focus::secs_until(block.end(), now), score)
+                });
+
+            if let Some(info) = self.tracker.get_focused_window() {
                 self.current_window_title = info.window_title;
             } else {
                 self.current_window_title = None;
@@ -231,9 +420,28 @@ impl<'a> App<'a> {
         } else {
             self.current_process = None;
             self.current_window_title = None;
+            self.focus = None;
         }
 
-        self.daemon_running = laches::process::is_daemon_running(&self.config_dir);
+        // the control socket knows about pauses; the pid file is the
+        // fallback for a daemon that can't be reached
+        let socket = laches::ipc::socket_path(&self.config_dir);
+        match laches::ipc::request_with_timeout(
+            &socket,
+            laches::ipc::Command::Status,
+            STATUS_TIMEOUT,
+        ) {
+            Ok(reply) => {
+                self.daemon_running = true;
+                self.daemon_paused = reply["paused"].as_bool().unwrap_or(false);
+                self.paused_until = reply["until"].as_str().and_then(laches::utils::local_hm);
+            }
+            Err(_) => {
+                self.daemon_running = laches::process::is_daemon_running(&self.config_dir);
+                self.daemon_paused = false;
+                self.paused_until = None;
+            }
+        }
 
         if self.earliest_date.is_none() {
             self.earliest_date = self
@@ -243,8 +451,20 @@ impl<'a> App<'a> {
                 .flatten()
                 .and_then(|s| chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d").ok());
         }
+    }
 
-        self.rebuild_tag_groups();
+    /// Work out each goal's progress on the viewed day. Outcomes of
+    /// finished days are recorded by the daemon and `laches goals`; the tui
+    /// only reads them.
+    fn refresh_goals(&mut self, today: chrono::NaiveDate) -> rusqlite::Result<()> {
+        self.goals = self
+            .goal_defs
+            .iter()
+            .map(|goal| {
+                laches::goals::progress(self.db, goal, self.viewing_date, today, GOAL_HISTORY_DAYS)
+            })
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(())
     }
 
     fn compute_insights(&mut self, daily_map: &std::collections::HashMap<String, i64>) {
@@ -273,26 +493,6 @@ impl<'a> App<'a> {
             .unwrap_or(0);
         self.insights.avg_30d = month_total / 30;
 
-        let mut streak: u32 = 0;
-        let mut check = vd;
-        loop {
-            let key = check.format("%Y-%m-%d").to_string();
-            let has_data = daily_map.get(&key).copied().unwrap_or_else(|| {
-                let (s, e) = date_range_for_day(&key).unwrap_or_default();
-                self.db.query_total_active_seconds(&s, &e).unwrap_or(0)
-            });
-            if has_data > 0 {
-                streak += 1;
-            } else {
-                break;
-            }
-            match check.pred_opt() {
-                Some(d) => check = d,
-                None => break,
-            }
-        }
-        self.insights.streak = streak;
-
         let week_summaries = self
             .db
             .query_process_summaries(&w7s, &w7e, None)
@@ -306,60 +506,55 @@ impl<'a> App<'a> {
         }
     }
 
-    fn rebuild_tag_groups(&mut self) {
+    /// Group the viewed day's time by explicit and rule-derived tags. Each
+    /// group counts the sessions carrying its tag once, so a process is
+    /// only credited with the time a title rule actually tagged.
+    fn rebuild_tag_groups(&mut self, day_start: &str, day_end: &str) {
         self.tag_groups.clear();
         if !self.group_by_tag {
             return;
         }
 
-        let all_tags = self.db.get_all_tags().unwrap_or_default();
-
-        let mut tag_map: std::collections::HashMap<String, (i64, Vec<String>)> =
-            std::collections::HashMap::new();
+        let mut tags: std::collections::BTreeSet<String> = self
+            .db
+            .get_all_tags()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(_, tag)| tag)
+            .collect();
+        tags.extend(self.rule_tags.iter().cloned());
 
         let mut tagged_processes: std::collections::HashSet<String> =
             std::collections::HashSet::new();
-
-        for (process_name, tag) in &all_tags {
-            tagged_processes.insert(process_name.clone());
-            if let Some(s) = self
-                .summaries
-                .iter()
-                .find(|s| &s.process_name == process_name)
-            {
-                let entry = tag_map.entry(tag.clone()).or_insert((0, Vec::new()));
-                entry.0 += s.total_seconds;
-                if !entry.1.contains(&s.process_name) {
-                    entry.1.push(s.process_name.clone());
-                }
+        let mut groups: Vec<TagGroup> = Vec::new();
+        for tag in tags {
+            let summaries = self
+                .db
+                .query_process_summaries(day_start, day_end, Some(&tag))
+                .unwrap_or_default();
+            if summaries.is_empty() {
+                continue;
             }
+            tagged_processes.extend(summaries.iter().map(|s| s.process_name.clone()));
+            groups.push(TagGroup {
+                tag,
+                total_seconds: summaries.iter().map(|s| s.total_seconds).sum(),
+                processes: summaries.into_iter().map(|s| s.process_name).collect(),
+            });
         }
 
-        let mut untagged_secs: i64 = 0;
-        let mut untagged_procs: Vec<String> = Vec::new();
-        for s in &self.summaries {
-            if !tagged_processes.contains(&s.process_name) {
-                untagged_secs += s.total_seconds;
-                untagged_procs.push(s.process_name.clone());
-            }
-        }
+        groups.sort_by_key(|g| std::cmp::Reverse(g.total_seconds));
 
-        let mut groups: Vec<TagGroup> = tag_map
-            .into_iter()
-            .map(|(tag, (total, procs))| TagGroup {
-                tag,
-                total_seconds: total,
-                processes: procs,
-            })
+        let untagged: Vec<&ProcessSummary> = self
+            .summaries
+            .iter()
+            .filter(|s| !tagged_processes.contains(&s.process_name))
             .collect();
-
-        groups.sort_by(|a, b| b.total_seconds.cmp(&a.total_seconds));
-
-        if !untagged_procs.is_empty() {
+        if !untagged.is_empty() {
             groups.push(TagGroup {
                 tag: "untagged".to_string(),
-                total_seconds: untagged_secs,
-                processes: untagged_procs,
+                total_seconds: untagged.iter().map(|s| s.total_seconds).sum(),
+                processes: untagged.iter().map(|s| s.process_name.clone()).collect(),
             });
         }
 
@@ -9,6 +9,7 @@ use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use laches::config::RuleAction;
 use ratatui::prelude::*;
 use std::{io, time::Duration};
 use theme::Theme;
@@ -22,13 +23,21 @@ fn main() -> io::Result<()> {
         }
     };
 
+    let config = match laches::config::load_or_create_config(&config_dir) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("error: failed to load config: {}", e);
+            std::process::exit(1);
+        }
+    };
+
     let machine_id = laches::config::get_machine_id(&config_dir);
-    let data_dir = laches::config::data_dir(&config_dir);
+    let data_dir = laches::config::data_dir(&config_dir, &config);
     if !data_dir.exists() {
         std::fs::create_dir_all(&data_dir).ok();
     }
 
-    let db_path = laches::config::machine_db_path(&config_dir, &machine_id);
+    let db_path = laches::config::machine_db_path(&config_dir, &config, &machine_id);
     let db = match laches::db::Database::open(&db_path) {
         Ok(d) => d,
         Err(e) => {
@@ -36,6 +45,17 @@ fn main() -> io::Result<()> {
             std::process::exit(1);
         }
     };
+    if let Err(e) = db.set_rules(&config.rules) {
+        eprintln!("warning: failed to load rules: {}", e);
+    }
+
+    let normalizer = match laches::titles::TitleNormalizer::new(&config.title_extractors) {
+        Ok(n) => n,
+        Err(e) => {
+            eprintln!("error: failed to load title extractors: {}", e);
+            std::process::exit(1);
+        }
+    };
 
     // set up terminal
     enable_raw_mode()?;
@@ -53,7 +73,21 @@ fn main() -> io::Result<()> {
     }));
 
     let theme = Theme::default();
-    let mut app = App::new(&db, config_dir);
+    let tracker = laches::platform::create_tracker(config.daemon.backend);
+    let rule_tags = config
+        .rules
+        .iter()
+        .filter(|r| r.action == RuleAction::Tag)
+        .filter_map(|r| r.tag.clone())
+        .collect();
+    let mut app = App::new(
+        &db,
+        config_dir,
+        tracker,
+        config.goals.clone(),
+        normalizer,
+        rule_tags,
+    );
     let result = run(&mut terminal, &mut app, &theme);
 
     // restore terminal
@@ -88,12 +122,15 @@ fn run(
                     }
                 } else {
                     match key.code {
+                        KeyCode::Esc | KeyCode::Backspace if app.close_drill() => {}
                         KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
                         KeyCode::Char('?') => app.toggle_help(),
                         KeyCode::Char('1') => app.set_tab(0),
                         KeyCode::Char('2') => app.set_tab(1),
                         KeyCode::Char('3') => app.set_tab(2),
                         KeyCode::Char('4') => app.set_tab(3),
+                        KeyCode::Char('5') => app.set_tab(4),
+                        KeyCode::Char('6') => app.set_tab(5),
                         KeyCode::Tab => app.next_tab(),
                         KeyCode::BackTab => app.prev_tab(),
                         KeyCode::Left | KeyCode::Char('h') => app.prev_day(),
@@ -101,7 +138,9 @@ fn run(
                         KeyCode::Up | KeyCode::Char('k') => app.scroll_up(),
                         KeyCode::Down | KeyCode::Char('j') => app.scroll_down(),
                         KeyCode::Char('g') => app.toggle_group_by_tag(),
+                        KeyCode::Char('c') => app.toggle_group_by_category(),
                         KeyCode::Char('r') => app.refresh_data(),
+                        KeyCode::Enter => app.open_drill(),
                         _ => {}
                     }
                 }
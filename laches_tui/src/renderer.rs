@@ -6,7 +6,9 @@ use ratatui::{
     widgets::{Block, Borders, Clear, Padding, Paragraph, Tabs},
 };
 
-const TAB_TITLES: [&str; 4] = ["today", "timeline", "insights", "sessions"];
+const TAB_TITLES: [&str; 6] = [
+    "today", "timeline", "insights", "sessions", "goals", "heatmap",
+];
 
 pub fn render(app: &App, frame: &mut Frame, theme: &Theme) {
     let chunks = Layout::default()
@@ -37,6 +39,8 @@ pub fn render(app: &App, frame: &mut Frame, theme: &Theme) {
         1 => views::timeline::render(app, frame, chunks[1], theme),
         2 => views::insights::render(app, frame, chunks[1], theme),
         3 => views::sessions::render(app, frame, chunks[1], theme),
+        4 => views::goals::render(app, frame, chunks[1], theme),
+        5 => views::heatmap::render(app, frame, chunks[1], theme),
         _ => {}
     }
 
@@ -84,13 +88,16 @@ fn render_help(frame: &mut Frame, theme: &Theme) {
 
     let bindings = [
         ("q / Esc", "quit"),
-        ("1..4", "jump to tab"),
+        ("1..6", "jump to tab"),
         ("Tab / Shift+Tab", "next / previous tab"),
         ("h / Left", "previous day"),
         ("l / Right", "next day"),
         ("j / Down", "scroll down"),
         ("k / Up", "scroll up"),
+        ("Enter", "window titles of process"),
+        ("Esc / Backspace", "back from window titles"),
         ("g", "group by tag"),
+        ("c", "group by category"),
         ("r", "refresh data"),
         ("?", "toggle this help"),
     ];
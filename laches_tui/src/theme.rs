@@ -78,6 +78,10 @@ impl Theme {
         Style::default().fg(self.success).bold()
     }
 
+    pub fn selected_row(&self) -> Style {
+        Style::default().fg(self.accent).bold()
+    }
+
     pub fn empty_text(&self) -> Style {
         Style::default().fg(self.muted)
     }
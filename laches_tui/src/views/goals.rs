@@ -0,0 +1,115 @@
+use crate::app::App;
+use crate::theme::Theme;
+use laches::goals::GoalProgress;
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph},
+};
+
+/// Lines each goal takes up, including the gap after it.
+const GOAL_HEIGHT: usize = 5;
+
+pub fn render(app: &App, frame: &mut Frame, area: Rect, theme: &Theme) {
+    let block = Block::default().borders(Borders::ALL).title(" goals ");
+
+    if app.goals.is_empty() {
+        let lines = vec![
+            Line::from(""),
+            Line::from(Span::styled("  No goals set.", theme.empty_text())),
+            Line::from(vec![
+                Span::styled("  Add one with ", theme.empty_text()),
+                Span::styled(
+                    "laches goals add --at-least 4h --tag dev",
+                    theme.header_tracking(),
+                ),
+            ]),
+        ];
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+        return;
+    }
+
+    let inner_height = area.height.saturating_sub(2) as usize;
+    let inner_width = area.width.saturating_sub(2) as usize;
+    if inner_height == 0 || inner_width == 0 {
+        return;
+    }
+    let visible = (inner_height / GOAL_HEIGHT).max(1);
+    let scroll = app.scroll_offsets[4].min(app.goals.len().saturating_sub(visible));
+    let day_label = if app.is_viewing_today() {
+        "today"
+    } else {
+        "this day"
+    };
+
+    let mut lines: Vec<Line> = Vec::new();
+    for goal in app.goals.iter().skip(scroll).take(visible) {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", goal.goal),
+            theme.key_hint(),
+        )));
+        lines.push(progress_line(goal, day_label, inner_width, theme));
+        let days = |n: u32| format!("{} day{}", n, if n == 1 { "" } else { "s" });
+        lines.push(Line::from(vec![
+            Span::styled("    streak ", theme.key_desc()),
+            Span::styled(days(goal.streak), Style::default().fg(theme.accent)),
+            Span::styled(
+                format!("   best {}", days(goal.best_streak)),
+                theme.key_desc(),
+            ),
+        ]));
+        lines.push(history_line(goal, inner_width, theme));
+        lines.push(Line::from(""));
+    }
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn progress_line(
+    goal: &GoalProgress,
+    day_label: &str,
+    width: usize,
+    theme: &Theme,
+) -> Line<'static> {
+    if !goal.applies {
+        return Line::from(Span::styled(
+            format!("    {}: not a goal day", day_label),
+            theme.key_desc(),
+        ));
+    }
+
+    let bar_width = 20.min(width.saturating_sub(8));
+    let ratio = goal.secs as f64 / goal.required_secs.max(1) as f64;
+    let filled = ((ratio.min(1.0)) * bar_width as f64) as usize;
+    let color = match (goal.goal.is_minimum(), goal.goal.passes(goal.secs)) {
+        (true, true) | (false, true) => theme.success,
+        (true, false) => theme.accent,
+        (false, false) => theme.error,
+    };
+    Line::from(vec![
+        Span::raw("    "),
+        Span::styled("\u{2588}".repeat(filled), Style::default().fg(color)),
+        Span::styled(
+            "\u{2591}".repeat(bar_width - filled),
+            Style::default().fg(theme.bar_empty),
+        ),
+        Span::styled(
+            format!("  {}: {}", day_label, goal.describe()),
+            theme.key_desc(),
+        ),
+    ])
+}
+
+/// One square per earlier day, most recent on the right, as many as fit.
+fn history_line(goal: &GoalProgress, width: usize, theme: &Theme) -> Line<'static> {
+    let fit = width.saturating_sub(6);
+    let skip = goal.history.len().saturating_sub(fit);
+    let mut spans = vec![Span::raw("    ")];
+    for (_, passed) in goal.history.iter().skip(skip) {
+        spans.push(match passed {
+            Some(true) => Span::styled("\u{25a0}", Style::default().fg(theme.success)),
+            Some(false) => Span::styled("\u{25a0}", Style::default().fg(theme.error)),
+            None => Span::styled("\u{00b7}", Style::default().fg(theme.muted)),
+        });
+    }
+    Line::from(spans)
+}
@@ -0,0 +1,103 @@
+use crate::app::{App, HEATMAP_DAYS};
+use crate::theme::Theme;
+use chrono::Datelike;
+use laches::utils::{format_duration_hm, heat_char};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph},
+};
+
+const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// Columns taken by the weekday label and the row total.
+const LABEL_WIDTH: usize = 7;
+const TOTAL_WIDTH: usize = 10;
+
+pub fn render(app: &App, frame: &mut Frame, area: Rect, theme: &Theme) {
+    let max = app.heatmap.iter().flatten().copied().max().unwrap_or(0);
+    if max == 0 {
+        super::render_empty(app, frame, area, theme, "heatmap");
+        return;
+    }
+
+    let title = format!(
+        " heatmap \u{2500} {} days to {} ",
+        HEATMAP_DAYS,
+        app.viewing_date.format("%b %d")
+    );
+    let block = Block::default().borders(Borders::ALL).title(title);
+    let inner_width = area.width.saturating_sub(2) as usize;
+    let inner_height = area.height.saturating_sub(2) as usize;
+    if inner_width == 0 || inner_height == 0 {
+        return;
+    }
+
+    // widen the cells to fill the space, and space the rows out when
+    // there is room for it
+    let cell = (inner_width.saturating_sub(LABEL_WIDTH + TOTAL_WIDTH) / 24).clamp(1, 4);
+    let spaced = inner_height >= 7 * 2 + 5;
+
+    let mut lines: Vec<Line> = vec![Line::from("")];
+    // label every third hour, which fits even at one column per hour
+    let mut header = " ".repeat(LABEL_WIDTH);
+    for hour in (0..24).step_by(3) {
+        header.push_str(&format!(
+            "{:<width$}",
+            format!("{:02}", hour),
+            width = cell * 3
+        ));
+    }
+    lines.push(Line::from(Span::styled(header, theme.key_desc())));
+    if spaced {
+        lines.push(Line::from(""));
+    }
+
+    let viewed_weekday = app.viewing_date.weekday().num_days_from_monday() as usize;
+    for (day, row) in app.heatmap.iter().enumerate() {
+        let label_style = if day == viewed_weekday {
+            theme.key_hint().fg(theme.accent)
+        } else {
+            theme.key_hint()
+        };
+        let mut spans = vec![Span::styled(
+            format!("  {:<width$}", WEEKDAYS[day], width = LABEL_WIDTH - 2),
+            label_style,
+        )];
+        for secs in row {
+            let cell_text: String = std::iter::repeat_n(heat_char(*secs, max), cell).collect();
+            let color = if *secs > 0 { theme.accent } else { theme.muted };
+            spans.push(Span::styled(cell_text, Style::default().fg(color)));
+        }
+        spans.push(Span::styled(
+            format!(
+                "{:>width$}",
+                format_duration_hm(row.iter().sum()),
+                width = TOTAL_WIDTH
+            ),
+            theme.key_desc(),
+        ));
+        lines.push(Line::from(spans));
+        if spaced {
+            lines.push(Line::from(""));
+        }
+    }
+
+    let (busiest_day, busiest_hour) = (0..7)
+        .flat_map(|d| (0..24).map(move |h| (d, h)))
+        .max_by_key(|&(d, h)| (app.heatmap[d][h], std::cmp::Reverse((d, h))))
+        .unwrap_or((0, 0));
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("  busiest hour  ", theme.key_hint()),
+        Span::styled(
+            format!("{} {:02}:00", WEEKDAYS[busiest_day], busiest_hour),
+            Style::default().fg(theme.accent),
+        ),
+        Span::styled(
+            format!("  {} in total", format_duration_hm(max)),
+            theme.key_desc(),
+        ),
+    ]));
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
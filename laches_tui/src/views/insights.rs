@@ -107,19 +107,26 @@ fn render_details(app: &App, frame: &mut Frame, area: Rect, theme: &Theme) {
     let ins = &app.insights;
     let mut lines: Vec<Line> = Vec::new();
 
-    let streak_str = if ins.streak > 0 {
-        format!(
-            "{} day{}",
-            ins.streak,
-            if ins.streak == 1 { "" } else { "s" }
-        )
-    } else {
-        "none".to_string()
-    };
-    lines.push(Line::from(vec![
-        Span::styled("  streak        ", theme.key_hint()),
-        Span::styled(streak_str, Style::default().fg(theme.accent)),
-    ]));
+    if app.goals.is_empty() {
+        lines.push(Line::from(vec![
+            Span::styled("  streak        ", theme.key_hint()),
+            Span::styled("no goals set", theme.key_desc()),
+        ]));
+    }
+    for goal in &app.goals {
+        let days = |n: u32| format!("{} day{}", n, if n == 1 { "" } else { "s" });
+        lines.push(Line::from(vec![
+            Span::styled("  streak        ", theme.key_hint()),
+            Span::styled(
+                format!("{:<12}", days(goal.streak)),
+                Style::default().fg(theme.accent),
+            ),
+            Span::styled(
+                format!("{} (best {})", goal.goal, days(goal.best_streak)),
+                theme.key_desc(),
+            ),
+        ]));
+    }
 
     lines.push(Line::from(""));
 
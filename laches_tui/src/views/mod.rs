@@ -1,3 +1,5 @@
+pub mod goals;
+pub mod heatmap;
 pub mod insights;
 pub mod sessions;
 pub mod timeline;
@@ -24,12 +24,12 @@ pub fn render(app: &App, frame: &mut Frame, area: Rect, theme: &Theme) {
         .skip(scroll)
         .take(max_visible)
         .map(|s| {
-            let start = s.start_time.get(11..16).unwrap_or("?");
+            let start = laches::utils::local_hm(&s.start_time).unwrap_or_else(|| "?".into());
             let end = s
                 .end_time
-                .as_ref()
-                .and_then(|e| e.get(11..16))
-                .unwrap_or("now");
+                .as_deref()
+                .and_then(laches::utils::local_hm)
+                .unwrap_or_else(|| "now".into());
 
             let duration = if let Some(ref et) = s.end_time {
                 match laches::utils::session_duration_secs(&s.start_time, et) {
@@ -6,7 +6,7 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph},
 };
 
-use laches::db::TIMESTAMP_FORMAT;
+use laches::db::local_time;
 
 struct TimelineEntry {
     process_name: String,
@@ -32,17 +32,24 @@ pub fn render(app: &App, frame: &mut Frame, area: Rect, theme: &Theme) {
             .unwrap()
     };
 
+    // sessions crossing midnight are clipped to the viewed day
+    let day_start = app.viewing_date.and_hms_opt(0, 0, 0).unwrap();
+
     let entries: Vec<TimelineEntry> = sessions
         .iter()
         .rev()
         .filter_map(|s| {
-            let start =
-                chrono::NaiveDateTime::parse_from_str(&s.start_time, TIMESTAMP_FORMAT).ok()?;
+            let start = local_time(&s.start_time)?.naive_local().max(day_start);
             let end = s
                 .end_time
                 .as_ref()
-                .and_then(|e| chrono::NaiveDateTime::parse_from_str(e, TIMESTAMP_FORMAT).ok())
-                .unwrap_or(now);
+                .and_then(|e| local_time(e))
+                .map(|e| e.naive_local())
+                .unwrap_or(now)
+                .min(now);
+            if end <= start {
+                return None;
+            }
             Some(TimelineEntry {
                 process_name: s.process_name.clone(),
                 start,
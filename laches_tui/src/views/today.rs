@@ -17,8 +17,12 @@ pub fn render(app: &App, frame: &mut Frame, area: Rect, theme: &Theme) {
 
     render_header(app, frame, chunks[0], theme);
 
-    if app.group_by_tag && !app.tag_groups.is_empty() {
+    if let Some(process) = &app.drill {
+        render_titles(app, process, frame, chunks[1], theme);
+    } else if app.group_by_tag && !app.tag_groups.is_empty() {
         render_tag_groups(app, frame, chunks[1], theme);
+    } else if app.group_by_category && !app.category_tree.is_empty() {
+        render_category_tree(app, frame, chunks[1], theme);
     } else if app.summaries.is_empty() {
         super::render_empty(app, frame, chunks[1], theme, "top processes");
     } else {
@@ -38,7 +42,25 @@ fn render_header(app: &App, frame: &mut Frame, area: Rect, theme: &Theme) {
     } else {
         String::new()
     };
-    let status = if let Some(ref p) = app.current_process {
+    let paused = if app.paused_secs > 0 {
+        format!(
+            "  paused: {}",
+            laches::utils::format_duration_hm(app.paused_secs)
+        )
+    } else {
+        String::new()
+    };
+    let status = if app.daemon_paused {
+        let until = app
+            .paused_until
+            .as_ref()
+            .map(|t| format!(" until {}", t))
+            .unwrap_or_default();
+        Span::styled(
+            format!("  |  \u{2016} tracking paused{}", until),
+            theme.key_desc(),
+        )
+    } else if let Some(ref p) = app.current_process {
         let title_suffix = app
             .current_window_title
             .as_ref()
@@ -57,6 +79,15 @@ fn render_header(app: &App, frame: &mut Frame, area: Rect, theme: &Theme) {
         Span::styled("  |  \u{25cb} daemon stopped", theme.error_text())
     };
 
+    let focus = match app.focus {
+        Some((left_secs, score)) => format!(
+            "  |  focus: {} left{}",
+            laches::utils::format_duration_hm(left_secs),
+            score.map(|p| format!(", {}%", p)).unwrap_or_default()
+        ),
+        None => String::new(),
+    };
+
     let title = if app.is_viewing_today() {
         " today ".to_string()
     } else {
@@ -66,7 +97,9 @@ fn render_header(app: &App, frame: &mut Frame, area: Rect, theme: &Theme) {
     let header = Paragraph::new(Line::from(vec![
         Span::styled(format!(" active: {}", active), theme.header_active()),
         Span::styled(idle, theme.key_desc()),
+        Span::styled(paused, theme.key_desc()),
         status,
+        Span::styled(focus, theme.header_active()),
     ]))
     .block(Block::default().borders(Borders::ALL).title(title));
     frame.render_widget(header, area);
@@ -80,7 +113,7 @@ fn render_process_list(app: &App, frame: &mut Frame, area: Rect, theme: &Theme)
     }
 
     let total_items = app.summaries.len();
-    let scroll = app.scroll_offsets[0].min(total_items.saturating_sub(inner_height));
+    let (selected, scroll) = selection_window(app.scroll_offsets[0], total_items, inner_height);
 
     let total_secs: i64 = app.summaries.iter().map(|s| s.total_seconds).sum();
     let max_secs = app
@@ -120,9 +153,15 @@ fn render_process_list(app: &App, frame: &mut Frame, area: Rect, theme: &Theme)
             0
         };
 
+        let name_style = if rank - 1 == selected {
+            theme.selected_row()
+        } else {
+            Style::default()
+        };
+
         lines.push(Line::from(vec![
             Span::styled(format!(" {:>2}. ", rank), theme.rank_style()),
-            Span::raw(padded_name),
+            Span::styled(padded_name, name_style),
             Span::raw(" "),
             Span::styled(bar_filled, Style::default().fg(theme.bar_filled)),
             Span::styled(bar_empty, Style::default().fg(theme.bar_empty)),
@@ -146,20 +185,105 @@ fn render_process_list(app: &App, frame: &mut Frame, area: Rect, theme: &Theme)
     let para = Paragraph::new(lines).block(block);
     frame.render_widget(para, area);
 
-    if total_items > inner_height {
-        let mut scrollbar_state =
-            ScrollbarState::new(total_items.saturating_sub(inner_height)).position(scroll);
-        frame.render_stateful_widget(
-            Scrollbar::new(ScrollbarOrientation::VerticalRight)
-                .begin_symbol(None)
-                .end_symbol(None),
-            area.inner(Margin {
-                vertical: 1,
-                horizontal: 0,
-            }),
-            &mut scrollbar_state,
-        );
+    render_scrollbar(frame, area, total_items, inner_height, scroll);
+}
+
+/// The drilled-into process's time on the viewed day by window title.
+fn render_titles(app: &App, process: &str, frame: &mut Frame, area: Rect, theme: &Theme) {
+    let title = format!(" {} by title \u{2500} esc to go back ", process);
+    let block = Block::default().borders(Borders::ALL).title(title);
+
+    if app.title_groups.is_empty() {
+        let lines = vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                "  No window titles recorded for this process on this day.",
+                theme.empty_text(),
+            )),
+        ];
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+        return;
+    }
+
+    let inner_height = area.height.saturating_sub(2) as usize;
+    let inner_width = area.width.saturating_sub(2) as usize;
+    if inner_height == 0 || inner_width == 0 {
+        return;
+    }
+
+    let total_items = app.title_groups.len();
+    let (selected, scroll) = selection_window(app.drill_scroll, total_items, inner_height);
+    let total_secs: i64 = app.title_groups.iter().map(|g| g.total_seconds).sum();
+    let max_secs = app.title_groups[0].total_seconds.max(1);
+
+    let name_width = 40.min(inner_width.saturating_sub(30));
+    let bar_width = inner_width.saturating_sub(22 + name_width).clamp(4, 30);
+
+    let mut lines: Vec<Line> = Vec::with_capacity(inner_height);
+    for (i, g) in app
+        .title_groups
+        .iter()
+        .skip(scroll)
+        .take(inner_height)
+        .enumerate()
+    {
+        let name = laches::utils::truncate_str(&g.name, name_width);
+        let filled =
+            ((g.total_seconds as f64 / max_secs as f64) * bar_width as f64).round() as usize;
+        let pct = (g.total_seconds as f64 / total_secs.max(1) as f64 * 100.0).round() as u32;
+        let name_style = if scroll + i == selected {
+            theme.selected_row()
+        } else {
+            Style::default()
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {:>2}. ", scroll + i + 1), theme.rank_style()),
+            Span::styled(format!("{:<width$}", name, width = name_width), name_style),
+            Span::raw(" "),
+            Span::styled(
+                "\u{2588}".repeat(filled),
+                Style::default().fg(theme.bar_filled),
+            ),
+            Span::styled(
+                "\u{2591}".repeat(bar_width.saturating_sub(filled)),
+                Style::default().fg(theme.bar_empty),
+            ),
+            Span::raw(format!(
+                " {:>8} ",
+                laches::utils::format_duration_hm(g.total_seconds)
+            )),
+            Span::styled(format!("{:>3}%", pct), theme.pct_style()),
+        ]));
     }
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+    render_scrollbar(frame, area, total_items, inner_height, scroll);
+}
+
+/// Clamp a list's `selected` row to its `total` items and pick the first
+/// visible row so the selection stays in view. Returns (selected, scroll).
+fn selection_window(selected: usize, total: usize, height: usize) -> (usize, usize) {
+    let selected = selected.min(total.saturating_sub(1));
+    (selected, selected.saturating_sub(height.saturating_sub(1)))
+}
+
+/// Draw a scrollbar inside `area`'s border when the list doesn't fit.
+fn render_scrollbar(frame: &mut Frame, area: Rect, total: usize, height: usize, scroll: usize) {
+    if total <= height {
+        return;
+    }
+    let mut scrollbar_state = ScrollbarState::new(total.saturating_sub(height)).position(scroll);
+    frame.render_stateful_widget(
+        Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None),
+        area.inner(Margin {
+            vertical: 1,
+            horizontal: 0,
+        }),
+        &mut scrollbar_state,
+    );
 }
 
 fn render_tag_groups(app: &App, frame: &mut Frame, area: Rect, theme: &Theme) {
@@ -179,7 +303,9 @@ fn render_tag_groups(app: &App, frame: &mut Frame, area: Rect, theme: &Theme) {
         .max()
         .unwrap_or(1)
         .max(1);
-    let total_secs: i64 = app.tag_groups.iter().map(|g| g.total_seconds).sum();
+    // a session with several tags shows under each of them, so shares are
+    // of the day's total rather than of the groups added up
+    let total_secs: i64 = app.summaries.iter().map(|s| s.total_seconds).sum();
 
     let name_width = 20.min(inner_width.saturating_sub(22));
     let bar_width = inner_width.saturating_sub(22 + name_width).max(4);
@@ -227,20 +353,80 @@ fn render_tag_groups(app: &App, frame: &mut Frame, area: Rect, theme: &Theme) {
     let para = Paragraph::new(lines).block(block);
     frame.render_widget(para, area);
 
-    if total_items > inner_height {
-        let mut scrollbar_state =
-            ScrollbarState::new(total_items.saturating_sub(inner_height)).position(scroll);
-        frame.render_stateful_widget(
-            Scrollbar::new(ScrollbarOrientation::VerticalRight)
-                .begin_symbol(None)
-                .end_symbol(None),
-            area.inner(Margin {
-                vertical: 1,
-                horizontal: 0,
-            }),
-            &mut scrollbar_state,
-        );
+    render_scrollbar(frame, area, total_items, inner_height, scroll);
+}
+
+fn render_category_tree(app: &App, frame: &mut Frame, area: Rect, theme: &Theme) {
+    let inner_height = area.height.saturating_sub(2) as usize;
+    let inner_width = area.width.saturating_sub(2) as usize;
+    if inner_height == 0 || inner_width == 0 {
+        return;
     }
+
+    let rows = app.category_rows();
+    let total_items = rows.len();
+    let scroll = app.scroll_offsets[0].min(total_items.saturating_sub(inner_height));
+
+    // every session sits in one category, so the top level adds up exactly
+    let total_secs: i64 = app.category_tree.iter().map(|n| n.total_seconds).sum();
+    let max_secs = app
+        .category_tree
+        .iter()
+        .map(|n| n.total_seconds)
+        .max()
+        .unwrap_or(1)
+        .max(1);
+
+    let name_width = 24.min(inner_width.saturating_sub(22));
+    let bar_width = inner_width.saturating_sub(18 + name_width).max(4);
+
+    let mut lines: Vec<Line> = Vec::with_capacity(inner_height);
+
+    for row in rows.iter().skip(scroll).take(inner_height) {
+        let indent = "  ".repeat(row.depth);
+        let label = if row.is_process {
+            format!("{}{}", indent, row.label)
+        } else {
+            format!("{}{}/", indent, row.label)
+        };
+        let name = laches::utils::truncate_str(&label, name_width);
+        let padded_name = format!(" {:<width$}", name, width = name_width);
+
+        let filled =
+            ((row.total_seconds as f64 / max_secs as f64) * bar_width as f64).round() as usize;
+        let empty = bar_width.saturating_sub(filled);
+        let bar_filled = "\u{2588}".repeat(filled);
+        let bar_empty = "\u{2591}".repeat(empty);
+
+        let duration = laches::utils::format_duration_hm(row.total_seconds);
+        let pct = if total_secs > 0 {
+            (row.total_seconds as f64 / total_secs as f64 * 100.0).round() as u32
+        } else {
+            0
+        };
+
+        let name_style = if row.is_process {
+            theme.key_desc()
+        } else {
+            Style::default().fg(theme.accent)
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled(padded_name, name_style),
+            Span::raw(" "),
+            Span::styled(bar_filled, Style::default().fg(theme.bar_filled)),
+            Span::styled(bar_empty, Style::default().fg(theme.bar_empty)),
+            Span::raw(format!(" {:>8} ", duration)),
+            Span::styled(format!("{:>3}%", pct), theme.pct_style()),
+        ]));
+    }
+
+    let title = format!(" by category ({}) ", app.category_tree.len());
+    let block = Block::default().borders(Borders::ALL).title(title);
+    let para = Paragraph::new(lines).block(block);
+    frame.render_widget(para, area);
+
+    render_scrollbar(frame, area, total_items, inner_height, scroll);
 }
 
 fn render_footer(app: &App, frame: &mut Frame, area: Rect, theme: &Theme) {